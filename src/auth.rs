@@ -0,0 +1,217 @@
+// Microsoft account sign in with the OAuth device code flow: the user enters a short code on
+// microsoft.com/link, then the Microsoft token is traded for Xbox Live, XSTS and finally
+// Minecraft tokens. Signed in accounts are kept in accounts.json next to profiles.json.
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::fs;
+use crate::models::Account;
+
+// Azure application the sign in is made for, set when building. Without one only offline play works.
+const CLIENT_ID: Option<&str> = option_env!("RCRAFT_MSA_CLIENT_ID");
+const SCOPE: &str = "XboxLive.signin offline_access";
+
+const DEVICE_CODE_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/devicecode";
+const TOKEN_URL: &str = "https://login.microsoftonline.com/consumers/oauth2/v2.0/token";
+const XBL_AUTH_URL: &str = "https://user.auth.xboxlive.com/user/authenticate";
+const XSTS_AUTH_URL: &str = "https://xsts.auth.xboxlive.com/xsts/authorize";
+const MINECRAFT_LOGIN_URL: &str = "https://api.minecraftservices.com/authentication/login_with_xbox";
+const MINECRAFT_PROFILE_URL: &str = "https://api.minecraftservices.com/minecraft/profile";
+
+const ACCOUNTS_FILE: &str = "accounts.json";
+
+fn client_id() -> Result<&'static str> {
+    CLIENT_ID.ok_or_else(|| anyhow!("This build of RCraft has no Microsoft client id, only offline play is available"))
+}
+
+// What the user is asked to enter, and where
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceCode {
+    pub user_code: String,
+    pub device_code: String,
+    pub verification_uri: String,
+    pub expires_in: u64,
+    pub interval: u64,
+}
+
+#[derive(Deserialize)]
+struct MicrosoftToken {
+    access_token: String,
+    refresh_token: String,
+}
+
+pub async fn request_device_code() -> Result<DeviceCode> {
+    let response = reqwest::Client::new()
+        .post(DEVICE_CODE_URL)
+        .form(&[("client_id", client_id()?), ("scope", SCOPE)])
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("Microsoft sign in is unavailable: {}", response.status()));
+    }
+    Ok(response.json().await?)
+}
+
+// Polls until the user has entered `code` (or it expires), then signs in to Minecraft
+pub async fn complete_device_code(code: &DeviceCode) -> Result<Account> {
+    #[derive(Deserialize)]
+    struct PollError {
+        error: String,
+    }
+
+    let client = reqwest::Client::new();
+    let deadline = Instant::now() + Duration::from_secs(code.expires_in);
+    let mut interval = Duration::from_secs(code.interval.max(1));
+    loop {
+        tokio::time::sleep(interval).await;
+        if Instant::now() >= deadline {
+            return Err(anyhow!("The code expired before it was entered, try again"));
+        }
+        let response = client.post(TOKEN_URL)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                ("client_id", client_id()?),
+                ("device_code", code.device_code.as_str()),
+            ])
+            .send()
+            .await?;
+        if response.status().is_success() {
+            let token: MicrosoftToken = response.json().await?;
+            return sign_in(&client, token).await;
+        }
+        match response.json::<PollError>().await?.error.as_str() {
+            "authorization_pending" => {}
+            "slow_down" => interval += Duration::from_secs(5),
+            "authorization_declined" => return Err(anyhow!("Sign in was declined")),
+            "expired_token" => return Err(anyhow!("The code expired before it was entered, try again")),
+            other => return Err(anyhow!("Sign in failed: {}", other)),
+        }
+    }
+}
+
+// Fresh Minecraft token for an account whose token has expired, from its refresh token
+pub async fn refresh(account: &Account) -> Result<Account> {
+    let client = reqwest::Client::new();
+    let response = client.post(TOKEN_URL)
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("client_id", client_id()?),
+            ("refresh_token", account.refresh_token.as_str()),
+            ("scope", SCOPE),
+        ])
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(anyhow!("{} has to sign in again, remove and add the account in settings", account.username));
+    }
+    sign_in(&client, response.json().await?).await
+}
+
+async fn sign_in(client: &reqwest::Client, microsoft: MicrosoftToken) -> Result<Account> {
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct XboxToken {
+        token: String,
+        display_claims: DisplayClaims,
+    }
+    #[derive(Deserialize)]
+    struct DisplayClaims {
+        xui: Vec<UserHash>,
+    }
+    #[derive(Deserialize)]
+    struct UserHash {
+        uhs: String,
+    }
+    #[derive(Deserialize)]
+    struct XstsError {
+        #[serde(rename = "XErr")]
+        xerr: u64,
+    }
+    #[derive(Deserialize)]
+    struct MinecraftToken {
+        access_token: String,
+        expires_in: u64,
+    }
+    #[derive(Deserialize)]
+    struct MinecraftProfile {
+        id: String,
+        name: String,
+    }
+
+    let xbl: XboxToken = client.post(XBL_AUTH_URL)
+        .json(&json!({
+            "Properties": {
+                "AuthMethod": "RPS",
+                "SiteName": "user.auth.xboxlive.com",
+                "RpsTicket": format!("d={}", microsoft.access_token),
+            },
+            "RelyingParty": "http://auth.xboxlive.com",
+            "TokenType": "JWT",
+        }))
+        .send().await?
+        .error_for_status()?
+        .json().await?;
+
+    let response = client.post(XSTS_AUTH_URL)
+        .json(&json!({
+            "Properties": { "SandboxId": "RETAIL", "UserTokens": [xbl.token] },
+            "RelyingParty": "rp://api.minecraftservices.com/",
+            "TokenType": "JWT",
+        }))
+        .send().await?;
+    if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+        let xerr = response.json::<XstsError>().await.map(|e| e.xerr).unwrap_or(0);
+        return Err(anyhow!(xsts_error_message(xerr)));
+    }
+    let xsts: XboxToken = response.error_for_status()?.json().await?;
+    let user_hash = &xsts.display_claims.xui.first().ok_or_else(|| anyhow!("Xbox Live returned no user hash"))?.uhs;
+
+    let minecraft: MinecraftToken = client.post(MINECRAFT_LOGIN_URL)
+        .json(&json!({ "identityToken": format!("XBL3.0 x={};{}", user_hash, xsts.token) }))
+        .send().await?
+        .error_for_status()?
+        .json().await?;
+
+    let response = client.get(MINECRAFT_PROFILE_URL).bearer_auth(&minecraft.access_token).send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(anyhow!("This Microsoft account doesn't own Minecraft: Java Edition"));
+    }
+    let profile: MinecraftProfile = response.error_for_status()?.json().await?;
+
+    Ok(Account {
+        username: profile.name,
+        uuid: profile.id,
+        access_token: minecraft.access_token,
+        refresh_token: microsoft.refresh_token,
+        expires_at: crate::utils::unix_now() + minecraft.expires_in,
+    })
+}
+
+// XSTS refuses some accounts with an XErr code instead of a token
+fn xsts_error_message(xerr: u64) -> String {
+    match xerr {
+        2148916233 => "This Microsoft account has no Xbox profile yet, sign in once on xbox.com first".to_string(),
+        2148916235 => "Xbox Live isn't available in this account's country".to_string(),
+        2148916236 | 2148916237 => "This account needs adult verification on xbox.com first".to_string(),
+        2148916238 => "Child accounts have to be added to a Microsoft family before they can sign in".to_string(),
+        _ => format!("Xbox Live sign in failed (error {})", xerr),
+    }
+}
+
+pub async fn load_accounts(config_dir: &Path) -> Vec<Account> {
+    match fs::read_to_string(config_dir.join(ACCOUNTS_FILE)).await {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+// The file holds access and refresh tokens, so only the user can read it
+pub async fn save_accounts(config_dir: &Path, accounts: &[Account]) -> std::io::Result<()> {
+    fs::create_dir_all(config_dir).await?;
+    let path = config_dir.join(ACCOUNTS_FILE);
+    let json = serde_json::to_vec_pretty(accounts).unwrap_or_default();
+    tokio::task::spawn_blocking(move || crate::utils::write_atomic_private(&path, &json)).await?
+}
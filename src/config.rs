@@ -1,27 +1,185 @@
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+// Small files that used to live in .minecraft and now belong in config_dir
+const CONFIG_FILES: [&str; 2] = ["settings.json", "profiles.json"];
+
+// Portable mode keeps everything (config, game files, runtimes) next to the executable instead
+// of in $HOME. Turned on by --portable or a portable.txt beside the binary.
+const PORTABLE_MARKER: &str = "portable.txt";
+static PORTABLE: AtomicBool = AtomicBool::new(false);
+
+pub fn enable_portable() {
+    PORTABLE.store(true, Ordering::Relaxed);
+}
+
+// Directory of the executable when running portable. Inside an AppImage that's the directory
+// of the .AppImage file, not the read-only mount it runs from.
+fn portable_root() -> Option<PathBuf> {
+    let exe = match std::env::var_os("APPIMAGE") {
+        Some(appimage) => PathBuf::from(appimage),
+        None => std::env::current_exe().ok()?,
+    };
+    let exe_dir = exe.parent()?.to_path_buf();
+    let portable = PORTABLE.load(Ordering::Relaxed) || exe_dir.join(PORTABLE_MARKER).exists();
+    portable.then_some(exe_dir)
+}
+
+// Files in the cache are dropped once they're this old, so it can't grow without bound
+const CACHE_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+// Mod icons, gallery images and saved Modrinth responses. Everything in here can be fetched
+// again, so it's safe to throw away at any time. $XDG_CACHE_HOME/rcraft, or beside the
+// executable when portable.
+pub fn cache_dir() -> PathBuf {
+    match portable_root() {
+        Some(root) => root.join("cache"),
+        None => dirs::cache_dir().unwrap_or_else(std::env::temp_dir).join("rcraft"),
+    }
+}
+
+// Removes cached files older than CACHE_MAX_AGE and returns the bytes freed
+pub fn evict_stale_cache() -> u64 {
+    remove_files_older_than(&cache_dir(), CACHE_MAX_AGE, SystemTime::now())
+}
+
+fn remove_files_older_than(dir: &Path, max_age: Duration, now: SystemTime) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else { return 0 };
+    let mut freed = 0;
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.path().symlink_metadata() else { continue };
+        if meta.is_dir() {
+            freed += remove_files_older_than(&entry.path(), max_age, now);
+            continue;
+        }
+        let age = meta.modified().ok().and_then(|modified| now.duration_since(modified).ok());
+        if age.is_some_and(|age| age > max_age) && std::fs::remove_file(entry.path()).is_ok() {
+            freed += meta.len();
+        }
+    }
+    freed
+}
 
 #[derive(Clone)]
 pub struct LauncherConfig {
+    // settings.json and profiles.json, $XDG_CONFIG_HOME/rcraft. Kept apart from the game data
+    // below so it can be backed up on its own.
+    pub config_dir: PathBuf,
     pub minecraft_dir: PathBuf,
     pub versions_dir: PathBuf,
     pub assets_dir: PathBuf,
     pub libraries_dir: PathBuf,
     pub runtimes_dir: PathBuf,
+    // Root of the per-profile game dirs, can live apart from the shared cache above
+    pub instances_dir: PathBuf,
+    // Re-hash files that are already on disk before trusting them. Shared by all clones so the
+    // settings toggle applies to launches that are already set up. Fresh downloads are always checked.
+    pub verify_integrity: Arc<AtomicBool>,
+    // options.txt copied into an instance that doesn't have one yet
+    pub options_template: Option<PathBuf>,
 }
 
 impl LauncherConfig {
     pub fn new() -> Result<Self> {
-        let minecraft_dir = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?.join(".minecraft");
+        let config_dir = match portable_root() {
+            Some(root) => root.join("config"),
+            None => dirs::config_dir().ok_or_else(|| anyhow::anyhow!("Could not find config directory"))?.join("rcraft"),
+        };
+        let minecraft_dir = Self::default_minecraft_dir()?;
 
         Ok(Self {
+            config_dir,
             versions_dir: minecraft_dir.join("versions"),
             assets_dir: minecraft_dir.join("assets"),
             libraries_dir: minecraft_dir.join("libraries"),
             runtimes_dir: minecraft_dir.join("runtimes"),
+            instances_dir: minecraft_dir.join("instances"),
             minecraft_dir,
+            verify_integrity: Arc::new(AtomicBool::new(true)),
+            options_template: None,
         })
     }
 
+    // .minecraft beside the executable when portable, in $HOME otherwise
+    pub fn default_minecraft_dir() -> Result<PathBuf> {
+        match portable_root() {
+            Some(root) => Ok(root.join(".minecraft")),
+            None => Ok(dirs::home_dir().ok_or_else(|| anyhow::anyhow!("Could not find home directory"))?.join(".minecraft")),
+        }
+    }
+
+    // Points every game data dir at `minecraft_dir`. The instances root follows along, a custom
+    // one has to be applied again afterwards.
+    pub fn set_minecraft_dir(&mut self, minecraft_dir: PathBuf) {
+        self.versions_dir = minecraft_dir.join("versions");
+        self.assets_dir = minecraft_dir.join("assets");
+        self.libraries_dir = minecraft_dir.join("libraries");
+        self.runtimes_dir = minecraft_dir.join("runtimes");
+        self.instances_dir = minecraft_dir.join("instances");
+        self.minecraft_dir = minecraft_dir;
+    }
+
+    // Moves config files left in .minecraft by older versions into config_dir. A file already in
+    // config_dir wins, the old copy is then left alone.
+    pub fn migrate_legacy_config(&self) {
+        for name in CONFIG_FILES {
+            let old = self.minecraft_dir.join(name);
+            let new = self.config_dir.join(name);
+            if !old.exists() || new.exists() {
+                continue;
+            }
+            if let Err(e) = std::fs::create_dir_all(&self.config_dir) {
+                tracing::warn!("Could not create {}: {}", self.config_dir.display(), e);
+                return;
+            }
+            // Rename fails across filesystems, fall back to copying
+            let moved = std::fs::rename(&old, &new)
+                .or_else(|_| std::fs::copy(&old, &new).and_then(|_| std::fs::remove_file(&old)));
+            match moved {
+                Ok(()) => tracing::info!("Moved {} to {}", old.display(), new.display()),
+                Err(e) => tracing::warn!("Could not move {} to {}: {}", old.display(), new.display(), e),
+            }
+        }
+    }
+
+    pub fn default_instances_dir(&self) -> PathBuf {
+        self.minecraft_dir.join("instances")
+    }
 
+    pub fn set_verify_integrity(&self, verify: bool) {
+        self.verify_integrity.store(verify, Ordering::Relaxed);
+    }
+
+    pub fn verify_integrity(&self) -> bool {
+        self.verify_integrity.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_old_cache_files_are_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let icons = dir.path().join("icons");
+        std::fs::create_dir_all(&icons).unwrap();
+        let now = SystemTime::now();
+        let age = |path: &Path, days: u64| {
+            std::fs::write(path, "cached").unwrap();
+            let file = std::fs::File::options().write(true).open(path).unwrap();
+            file.set_modified(now - Duration::from_secs(days * 24 * 60 * 60)).unwrap();
+        };
+        age(&icons.join("old.png"), 40);
+        age(&icons.join("fresh.png"), 2);
+        age(&dir.path().join("old.json"), 31);
+
+        assert_eq!(remove_files_older_than(dir.path(), CACHE_MAX_AGE, now), 12);
+        assert!(icons.join("fresh.png").exists());
+        assert!(!icons.join("old.png").exists());
+        assert!(!dir.path().join("old.json").exists());
+    }
 }
@@ -0,0 +1,132 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::models::Profile;
+
+// How many of the most recent log lines end up in the bundle
+pub const DIAGNOSTIC_LOG_LINES: usize = 200;
+
+// Everything needed to describe what RCraft was doing when it failed.
+// Nothing here is ever uploaded, the bundle is only written to disk.
+pub struct DiagnosticReport {
+    pub error: String,
+    pub log_lines: Vec<String>,
+    pub java_installations: Vec<String>,
+    pub profile: Option<Profile>,
+    // Player names scrubbed from every part of the report, logs included
+    pub usernames: Vec<String>,
+}
+
+// Replaces $HOME with ~ and each of `names` with <redacted> where it stands as a word of its
+// own, so "Setting user: Steve" is scrubbed but "Steven" or "steve_mod.jar" is left alone.
+pub fn scrub(text: &str, home: Option<&str>, names: &[String]) -> String {
+    let mut out = match home.filter(|h| h.len() > 1) {
+        Some(home) => text.replace(home.trim_end_matches('/'), "~"),
+        None => text.to_string(),
+    };
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    for name in names.iter().filter(|n| !n.is_empty()) {
+        let mut scrubbed = String::with_capacity(out.len());
+        let mut rest = out.as_str();
+        while let Some(at) = rest.find(name.as_str()) {
+            let before = rest[..at].chars().next_back();
+            let after = rest[at + name.len()..].chars().next();
+            scrubbed.push_str(&rest[..at]);
+            if before.is_some_and(is_word) || after.is_some_and(is_word) {
+                scrubbed.push_str(name);
+            } else {
+                scrubbed.push_str("<redacted>");
+            }
+            rest = &rest[at + name.len()..];
+        }
+        scrubbed.push_str(rest);
+        out = scrubbed;
+    }
+    out
+}
+
+impl DiagnosticReport {
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("RCraft diagnostics\n");
+        out.push_str("==================\n\n");
+        out.push_str(&format!("RCraft version: {}\n", env!("CARGO_PKG_VERSION")));
+        out.push_str(&format!("OS: {}\n", std::env::consts::OS));
+        out.push_str(&format!("Arch: {}\n", std::env::consts::ARCH));
+        let distro = std::fs::read_to_string("/etc/os-release").ok().and_then(|release| {
+            release.lines()
+                .find(|l| l.starts_with("PRETTY_NAME="))
+                .map(|l| l.trim_start_matches("PRETTY_NAME=").trim_matches('"').to_string())
+        });
+        if let Some(name) = distro {
+            out.push_str(&format!("Distribution: {}\n", name));
+        }
+        out.push_str(&format!("Total memory: {} MB\n", crate::utils::get_total_memory_mb()));
+
+        out.push_str("\n[Error]\n");
+        out.push_str(&self.error);
+        out.push('\n');
+
+        out.push_str("\n[Java]\n");
+        if self.java_installations.is_empty() {
+            out.push_str("No Java installations detected\n");
+        } else {
+            for java in &self.java_installations {
+                out.push_str(java);
+                out.push('\n');
+            }
+        }
+
+        out.push_str("\n[Profile]\n");
+        match &self.profile {
+            Some(profile) => {
+                // Username is personal, don't leak it into something meant to be shared
+                let mut redacted = profile.clone();
+                redacted.username = "<redacted>".to_string();
+                out.push_str(&serde_json::to_string_pretty(&redacted).unwrap_or_default());
+                out.push('\n');
+            }
+            None => out.push_str("No profile involved\n"),
+        }
+
+        out.push_str(&format!("\n[Last {} log lines]\n", self.log_lines.len()));
+        for line in &self.log_lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+
+        // Log lines and paths carry the player name ("Setting user: ...") and the home folder
+        let home = dirs::home_dir().map(|h| h.display().to_string());
+        let mut names = self.usernames.clone();
+        names.extend(std::env::var("USER").ok());
+        scrub(&out, home.as_deref(), &names)
+    }
+
+    pub fn write_to(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let path = dir.join(format!("rcraft-diagnostics-{}.txt", timestamp));
+        std::fs::write(&path, self.render())?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scrubs_names_and_home_from_any_text() {
+        let names = vec!["Steve".to_string()];
+        let log = "[main/INFO]: Setting user: Steve\nLoading /home/alex/.minecraft/mods/steve_tweaks.jar for Steven\n<Steve> hi";
+        assert_eq!(
+            scrub(log, Some("/home/alex/"), &names),
+            "[main/INFO]: Setting user: <redacted>\nLoading ~/.minecraft/mods/steve_tweaks.jar for Steven\n<<redacted>> hi"
+        );
+        assert_eq!(scrub("/ stays", Some("/"), &[]), "/ stays");
+    }
+}
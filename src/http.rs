@@ -0,0 +1,112 @@
+// Retry behaviour shared by the Modrinth client and the launcher's downloads. Set from the
+// advanced settings; a failed request is retried with a doubling delay (1s, 2s, 4s...) capped
+// at the configured maximum. The same settings cap how many game files download at once.
+
+use anyhow::{anyhow, Result};
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+use tokio::fs;
+
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_MAX_BACKOFF_SECS: u64 = 8;
+pub const DEFAULT_DOWNLOAD_CONCURRENCY: usize = 8;
+
+// Downloads whose bytes don't hash to the expected sha1 are fetched again this many times in all
+const CHECKSUM_ATTEMPTS: u32 = 3;
+
+static MAX_RETRIES: AtomicU32 = AtomicU32::new(DEFAULT_MAX_RETRIES);
+static MAX_BACKOFF_SECS: AtomicU64 = AtomicU64::new(DEFAULT_MAX_BACKOFF_SECS);
+static DOWNLOAD_CONCURRENCY: AtomicUsize = AtomicUsize::new(DEFAULT_DOWNLOAD_CONCURRENCY);
+
+pub fn configure(max_retries: u32, max_backoff_secs: u64) {
+    MAX_RETRIES.store(max_retries, Ordering::Relaxed);
+    MAX_BACKOFF_SECS.store(max_backoff_secs.max(1), Ordering::Relaxed);
+}
+
+pub fn set_download_concurrency(concurrency: usize) {
+    DOWNLOAD_CONCURRENCY.store(concurrency.max(1), Ordering::Relaxed);
+}
+
+// Libraries or asset objects fetched at the same time
+pub fn download_concurrency() -> usize {
+    DOWNLOAD_CONCURRENCY.load(Ordering::Relaxed)
+}
+
+// Extra attempts after the first one
+pub fn max_retries() -> u32 {
+    MAX_RETRIES.load(Ordering::Relaxed)
+}
+
+// How long to wait before retry number `attempt` (0 based)
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let secs = 1u64.checked_shl(attempt).unwrap_or(u64::MAX);
+    Duration::from_secs(secs.min(MAX_BACKOFF_SECS.load(Ordering::Relaxed)))
+}
+
+// GET that retries connection errors and 5xx responses. Anything else, including the last
+// failed response, is handed back to the caller to report.
+pub async fn get(url: &str) -> reqwest::Result<reqwest::Response> {
+    let retries = max_retries();
+    let mut attempt = 0;
+    loop {
+        let result = reqwest::get(url).await;
+        let retryable = match &result {
+            Ok(response) => response.status().is_server_error(),
+            Err(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+        };
+        if !retryable || attempt >= retries {
+            return result;
+        }
+        tracing::debug!("Request to {} failed, retrying ({}/{})", url, attempt + 1, retries);
+        tokio::time::sleep(backoff_delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+// Downloads `url` to `path` unless it's already there. With `expected_sha1` the fresh download
+// is always checked, and fetched again on a mismatch (a truncated or corrupted transfer) before
+// giving up; an existing file is only re-hashed when `verify_existing` is set.
+pub async fn download_file(url: &str, path: &Path, expected_sha1: Option<&str>, verify_existing: bool) -> Result<()> {
+    if path.exists() {
+        let Some(expected) = expected_sha1.filter(|_| verify_existing) else {
+            return Ok(());
+        };
+        let p = path.to_path_buf();
+        let actual = tokio::task::spawn_blocking(move || crate::utils::sha1_file(&p)).await??;
+        if actual.eq_ignore_ascii_case(expected) {
+            return Ok(());
+        }
+        tracing::warn!("Checksum mismatch for {:?}, downloading again", path);
+        fs::remove_file(path).await?;
+    }
+
+    let mut attempt = 1;
+    let bytes = loop {
+        tracing::debug!("Downloading {} to {}", url, path.display());
+        let response = get(url).await?;
+        if !response.status().is_success() {
+            tracing::warn!("Download of {} failed: {}", url, response.status());
+            return Err(anyhow!("Failed to download file from {}: {}", url, response.status()));
+        }
+        let bytes = response.bytes().await?;
+        let matches = expected_sha1.is_none_or(|expected| crate::utils::sha1_hex(&bytes).eq_ignore_ascii_case(expected));
+        if matches {
+            break bytes;
+        }
+        if attempt >= CHECKSUM_ATTEMPTS {
+            return Err(anyhow!("Checksum mismatch for {}", path.display()));
+        }
+        tracing::warn!("Checksum mismatch for {}, downloading again ({}/{})", path.display(), attempt, CHECKSUM_ATTEMPTS - 1);
+        attempt += 1;
+    };
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    // Written aside and renamed into place: a cancelled launch must not leave a truncated file
+    // that the exists check above would take for a finished one
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || crate::utils::write_atomic(&path, &bytes)).await??;
+    Ok(())
+}
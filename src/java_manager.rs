@@ -1,16 +1,26 @@
 use anyhow::{anyhow, Result};
 use flate2::read::GzDecoder;
 use reqwest;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::io::Cursor;
 use std::path::{Path, PathBuf};
 use std::process::{Command as StdCommand};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
 use tar::Archive;
+use xz2::read::XzDecoder;
+
+use crate::models::JavaVendor;
 
 #[derive(Deserialize, Debug)]
 struct AdoptiumRelease {
     binaries: Vec<AdoptiumBinary>,
+    // Build string, e.g. "jdk-17.0.9+9"
+    #[serde(default)]
+    release_name: String,
 }
 
 #[derive(Deserialize, Debug)]
@@ -23,24 +33,128 @@ struct AdoptiumPackage {
     link: String,
 }
 
+#[derive(Deserialize, Debug)]
+struct DiscoResponse {
+    result: Vec<DiscoPackage>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DiscoPackage {
+    links: DiscoLinks,
+    // e.g. "17.0.9+9"
+    #[serde(default)]
+    java_version: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct DiscoLinks {
+    pkg_download_redirect: String,
+}
+
+
+// Written into a managed runtime's directory at install time, what update checks compare against
+const RUNTIME_METADATA_FILE: &str = "rcraft-runtime.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RuntimeMetadata {
+    version: u32,
+    vendor: JavaVendor,
+    build: String,
+}
+
+// A managed runtime and, once checked, the newest build its vendor offers
+#[derive(Debug, Clone)]
+pub struct RuntimeStatus {
+    pub version: u32,
+    pub vendor: JavaVendor,
+    // None for runtimes installed before builds were recorded
+    pub installed_build: Option<String>,
+    pub latest_build: Option<String>,
+}
+
+impl RuntimeStatus {
+    pub fn update_available(&self) -> bool {
+        match (&self.installed_build, &self.latest_build) {
+            (Some(installed), Some(latest)) => installed != latest,
+            // Unknown build, a re-download is the only way to know it's current
+            (None, Some(_)) => true,
+            _ => false,
+        }
+    }
+}
 
+// Java binaries already resolved per major version. Only valid for as long as the
+// runtimes dir itself is unchanged (a runtime added or removed bumps its mtime).
+#[derive(Default)]
+struct JavaPathCache {
+    runtimes_mtime: Option<SystemTime>,
+    paths: HashMap<u32, PathBuf>,
+}
 
 #[derive(Clone)]
 pub struct JavaManager {
     runtimes_dir: PathBuf,
+    resolved: Arc<Mutex<JavaPathCache>>,
 }
 
 impl JavaManager {
     pub fn new(runtimes_dir: PathBuf) -> Self {
-        Self { runtimes_dir }
+        Self { runtimes_dir, resolved: Arc::new(Mutex::new(JavaPathCache::default())) }
+    }
+
+    // Where a managed runtime lives, installed or not
+    pub fn runtime_dir(&self, version: u32, vendor: JavaVendor) -> PathBuf {
+        self.runtimes_dir.join(Self::runtime_dir_name(version, vendor))
+    }
+
+    fn runtime_dir_name(version: u32, vendor: JavaVendor) -> String {
+        match vendor.dir_suffix() {
+            Some(suffix) => format!("java-{}-{}", version, suffix),
+            None => format!("java-{}", version),
+        }
+    }
+
+    // Download URL and build string of the latest GA release
+    async fn resolve_download_url(client: &reqwest::Client, version: u32, vendor: JavaVendor) -> Result<(String, String)> {
+        // Adoptium API uses "linux"
+        let api_os = "linux";
+
+        if vendor == JavaVendor::Temurin {
+            let url = format!(
+                "https://api.adoptium.net/v3/assets/feature_releases/{}/ga?architecture={}&heap_size=normal&image_type=jdk&jvm_impl=hotspot&os={}",
+                version, crate::utils::get_arch(), api_os
+            );
+
+            let resp = client.get(&url).send().await?;
+            let releases: Vec<AdoptiumRelease> = resp.json().await?;
+
+            let release = releases.first().ok_or_else(|| anyhow!("No Java runtimes found for version {}", version))?;
+            let binary = release.binaries.first()
+                .ok_or_else(|| anyhow!("No Java runtimes found for version {}", version))?;
+            return Ok((binary.package.link.clone(), release.release_name.clone()));
+        }
+
+        // Everything else goes through the vendor-agnostic Foojay Disco API
+        let url = format!(
+            "https://api.foojay.io/disco/v3.0/packages?version={}&distribution={}&architecture={}&archive_type=tar.gz&archive_type=zip&package_type=jdk&operating_system={}&release_status=ga&latest=available",
+            version, vendor.disco_distribution(), crate::utils::get_arch(), api_os
+        );
+
+        let resp = client.get(&url).send().await?;
+        let packages: DiscoResponse = resp.json().await?;
+
+        packages.result.first()
+            .map(|p| (p.links.pkg_download_redirect.clone(), p.java_version.clone()))
+            .ok_or_else(|| anyhow!("No {} Java runtimes found for version {} on {}", vendor, version, crate::utils::get_arch()))
     }
 
-    pub async fn download_and_install_java<F>(&self, version: u32, on_progress: F) -> Result<PathBuf>
+    // Setting `cancel` stops the download, leaving no partial runtime behind
+    pub async fn download_and_install_java<F>(&self, version: u32, vendor: JavaVendor, cancel: Arc<AtomicBool>, on_progress: F) -> Result<PathBuf>
     where
         F: Fn(f64, String) + Send + Sync + 'static,
     {
         // 1. Check if already installed (ISOLATED: ONLY CHECK RUNTIMES DIR)
-        let target_dir = self.runtimes_dir.join(format!("java-{}", version));
+        let target_dir = self.runtime_dir(version, vendor);
         if target_dir.exists() {
             let java_bin = target_dir.join("bin").join("java");
             if java_bin.exists() {
@@ -48,41 +162,54 @@ impl JavaManager {
             }
         }
 
-        on_progress(0.0, format!("Finding Java {}...", version));
-
-        // 2. Fetch Release Info
-        // Adoptium API uses "linux"
-        let api_os = "linux";
+        self.install_java(version, vendor, cancel, on_progress).await
+    }
 
-        let url = format!(
-            "https://api.adoptium.net/v3/assets/feature_releases/{}/ga?architecture=x64&heap_size=normal&image_type=jdk&jvm_impl=hotspot&os={}",
-            version, api_os
-        );
+    // Replaces an installed runtime with the latest build of the same feature version
+    pub async fn update_java<F>(&self, version: u32, vendor: JavaVendor, cancel: Arc<AtomicBool>, on_progress: F) -> Result<PathBuf>
+    where
+        F: Fn(f64, String) + Send + Sync + 'static,
+    {
+        self.install_java(version, vendor, cancel, on_progress).await
+    }
 
-        let client = reqwest::Client::new();
-        let resp = client.get(&url).send().await?;
-        let releases: Vec<AdoptiumRelease> = resp.json().await?;
+    async fn install_java<F>(&self, version: u32, vendor: JavaVendor, cancel: Arc<AtomicBool>, on_progress: F) -> Result<PathBuf>
+    where
+        F: Fn(f64, String) + Send + Sync + 'static,
+    {
+        let target_dir = self.runtime_dir(version, vendor);
 
-        if releases.is_empty() {
-             return Err(anyhow!("No Java runtimes found for version {}", version));
-        }
+        on_progress(0.0, format!("Finding {} Java {}...", vendor, version));
 
-        let release = &releases[0];
-        let binary = &release.binaries[0];
-        let download_url = &binary.package.link;
+        // 2. Fetch Release Info
+        let client = reqwest::Client::new();
+        let (download_url, build) = Self::resolve_download_url(&client, version, vendor).await?;
+        tracing::info!("Downloading {} Java {} from {}", vendor, version, download_url);
 
         on_progress(0.1, format!("Downloading Java {}...", version));
 
         // 3. Download
-        let response = client.get(download_url).send().await?;
+        let response = client.get(&download_url).send().await?;
+        // Disco redirects to the real file, so use the final URL to detect the archive type
+        let download_url = response.url().to_string();
         let total_size = response.content_length().unwrap_or(0);
 
+        let temp_dir = self.runtimes_dir.join(format!("temp_{}", Self::runtime_dir_name(version, vendor)));
+        let cancelled = || {
+            let _ = fs::remove_dir_all(&temp_dir);
+            tracing::info!("{} Java {} download cancelled", vendor, version);
+            anyhow!("Java download cancelled")
+        };
+
         let mut stream = response.bytes_stream();
         let mut downloaded: u64 = 0;
         let mut chunks = Vec::new();
 
         use futures::StreamExt;
         while let Some(chunk) = stream.next().await {
+            if cancel.load(Ordering::Relaxed) {
+                return Err(cancelled());
+            }
             let chunk = chunk?;
             downloaded += chunk.len() as u64;
             chunks.extend_from_slice(&chunk);
@@ -100,26 +227,33 @@ impl JavaManager {
         // Usually Adoptium returns .zip for Windows and .tar.gz for Linux.
         // We need to handle both or ensure we request tar.gz if possible, BUT Windows doesn't handle tar.gz natively easily?
         // Rust's `flate2`/`tar` can handle it fine.
-        // Let's check if the URL ends in zip.
-
-        let should_use_zip = download_url.ends_with(".zip");
+        // Some vendors ship .tar.xz instead, so pick the decoder from the URL suffix.
 
-        let temp_dir = self.runtimes_dir.join(format!("temp_{}", version));
         if temp_dir.exists() {
             fs::remove_dir_all(&temp_dir)?;
         }
         fs::create_dir_all(&temp_dir)?;
 
-        if should_use_zip {
+        if download_url.ends_with(".zip") {
              let reader = Cursor::new(chunks);
              let mut archive = zip::ZipArchive::new(reader)?;
              archive.extract(&temp_dir)?;
+        } else if download_url.ends_with(".tar.xz") || download_url.ends_with(".txz") {
+             let tar = XzDecoder::new(Cursor::new(chunks));
+             let mut archive = Archive::new(tar);
+             archive.unpack(&temp_dir)?;
         } else {
+             // Default to gzip, it's what Adoptium ships for Linux
              let tar = GzDecoder::new(Cursor::new(chunks));
              let mut archive = Archive::new(tar);
              archive.unpack(&temp_dir)?;
         }
 
+        // Last chance before the installed runtime is replaced
+        if cancel.load(Ordering::Relaxed) {
+            return Err(cancelled());
+        }
+
         // Find the extracted folder
         let mut entries = fs::read_dir(&temp_dir)?;
         let extracted_root = if let Some(entry) = entries.next() {
@@ -155,10 +289,15 @@ impl JavaManager {
             fs::set_permissions(&java_bin, perms)?;
         }
 
+        let metadata = RuntimeMetadata { version, vendor, build };
+        if let Err(e) = fs::write(target_dir.join(RUNTIME_METADATA_FILE), serde_json::to_string_pretty(&metadata)?) {
+            tracing::warn!("Could not record the Java build for {}: {}", target_dir.display(), e);
+        }
+
         Ok(java_bin)
     }
 
-    fn get_java_version(&self, path: &Path) -> Result<u32> {
+    pub fn get_java_version(&self, path: &Path) -> Result<u32> {
         let output = StdCommand::new(path)
             .arg("-version")
             .output()?;
@@ -198,10 +337,26 @@ impl JavaManager {
 
         // Add specific version candidates if requirement is known
         if let Some(ver) = required_version {
-             // Check runtimes directory (managed java)
-             let runtime_java = self.runtimes_dir.join(format!("java-{}", ver)).join("bin").join("java");
-             if runtime_java.exists() {
-                  return Ok(runtime_java);
+             let runtimes_mtime = fs::metadata(&self.runtimes_dir).and_then(|m| m.modified()).ok();
+             {
+                 let mut cache = self.resolved.lock().unwrap();
+                 if cache.runtimes_mtime != runtimes_mtime {
+                     cache.paths.clear();
+                     cache.runtimes_mtime = runtimes_mtime;
+                 }
+                 if let Some(path) = cache.paths.get(&ver).filter(|p| p.exists()) {
+                     return Ok(path.clone());
+                 }
+             }
+
+             // Scanning runs `java -version`, other lookups shouldn't queue behind it
+             if let Some(path) = self.scan_runtimes(ver) {
+                 let mut cache = self.resolved.lock().unwrap();
+                 // Runtimes changed while scanning, the next lookup scans again
+                 if cache.runtimes_mtime == runtimes_mtime {
+                     cache.paths.insert(ver, path.clone());
+                 }
+                 return Ok(path);
              }
         }
 
@@ -212,11 +367,95 @@ impl JavaManager {
         anyhow::bail!("Could not find Java in runtimes directory")
     }
 
-    #[allow(dead_code)]
+    fn scan_runtimes(&self, ver: u32) -> Option<PathBuf> {
+        // Check runtimes directory (managed java)
+        let runtime_java = self.runtimes_dir.join(format!("java-{}", ver)).join("bin").join("java");
+        if runtime_java.exists() && self.satisfies(&runtime_java, ver) {
+             return Some(runtime_java);
+        }
+
+        // Any vendor is fine as long as the major matches (java-17-graalvm, java-17-zulu...)
+        let prefix = format!("java-{}-", ver);
+        fs::read_dir(&self.runtimes_dir).ok()?
+            .flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|entry| entry.path().join("bin").join("java"))
+            .find(|java_bin| java_bin.exists() && self.satisfies(java_bin, ver))
+    }
+
+    // The directory name is only a hint, ask the binary itself. A half-extracted runtime or one
+    // that was swapped by hand would otherwise get picked and fail at launch with a cryptic error.
+    fn satisfies(&self, java_bin: &Path, required: u32) -> bool {
+        self.get_java_version(java_bin).is_ok_and(|found| found == required)
+    }
+
+    // Runtimes RCraft downloaded itself, with the build recorded at install time
+    pub fn managed_runtimes(&self) -> Vec<RuntimeStatus> {
+        let Ok(entries) = fs::read_dir(&self.runtimes_dir) else { return Vec::new() };
+        let mut runtimes: Vec<RuntimeStatus> = entries.flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let (version, vendor) = Self::parse_runtime_dir_name(&name)?;
+                if !entry.path().join("bin").join("java").exists() {
+                    return None;
+                }
+                let metadata = fs::read_to_string(entry.path().join(RUNTIME_METADATA_FILE)).ok()
+                    .and_then(|content| serde_json::from_str::<RuntimeMetadata>(&content).ok());
+                Some(RuntimeStatus {
+                    version,
+                    vendor,
+                    installed_build: metadata.map(|m| m.build).filter(|b| !b.is_empty()),
+                    latest_build: None,
+                })
+            })
+            .collect();
+        runtimes.sort_by_key(|r| (r.version, r.vendor.dir_suffix()));
+        runtimes
+    }
+
+    fn parse_runtime_dir_name(name: &str) -> Option<(u32, JavaVendor)> {
+        let rest = name.strip_prefix("java-")?;
+        let (version, suffix) = match rest.split_once('-') {
+            Some((version, suffix)) => (version, Some(suffix)),
+            None => (rest, None),
+        };
+        let vendor = [JavaVendor::Temurin, JavaVendor::GraalVM, JavaVendor::Zulu].into_iter()
+            .find(|v| v.dir_suffix() == suffix)?;
+        Some((version.parse().ok()?, vendor))
+    }
+
+    // Managed runtimes with the latest GA build of their feature version filled in.
+    // A vendor that can't be reached leaves `latest_build` empty rather than failing the rest.
+    pub async fn check_runtime_updates(&self) -> Vec<RuntimeStatus> {
+        let client = reqwest::Client::new();
+        let mut runtimes = self.managed_runtimes();
+        for runtime in &mut runtimes {
+            match Self::resolve_download_url(&client, runtime.version, runtime.vendor).await {
+                Ok((_, build)) if !build.is_empty() => runtime.latest_build = Some(build),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Could not check {} Java {} for updates: {}", runtime.vendor, runtime.version, e),
+            }
+        }
+        runtimes
+    }
+
     pub fn get_installed_java_versions(&self) -> Vec<String> {
         let mut found_versions = Vec::new();
         let mut seen_paths = std::collections::HashSet::new();
 
+        // 0. Managed runtimes
+        if let Ok(entries) = fs::read_dir(&self.runtimes_dir) {
+            for entry in entries.flatten() {
+                let Ok(path_abs) = std::fs::canonicalize(entry.path().join("bin").join("java")) else { continue };
+                if !seen_paths.insert(path_abs.clone()) {
+                    continue;
+                }
+                if let Ok(ver) = self.get_java_version(&path_abs) {
+                    found_versions.push(format!("Java {} ({}) [managed]", ver, path_abs.display()));
+                }
+            }
+        }
+
         // 1. Check JAVA_HOME environment variable (Cross-platform)
         if let Ok(java_home) = std::env::var("JAVA_HOME") {
             let path = PathBuf::from(java_home).join("bin").join("java");
@@ -1,44 +1,380 @@
 use anyhow::{anyhow, Result};
 
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::{Stdio};
 use tokio::fs;
 use tokio::process::Command as TokioCommand;
 
 use crate::config::LauncherConfig;
-use crate::models::{MinecraftVersion, VersionManifest, VersionJson, AssetIndexFile};
+use crate::models::{Account, Loader, MinecraftVersion, VersionManifest, VersionJson, AssetIndexFile, JavaVendor};
+use crate::version_cache::VersionJsonCache;
 use crate::library_manager::LibraryManager;
 use crate::utils::is_library_allowed;
 use crate::java_manager::JavaManager;
-use tokio::io::AsyncWriteExt;
 use futures::stream::{self, StreamExt};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 
+// How a version expects to find its assets, decided by its asset index:
+// - 1.7.10 and later read the hashed objects through the index from the shared assets root.
+// - 1.6.x ("legacy" index, `virtual: true`) need readable copies under assets/virtual/<index>,
+//   passed as --assetsDir (${game_assets}).
+// - Before 1.6 ("pre-1.6" index, `map_to_resources: true`) the game only looks in
+//   <game_dir>/resources, which becomes a symlink to the readable copies in the shared store.
+// Assets and libraries always stay in the shared store; an instance's game_dir only holds
+// mods, config and saves. --assetsDir must never point into the game_dir.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssetLayout {
+    Objects,
+    Virtual(PathBuf),
+    Resources(PathBuf),
+}
+
+impl AssetLayout {
+    pub fn for_index(index: &AssetIndexFile, index_id: &str, assets_dir: &Path) -> Self {
+        let copies = assets_dir.join("virtual").join(index_id);
+        if index.map_to_resources {
+            AssetLayout::Resources(copies)
+        } else if index.is_virtual {
+            AssetLayout::Virtual(copies)
+        } else {
+            AssetLayout::Objects
+        }
+    }
+
+    // Shared directory that needs a readable copy of every asset, if any
+    pub fn copy_dir(&self) -> Option<&PathBuf> {
+        match self {
+            AssetLayout::Objects => None,
+            AssetLayout::Virtual(dir) | AssetLayout::Resources(dir) => Some(dir),
+        }
+    }
+
+    // Path inside the instance that has to link back to the shared copies
+    pub fn instance_link(&self, game_dir: &Path) -> Option<PathBuf> {
+        match self {
+            AssetLayout::Resources(_) => Some(game_dir.join("resources")),
+            _ => None,
+        }
+    }
+
+    // Value for ${game_assets}: where old versions look for loose asset files
+    pub fn game_assets_dir(&self, assets_dir: &Path) -> PathBuf {
+        self.copy_dir().cloned().unwrap_or_else(|| assets_dir.to_path_buf())
+    }
+}
+
+// A directory under versions/ with a version json, as listed by the installed versions manager
+#[derive(Debug, Clone)]
+pub struct InstalledVersion {
+    pub id: String,
+    // Set for loader installs, the vanilla version they build on
+    pub inherits_from: Option<String>,
+    pub size: u64,
+}
+
+impl InstalledVersion {
+    // The game version it runs, for a loader install that of its parent
+    pub fn game_version(&self) -> &str {
+        self.inherits_from.as_deref().unwrap_or(&self.id)
+    }
+
+    pub fn loader_name(&self) -> Option<&'static str> {
+        self.inherits_from.as_ref()?;
+        Some(if self.id.starts_with("fabric-loader") {
+            "Fabric"
+        } else if self.id.starts_with("quilt-loader") {
+            "Quilt"
+        } else {
+            "Modded"
+        })
+    }
+}
+
+// Who the game runs as. Offline is the profile's username with a fixed uuid and a dummy token,
+// enough for single player and offline-mode servers. Online sessions are the account's, name
+// included, so a renamed account never launches under its old name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum LaunchAuth {
+    #[default]
+    Offline,
+    Online(Account),
+}
+
+impl LaunchAuth {
+    // The profile's username only stands in when there's no account
+    pub fn username<'a>(&'a self, profile_username: &'a str) -> &'a str {
+        match self {
+            LaunchAuth::Offline => profile_username,
+            LaunchAuth::Online(account) => &account.username,
+        }
+    }
+
+    pub fn uuid(&self) -> &str {
+        match self {
+            LaunchAuth::Offline => OFFLINE_UUID,
+            LaunchAuth::Online(account) => &account.uuid,
+        }
+    }
+
+    pub fn access_token(&self) -> &str {
+        match self {
+            LaunchAuth::Offline => "0",
+            LaunchAuth::Online(account) => &account.access_token,
+        }
+    }
+
+    // Pre-1.6 versions take the token and uuid as one `token:<access token>:<uuid>` session
+    pub fn session(&self) -> String {
+        match self {
+            LaunchAuth::Offline => self.access_token().to_string(),
+            LaunchAuth::Online(account) => format!("token:{}:{}", account.access_token, account.uuid),
+        }
+    }
+
+    pub fn user_type(&self) -> &'static str {
+        match self {
+            LaunchAuth::Offline => "legacy",
+            LaunchAuth::Online(_) => "msa",
+        }
+    }
+}
+
+// Everything the game arguments are built from
+pub struct GameArgsContext<'a> {
+    pub username: &'a str,
+    pub auth: &'a LaunchAuth,
+    pub version: &'a str,
+    pub game_dir: &'a Path,
+    // Shared assets store, never inside game_dir
+    pub assets_dir: &'a Path,
+    pub asset_layout: &'a AssetLayout,
+    pub asset_index_id: Option<&'a str>,
+}
+
+// Game arguments (after the main class). Pre-1.13 versions bring their own template
+// in `minecraftArguments`, newer ones get the fixed modern set.
+pub fn game_arguments(ctx: &GameArgsContext, minecraft_arguments: Option<&str>) -> Vec<String> {
+    let game_assets = ctx.asset_layout.game_assets_dir(ctx.assets_dir).display().to_string();
+
+    if let Some(template) = minecraft_arguments {
+        let mut vars = HashMap::new();
+        vars.insert("auth_player_name", ctx.username.to_string());
+        vars.insert("version_name", ctx.version.to_string());
+        vars.insert("game_directory", ctx.game_dir.display().to_string());
+        vars.insert("assets_root", ctx.assets_dir.display().to_string());
+        vars.insert("game_assets", game_assets);
+        vars.insert("assets_index_name", ctx.asset_index_id.unwrap_or("legacy").to_string());
+        vars.insert("auth_uuid", ctx.auth.uuid().to_string());
+        vars.insert("auth_access_token", ctx.auth.access_token().to_string());
+        vars.insert("auth_session", ctx.auth.session());
+        vars.insert("user_properties", "{}".to_string());
+        vars.insert("user_type", ctx.auth.user_type().to_string());
+        vars.insert("version_type", "release".to_string());
+        return substitute_minecraft_arguments(template, &vars);
+    }
+
+    let mut args = vec![
+        "--username".to_string(), ctx.username.to_string(),
+        "--version".to_string(), ctx.version.to_string(),
+        "--gameDir".to_string(), ctx.game_dir.display().to_string(),
+        "--assetsDir".to_string(), game_assets,
+    ];
+    if let Some(id) = ctx.asset_index_id {
+        args.push("--assetIndex".to_string());
+        args.push(id.to_string());
+    }
+    args.extend([
+        "--uuid", ctx.auth.uuid(),
+        "--accessToken", ctx.auth.access_token(),
+        "--userType", ctx.auth.user_type(),
+        "--userProperties", "{}",
+    ].map(String::from));
+    args
+}
+
+// "-Xmx4G" style sizes in MB. Plain numbers are bytes, like the JVM reads them.
+fn parse_heap_size(value: &str) -> Option<u64> {
+    let (digits, unit) = match value.char_indices().last()? {
+        (i, c) if c.is_ascii_alphabetic() => (&value[..i], c.to_ascii_lowercase()),
+        _ => (value, 'b'),
+    };
+    let amount: u64 = digits.parse().ok()?;
+    match unit {
+        'g' => Some(amount * 1024),
+        'm' => Some(amount),
+        'k' => Some(amount / 1024),
+        'b' => Some(amount / (1024 * 1024)),
+        _ => None,
+    }
+}
+
+// User arguments that would replace the classpath, main class or session RCraft sets up
+const REJECTED_JVM_FLAGS: [&str; 5] = ["-cp", "-classpath", "--class-path", "-jar", "--username"];
+
+// The user's arguments without rejected flags. A flag's separate value (`-cp libs.jar`) is
+// dropped along with it, and so is anything else that isn't a flag: the JVM would take the
+// first such word for the main class.
+fn allowed_user_args(user_args: &[String]) -> Vec<String> {
+    let mut allowed = Vec::new();
+    let mut args = user_args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            tracing::warn!("Ignoring JVM argument {}, only flags starting with - are passed on", arg);
+            continue;
+        }
+        let Some(flag) = REJECTED_JVM_FLAGS.iter().find(|f| arg.starts_with(**f)) else {
+            allowed.push(arg.clone());
+            continue;
+        };
+        tracing::warn!("Ignoring JVM argument {}, RCraft sets it itself", arg);
+        if arg == flag {
+            args.next();
+        }
+    }
+    allowed
+}
+
+// JVM arguments before the main class: heap flags followed by the user's own arguments.
+// The profile's RAM gives -Xmx and half of it as -Xms, unless the user set those flags
+// themselves. Only the last of repeated heap flags is kept, that's the one the JVM would use.
+// An -Xms above the resulting -Xmx is lowered to it, the JVM refuses to start otherwise.
+pub fn jvm_arguments(ram_mb: u32, user_args: &[String]) -> Vec<String> {
+    let user_args = allowed_user_args(user_args);
+    let user_xmx = user_args.iter().rev().find_map(|a| a.strip_prefix("-Xmx"));
+    let user_xms = user_args.iter().rev().find_map(|a| a.strip_prefix("-Xms"));
+
+    let xmx = user_xmx.map(str::to_string).unwrap_or_else(|| format!("{}M", ram_mb));
+    let mut xms = user_xms.map(str::to_string).unwrap_or_else(|| format!("{}M", ram_mb / 2));
+
+    let conflicting = matches!((parse_heap_size(&xmx), parse_heap_size(&xms)), (Some(max), Some(min)) if min > max);
+    if conflicting {
+        tracing::warn!("Initial heap -Xms{} is larger than -Xmx{}, lowering it to match", xms, xmx);
+        xms = xmx.clone();
+    }
+
+    let mut args = vec![format!("-Xmx{}", xmx), format!("-Xms{}", xms)];
+    args.extend(user_args.iter().filter(|a| !a.starts_with("-Xmx") && !a.starts_with("-Xms")).cloned());
+    args
+}
+
+// Shell-ready rendering of a launch command for support/scripting, with the access token redacted
+pub fn format_command_line(command: &TokioCommand) -> String {
+    let std_cmd = command.as_std();
+    let mut parts = vec![shell_quote(&std_cmd.get_program().to_string_lossy())];
+    let mut redact_next = false;
+    for arg in std_cmd.get_args() {
+        let arg = arg.to_string_lossy();
+        if redact_next || arg.starts_with("token:") {
+            parts.push("<redacted>".to_string());
+        } else {
+            parts.push(shell_quote(&arg));
+        }
+        redact_next = arg == "--accessToken" || arg == "--session";
+    }
+    parts.join(" ")
+}
+
+fn shell_quote(arg: &str) -> String {
+    let safe = !arg.is_empty() && arg.chars().all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+    if safe {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+// A launch ready to spawn. find_java only hands out a runtime of the required major version,
+// so that's the version the game runs on without asking the binary again.
+pub struct PreparedLaunch {
+    pub command: TokioCommand,
+    pub java_version: u32,
+}
+
+// Puts a version set aside by reinstall_version back in place unless disarmed, also when the
+// reinstall is cancelled mid download and its future dropped
+struct RestoreVersionDir {
+    backup: PathBuf,
+    version_dir: PathBuf,
+    armed: bool,
+}
+
+impl Drop for RestoreVersionDir {
+    fn drop(&mut self) {
+        if !self.armed {
+            return;
+        }
+        let _ = std::fs::remove_dir_all(&self.version_dir);
+        if let Err(e) = std::fs::rename(&self.backup, &self.version_dir) {
+            tracing::error!("Could not restore {}: {}", self.version_dir.display(), e);
+        }
+    }
+}
+
+// Loader metadata (which game versions a loader supports) and the pinned installers that set
+// a loader version up in versions/
+const FABRIC_META_URL: &str = "https://meta.fabricmc.net/v2";
+const FABRIC_INSTALLER_URL: &str = "https://maven.fabricmc.net/net/fabricmc/fabric-installer/1.1.0/fabric-installer-1.1.0.jar";
+const QUILT_META_URL: &str = "https://meta.quiltmc.org/v3";
+const QUILT_INSTALLER_URL: &str = "https://maven.quiltmc.org/repository/release/org/quiltmc/quilt-installer/0.9.2/quilt-installer-0.9.2.jar";
+
+// Offline sessions have no account, old versions still expect a well formed uuid
+const OFFLINE_UUID: &str = "00000000-0000-0000-0000-000000000000";
+
+// Expands a pre-1.13 `minecraftArguments` template into individual arguments.
+// Placeholders without a known value are left untouched, like the vanilla launcher does.
+pub fn substitute_minecraft_arguments(template: &str, vars: &HashMap<&str, String>) -> Vec<String> {
+    template
+        .split_whitespace()
+        .map(|token| {
+            let mut arg = token.to_string();
+            for (key, value) in vars {
+                arg = arg.replace(&format!("${{{}}}", key), value);
+            }
+            arg
+        })
+        .collect()
+}
+
 #[derive(Clone)]
 pub struct MinecraftLauncher {
     pub config: LauncherConfig,
     pub java_manager: JavaManager,
     pub library_manager: LibraryManager,
+    pub version_jsons: VersionJsonCache,
 }
 
 
 impl MinecraftLauncher {
     pub fn new() -> Result<Self> {
-        let config = LauncherConfig::new()?;
+        Ok(Self::with_config(LauncherConfig::new()?))
+    }
+
+    fn with_config(config: LauncherConfig) -> Self {
         let java_manager = JavaManager::new(config.runtimes_dir.clone());
-        let library_manager = LibraryManager::new(config.versions_dir.clone());
-        Ok(Self {
+        let version_jsons = VersionJsonCache::new(config.versions_dir.clone());
+        let library_manager = LibraryManager::new(config.versions_dir.clone(), version_jsons.clone(), config.verify_integrity.clone());
+        Self {
             config,
             java_manager,
             library_manager,
-        })
+            version_jsons,
+        }
+    }
+
+    // Moves the launcher to another game data dir. The managers hold on to paths inside it, so
+    // they're rebuilt too, which also drops whatever they had cached from the old one.
+    pub fn set_minecraft_dir(&mut self, minecraft_dir: PathBuf) {
+        let mut config = self.config.clone();
+        config.set_minecraft_dir(minecraft_dir);
+        *self = Self::with_config(config);
     }
 
     pub async fn get_available_versions(&self) -> Result<Vec<MinecraftVersion>> {
         let url = "https://launchermeta.mojang.com/mc/game/version_manifest.json";
-        let response = reqwest::get(url).await?;
+        let response = crate::http::get(url).await?;
         let manifest: VersionManifest = response.json().await?;
 
         let release_versions: Vec<MinecraftVersion> = manifest
@@ -91,17 +427,16 @@ impl MinecraftLauncher {
              return Ok(8);
         }
 
-        let version_data = fs::read_to_string(&version_file).await?;
-        let version_json: VersionJson = serde_json::from_str(&version_data)?;
+        let version_json = self.version_jsons.load(version).await?;
         
         // Check java_version field
-        if let Some(v) = version_json.java_version {
+        if let Some(v) = &version_json.java_version {
             return Ok(v.major_version);
         }
         
-        if let Some(parent_id) = version_json.inherits_from {
+        if let Some(parent_id) = &version_json.inherits_from {
              // Recursive check
-             return Box::pin(self.get_required_java_version(&parent_id)).await;
+             return Box::pin(self.get_required_java_version(parent_id)).await;
         }
 
         // Fallback heuristic check on the ID itself if it looks like a vanilla version
@@ -136,7 +471,7 @@ impl MinecraftLauncher {
         Ok(8) // Default for older versions without java_version field
     }
 
-    pub async fn prepare_java<F>(&self, version: &str, on_progress: F) -> Result<PathBuf>
+    pub async fn prepare_java<F>(&self, version: &str, vendor: JavaVendor, cancel: Arc<std::sync::atomic::AtomicBool>, on_progress: F) -> Result<PathBuf>
     where F: Fn(f64, String) + Send + Sync + 'static + Clone
     {
         let required_version = self.get_required_java_version(version).await?;
@@ -147,7 +482,7 @@ impl MinecraftLauncher {
         }
         
         // Not found, download
-        let path = self.java_manager.download_and_install_java(required_version, on_progress).await?;
+        let path = self.java_manager.download_and_install_java(required_version, vendor, cancel, on_progress).await?;
         
         Ok(path)
     }
@@ -172,8 +507,7 @@ impl MinecraftLauncher {
                  }
              }
 
-             let version_data = tokio::fs::read_to_string(&version_file).await?;
-             let version_json: VersionJson = serde_json::from_str(&version_data)?;
+             let version_json = self.version_jsons.load(&version).await?;
 
              for lib in &version_json.libraries {
                 let allowed = is_library_allowed(lib, os_name);
@@ -219,8 +553,8 @@ impl MinecraftLauncher {
                 }
              }
 
-             if let Some(parent) = version_json.inherits_from {
-                 current_version_id = Some(parent);
+             if let Some(parent) = &version_json.inherits_from {
+                 current_version_id = Some(parent.clone());
              } else {
                  // Base version (Vanilla) -> jar path
                  let jar_path = version_dir.join(format!("{}.jar", version));
@@ -243,62 +577,51 @@ impl MinecraftLauncher {
         Ok(cp_string)
     }
 
-    async fn download_file(url: &str, path: &Path) -> Result<()> {
-        if path.exists() {
-            return Ok(());
-        }
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).await?;
-        }
-        let response = reqwest::get(url).await?;
-        if !response.status().is_success() {
-             return Err(anyhow!("Failed to download file from {}: {}", url, response.status()));
-        }
-        let bytes = response.bytes().await?;
-        
-        // Ensure parent dir exists again just in case (race condition in parallel)
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).await?;
-        }
-
-        let mut file = fs::File::create(path).await?;
-        file.write_all(&bytes).await?;
-        Ok(())
-    }
-
-    pub async fn prepare_assets<F>(&self, version_json: &VersionJson, on_progress: Option<F>) -> Result<()> 
+    // Downloads the objects of the version's asset index and lays them out the way that version
+    // expects (see AssetLayout). `game_dir` is only written to for pre-1.6 versions.
+    pub async fn prepare_assets<F>(&self, version_json: &VersionJson, game_dir: &Path, on_progress: Option<F>) -> Result<AssetLayout> 
     where F: Fn(f64, String) + Send + Sync + 'static + Clone
     {
+        let mut layout = AssetLayout::Objects;
         if let Some(asset_index) = &version_json.asset_index {
             let indexes_dir = self.config.assets_dir.join("indexes");
             let index_path = indexes_dir.join(format!("{}.json", asset_index.id));
             
-            Self::download_file(&asset_index.url, &index_path).await?;
+            let verify_existing = self.config.verify_integrity();
+            crate::http::download_file(&asset_index.url, &index_path, Some(&asset_index.sha1), verify_existing).await?;
 
             let index_content = fs::read_to_string(&index_path).await?;
             let index: AssetIndexFile = serde_json::from_str(&index_content)?;
 
             let objects_dir = self.config.assets_dir.join("objects");
-            let legacy_virtual_dir = self.config.assets_dir.join("virtual/legacy");
+            layout = AssetLayout::for_index(&index, &asset_index.id, &self.config.assets_dir);
+            let legacy_virtual_dir = layout.copy_dir().cloned().unwrap_or_default();
+            let is_virtual = layout.copy_dir().is_some();
             
-            if index.is_virtual {
+            if is_virtual {
                 fs::create_dir_all(&legacy_virtual_dir).await?;
             }
 
-            // Collect all objects that need processing
-            let mut pending_objects = Vec::new();
-            for (name, object) in index.objects {
-                 // Check if we need to download or copy virtual
-                 let hash_head = &object.hash[0..2];
-                 let object_path = objects_dir.join(hash_head).join(&object.hash);
-                 
-                 let needs_download = !object_path.exists();
-                 let needs_virtual = index.is_virtual && !legacy_virtual_dir.join(&name).exists();
-                 
-                 if needs_download || needs_virtual {
-                     pending_objects.push((name, object, object_path, needs_download, needs_virtual));
-                 }
-            }
+            // Collect all objects that need processing. Thousands of stats, and a hash of each
+            // object when verifying, so off the async workers.
+            let virtual_dir = legacy_virtual_dir.clone();
+            let pending_objects = tokio::task::spawn_blocking(move || {
+                let mut pending_objects = Vec::new();
+                for (name, object) in index.objects {
+                     // Check if we need to download or copy virtual
+                     let hash_head = &object.hash[0..2];
+                     let object_path = objects_dir.join(hash_head).join(&object.hash);
+
+                     let needs_download = !object_path.exists()
+                         || (verify_existing && crate::utils::sha1_file(&object_path).map(|h| h != object.hash).unwrap_or(true));
+                     let needs_virtual = is_virtual && !virtual_dir.join(&name).exists();
+
+                     if needs_download || needs_virtual {
+                         pending_objects.push((name, object, object_path, needs_download, needs_virtual));
+                     }
+                }
+                pending_objects
+            }).await?;
 
             let total_items = pending_objects.len();
             let processed_count = Arc::new(AtomicUsize::new(0));
@@ -319,8 +642,11 @@ impl MinecraftLauncher {
                             if needs_download {
                                  let hash_head = &object.hash[0..2];
                                  let url = format!("https://resources.download.minecraft.net/{}/{}", hash_head, object.hash);
-                                 if let Err(e) = Self::download_file(&url, &object_path).await {
-                                     eprintln!("Failed to download asset {}: {}", name, e);
+                                 if object_path.exists() {
+                                     let _ = fs::remove_file(&object_path).await;
+                                 }
+                                 if let Err(e) = crate::http::download_file(&url, &object_path, Some(&object.hash), false).await {
+                                     tracing::error!("Failed to download asset {}: {}", name, e);
                                      // Continue anyway, don't fail everything for one asset
                                  }
                             }
@@ -336,22 +662,25 @@ impl MinecraftLauncher {
                             }
                             
                             let current = processed_count.fetch_add(1, Ordering::SeqCst) + 1;
-                            if current % 50 == 0 || current == total_items {
-                                 if let Some(cb) = &on_progress {
-                                     let pct = (current as f64 / total_items as f64) * 100.0; // using 0-100 logic or 0-1? usage suggests 0-1
-                                     // Actually existing usage in java_manager seems to be 0.0-1.0
-                                     // But let's check prepare_java usage: 0.1, 0.7... so 0.0-1.0
-                                      cb(current as f64 / total_items as f64, format!("Downloading assets: {}/{}", current, total_items));
-                                 }
+                            if let Some(cb) = &on_progress {
+                                cb(current as f64 / total_items as f64, format!("Downloading assets ({}/{}): {}", current, total_items, name));
                             }
                         }
                     })
-                    .buffer_unordered(20); // Parallel downloads
+                    .buffer_unordered(crate::http::download_concurrency());
 
                 bodies.collect::<Vec<()>>().await;
             }
+
+            // Pre-1.6 reads <game_dir>/resources, point it at the shared copies instead of duplicating them
+            let link = layout.instance_link(game_dir).filter(|l| std::fs::symlink_metadata(l).is_err());
+            if let (Some(link), Some(target)) = (link, layout.copy_dir()) {
+                fs::create_dir_all(game_dir).await?;
+                #[cfg(target_family = "unix")]
+                std::os::unix::fs::symlink(target, &link)?;
+            }
         }
-        Ok(())
+        Ok(layout)
     }
 
     pub async fn ensure_version_ready(&self, version: &str) -> Result<()> {
@@ -367,7 +696,8 @@ impl MinecraftLauncher {
         let version_info = manifest.iter().find(|v| v.id == version);
 
         if let Some(v_info) = version_info {
-             Self::download_file(&v_info.url, &version_file).await?;
+             crate::http::download_file(&v_info.url, &version_file, None, false).await?;
+             self.version_jsons.invalidate(version);
              Ok(())
         } else {
              Err(anyhow!("Version {} not found in manifest", version))
@@ -375,14 +705,15 @@ impl MinecraftLauncher {
     }
 
 
-    pub async fn launch_minecraft(&self, version: &str, username: &str, ram_mb: u32, game_dir: &Path) -> Result<TokioCommand> {
+    // `on_progress` covers 0.4 to 0.95 of the launch: libraries, then assets
+    #[allow(clippy::too_many_arguments)]
+    pub async fn launch_minecraft<F>(&self, version: &str, username: &str, auth: &LaunchAuth, ram_mb: u32, jvm_args: &[String], game_dir: &Path, on_progress: F) -> Result<PreparedLaunch>
+    where F: Fn(f64, String) + Send + Sync + 'static + Clone
+    {
         self.ensure_version_ready(version).await?;
 
         let version_dir = self.config.versions_dir.join(version);
-        let version_file = version_dir.join(format!("{}.json", version));
-
-        let version_data = fs::read_to_string(&version_file).await?;
-        let version_json: VersionJson = serde_json::from_str(&version_data)?;
+        let version_json = self.version_jsons.load(version).await?;
         
         let required_java = self.get_required_java_version(version).await?;
         let java_path = self.java_manager.find_java(Some(required_java))?;
@@ -407,17 +738,24 @@ impl MinecraftLauncher {
              let source_json = if jar_version == version {
                  version_json.clone()
              } else {
-                 let v_dir = self.config.versions_dir.join(jar_version);
-                 let v_file = v_dir.join(format!("{}.json", jar_version));
-                 let d = fs::read_to_string(&v_file).await?;
-                 serde_json::from_str(&d)?
+                 self.version_jsons.load(jar_version).await?
              };
 
              if let Some(downloads) = &source_json.downloads {
                  if let Some(client) = &downloads.client {
-                     Self::download_file(&client.url, &jar_path).await?;
+                     crate::http::download_file(&client.url, &jar_path, Some(&client.sha1), false).await?;
                  }
              }
+        } else if self.config.verify_integrity() {
+             // Known-good fast path is skipped: re-check the jar that is already on disk
+             let source_json = if jar_version == version {
+                 version_json.clone()
+             } else {
+                 self.version_jsons.load(jar_version).await?
+             };
+             if let Some(client) = source_json.downloads.as_ref().and_then(|d| d.client.as_ref()) {
+                 crate::http::download_file(&client.url, &jar_path, Some(&client.sha1), true).await?;
+             }
         }
 
         if !jar_path.exists() {
@@ -434,27 +772,33 @@ impl MinecraftLauncher {
         self.library_manager.check_and_extract_natives(natives_version).await?;
 
         // Check/Download Libraries
-        self.library_manager.check_and_download_libraries(natives_version).await?;
+        let libraries_progress = {
+            let on_progress = on_progress.clone();
+            move |fraction: f64, status: String| on_progress(0.4 + fraction * 0.15, status)
+        };
+        self.library_manager.check_and_download_libraries(natives_version, Some(libraries_progress)).await?;
 
         // Prepare Assets (Download & Virtualize if needed)
-        // For launch_minecraft direct call we don't report progress, maybe todo later
-        self.prepare_assets(&version_json, None::<fn(f64, String)>).await?;
+        let assets_progress = move |fraction: f64, status: String| on_progress(0.55 + fraction * 0.4, status);
+        let asset_layout = self.prepare_assets(&version_json, game_dir, Some(assets_progress)).await?;
 
         let mut main_class = version_json.main_class.clone();
         let mut asset_index_id = version_json.asset_index.as_ref().map(|a| a.id.clone());
+        let mut minecraft_arguments = version_json.minecraft_arguments.clone();
 
         if let Some(parent_id) = &version_json.inherits_from {
-            let parent_dir = self.config.versions_dir.join(parent_id);
-            let parent_file = parent_dir.join(format!("{}.json", parent_id));
+            let parent_file = self.version_jsons.path(parent_id);
             if parent_file.exists() {
-                 let parent_data = fs::read_to_string(&parent_file).await?;
-                 let parent_json: VersionJson = serde_json::from_str(&parent_data)?;
+                 let parent_json = self.version_jsons.load(parent_id).await?;
 
                  if main_class.is_none() {
-                     main_class = parent_json.main_class;
+                     main_class = parent_json.main_class.clone();
                  }
                  if asset_index_id.is_none() {
-                     asset_index_id = parent_json.asset_index.map(|a| a.id);
+                     asset_index_id = parent_json.asset_index.as_ref().map(|a| a.id.clone());
+                 }
+                 if minecraft_arguments.is_none() {
+                     minecraft_arguments = parent_json.minecraft_arguments.clone();
                  }
             }
         }
@@ -464,53 +808,56 @@ impl MinecraftLauncher {
 
         let mut command = TokioCommand::new(java_path);
         command
-            .arg("-Xmx".to_string() + &ram_mb.to_string() + "M")
-            .arg("-Xms".to_string() + &(ram_mb / 2).to_string() + "M")
+            .args(jvm_arguments(ram_mb, jvm_args))
             .arg("-Djava.library.path=".to_string() + &natives_dir.display().to_string())
             .arg("-cp")
             .arg(classpath)
-            .arg(main_class)
-            .arg("--username")
-            .arg(username)
-            .arg("--version")
-            .arg(version)
-            .arg("--gameDir")
-            .arg(game_dir)
-            .arg("--assetsDir")
-            .arg(&self.config.assets_dir);
-
-        if let Some(id) = asset_index_id {
-            command.arg("--assetIndex").arg(id);
-        }
+            .arg(main_class);
+
+        let ctx = GameArgsContext {
+            username: auth.username(username),
+            auth,
+            version,
+            game_dir,
+            assets_dir: &self.config.assets_dir,
+            asset_layout: &asset_layout,
+            asset_index_id: asset_index_id.as_deref(),
+        };
+        command.args(game_arguments(&ctx, minecraft_arguments.as_deref()));
 
         command
-            .arg("--accessToken")
-            .arg("0")
-            .arg("--userProperties")
-            .arg("{}")
             .current_dir(&version_dir)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped());
 
-        Ok(command)
+        Ok(PreparedLaunch { command, java_version: required_java })
     }
 
-    // High Level Launch Orchestration
-    pub async fn prepare_and_launch<F>(
+    // High Level Launch Orchestration. `on_installer_output` gets each line a loader installer
+    // prints, then None once it has finished.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn prepare_and_launch<F, L>(
         &self, 
         base_version: String, 
         username: String, 
+        auth: LaunchAuth,
         ram_mb: u32,
-        is_fabric: bool,
+        jvm_args: Vec<String>,
+        loader: Loader,
         game_dir_override: Option<PathBuf>,
-        on_progress: F
-    ) -> Result<TokioCommand> 
-    where F: Fn(f64, String) + Send + Sync + 'static + Clone
+        on_progress: F,
+        on_installer_output: L
+    ) -> Result<PreparedLaunch> 
+    where F: Fn(f64, String) + Send + Sync + 'static + Clone,
+          L: Fn(String, Option<String>) + Send + Sync
     {
         let mut version_to_launch = base_version.clone();
+        // Every json is read at most once from here on
+        self.version_jsons.clear();
+        tracing::info!("Preparing {} ({}) for launch", base_version, loader.name());
         
-        // 1. Check JAVA FIRST (Before Fabric)
-        // We need Java to install Fabric anyway, and we need to know if we have it to launch.
+        // 1. Check JAVA FIRST (Before the loader)
+        // We need Java to install a loader anyway, and we need to know if we have it to launch.
         // We check against base_version first.
         
         on_progress(0.1, "Verifying Java...".into());
@@ -523,20 +870,27 @@ impl MinecraftLauncher {
             }
         };
 
-        // 2. Handle Fabric
-        if is_fabric {
-             on_progress(0.2, "Checking Fabric...".into());
-             // Check if fabric version already exists for this base version
-             let fabric_installed = self.find_installed_fabric_version(&base_version).await;
+        // 2. Handle the mod loader
+        if loader != Loader::Vanilla {
+             let name = loader.name();
+             on_progress(0.2, format!("Checking {}...", name));
+             // Check if the loader is already installed for this base version
+             let loader_installed = self.find_installed_loader_version(loader, &base_version).await;
              
-             if let Some(fabric_id) = fabric_installed {
-                 version_to_launch = fabric_id;
+             if let Some(loader_version) = loader_installed {
+                 version_to_launch = loader_version;
              } else {
-                 on_progress(0.3, "Installing Fabric...".into());
+                 on_progress(0.3, format!("Installing {}...", name));
                  // Pass the java we found
-                 match self.install_fabric(&base_version, Some(java_p.clone())).await {
+                 let on_output = |line: String| on_installer_output(name.to_string(), Some(line));
+                 let installed = match loader {
+                     Loader::Quilt => self.install_quilt(&base_version, Some(java_p.clone()), on_output).await,
+                     _ => self.install_fabric(&base_version, Some(java_p.clone()), on_output).await,
+                 };
+                 on_installer_output(name.to_string(), None);
+                 match installed {
                     Ok(new_id) => version_to_launch = new_id,
-                    Err(e) => return Err(anyhow!("Failed to install Fabric: {}", e)),
+                    Err(e) => return Err(anyhow!("Failed to install {}: {}", name, e)),
                  }
              }
         }
@@ -555,6 +909,7 @@ impl MinecraftLauncher {
         if !game_dir.exists() {
              let _ = fs::create_dir_all(&game_dir).await;
         }
+        self.seed_options_txt(&game_dir).await;
 
         on_progress(0.4, "Launching Game...".into());
         // 4. Launch
@@ -563,43 +918,208 @@ impl MinecraftLauncher {
         let cmd = self.launch_minecraft(
             &version_to_launch,
             &username,
+            &auth,
             ram_mb,
-            &game_dir
+            &jvm_args,
+            &game_dir,
+            on_progress.clone()
         ).await;
 
         on_progress(1.0, "Game Started".into());
         cmd
     }
 
-    pub async fn find_installed_fabric_version(&self, mc_version: &str) -> Option<String> {
-         if let Ok(mut entries) = tokio::fs::read_dir(&self.config.versions_dir).await {
-            while let Ok(Some(entry)) = entries.next_entry().await {
-                if let Some(name) = entry.file_name().to_str() {
-                    if name.contains("fabric-loader") && name.ends_with(&format!("-{}", mc_version)) {
-                        return Some(name.to_string());
-                    }
-                }
-            }
+    // First launch of an instance: start from the user's options template (keybinds, render
+    // distance...) instead of the game defaults. Never touches an existing options.txt.
+    async fn seed_options_txt(&self, game_dir: &Path) {
+        let Some(template) = &self.config.options_template else { return };
+        let options = game_dir.join("options.txt");
+        if options.exists() {
+            return;
+        }
+        match fs::copy(template, &options).await {
+            Ok(_) => tracing::info!("Seeded {} from {}", options.display(), template.display()),
+            Err(e) => tracing::warn!("Could not copy options template {}: {}", template.display(), e),
         }
-        None
     }
 
+    // Whether a launch can get going without the network: the version json and client jar are
+    // both on disk. Libraries and assets may still be missing.
+    pub fn is_version_downloaded(&self, version: &str) -> bool {
+        let version_dir = self.config.versions_dir.join(version);
+        version_dir.join(format!("{}.json", version)).exists() && version_dir.join(format!("{}.jar", version)).exists()
+    }
 
-    pub async fn install_fabric(&self, mc_version: &str, java_path_buf: Option<PathBuf>) -> Result<String> {
-        // 1. Download Fabric Installer
-        let installer_url = "https://maven.fabricmc.net/net/fabricmc/fabric-installer/1.1.0/fabric-installer-1.1.0.jar";
+    // Versions with a version json in versions_dir, i.e. downloaded at least once
+    pub fn installed_versions(&self) -> std::collections::HashSet<String> {
+        let Ok(entries) = std::fs::read_dir(&self.config.versions_dir) else { return Default::default() };
+        entries.flatten()
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .filter(|name| self.config.versions_dir.join(name).join(format!("{}.json", name)).exists())
+            .collect()
+    }
+
+    // Installed versions with their size on disk, newest game version first and each vanilla
+    // version ahead of the loader installs built on it
+    pub async fn list_installed_versions(&self) -> Vec<InstalledVersion> {
+        let mut versions = Vec::new();
+        for id in self.installed_versions() {
+            let inherits_from = self.version_jsons.load(&id).await.ok().and_then(|json| json.inherits_from.clone());
+            let dir = self.config.versions_dir.join(&id);
+            let size = tokio::task::spawn_blocking(move || crate::utils::dir_size(&dir)).await.unwrap_or(0);
+            versions.push(InstalledVersion { id, inherits_from, size });
+        }
+        versions.sort_by(|a, b| {
+            crate::utils::compare_versions(b.game_version(), a.game_version())
+                .then_with(|| a.inherits_from.is_some().cmp(&b.inherits_from.is_some()))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        versions
+    }
+
+    // Removes the version's directory: json, client jar and natives. Libraries and assets are
+    // shared and stay; a launch of the version downloads it again.
+    pub async fn delete_version(&self, version: &str) -> Result<()> {
+        let version_dir = self.config.versions_dir.join(version);
+        if version.is_empty() || !version_dir.starts_with(&self.config.versions_dir) || version_dir == self.config.versions_dir {
+            return Err(anyhow!("Invalid version {:?}", version));
+        }
+        fs::remove_dir_all(&version_dir).await?;
+        self.version_jsons.invalidate(version);
+        Ok(())
+    }
+
+    // Moves the version's directory aside and downloads it again, dropping the old copy only once
+    // that worked. A failed or cancelled download puts the old one back.
+    pub async fn reinstall_version(&self, version: &str) -> Result<()> {
+        let version_dir = self.config.versions_dir.join(version);
+        if version.is_empty() || !version_dir.starts_with(&self.config.versions_dir) || version_dir == self.config.versions_dir {
+            return Err(anyhow!("Invalid version {:?}", version));
+        }
+        let backup = self.config.versions_dir.join(format!(".{}.reinstall", version));
+        if fs::try_exists(&backup).await? {
+            fs::remove_dir_all(&backup).await?;
+        }
+        fs::rename(&version_dir, &backup).await?;
+        let mut restore = RestoreVersionDir { backup, version_dir, armed: true };
+        self.version_jsons.invalidate(version);
+
+        let result = self.verify_version(version).await;
+        if result.is_ok() {
+            restore.armed = false;
+            let _ = fs::remove_dir_all(&restore.backup).await;
+        }
+        drop(restore);
+        self.version_jsons.invalidate(version);
+        result
+    }
+
+    // Re-hashes the client jar (a loader install checks its parent's) and downloads it again if
+    // it doesn't match, then fetches missing libraries and natives. A vanilla version whose
+    // directory is gone is downloaded from scratch.
+    pub async fn verify_version(&self, version: &str) -> Result<()> {
+        self.version_jsons.clear();
+        self.ensure_version_ready(version).await?;
+        let version_json = self.version_jsons.load(version).await?;
+        let jar_version = version_json.inherits_from.as_deref().unwrap_or(version);
+        if jar_version != version {
+            self.ensure_version_ready(jar_version).await?;
+        }
+
+        let source_json = self.version_jsons.load(jar_version).await?;
+        let client = source_json.downloads.as_ref().and_then(|d| d.client.as_ref())
+            .ok_or_else(|| anyhow!("{} has no client download to check against", jar_version))?;
+        let jar_path = self.config.versions_dir.join(jar_version).join(format!("{}.jar", jar_version));
+        crate::http::download_file(&client.url, &jar_path, Some(&client.sha1), true).await?;
+
+        self.library_manager.check_and_download_libraries(version, None::<fn(f64, String)>).await?;
+        if jar_version != version {
+            self.library_manager.check_and_download_libraries(jar_version, None::<fn(f64, String)>).await?;
+        }
+        self.library_manager.check_and_extract_natives(jar_version).await
+    }
+
+    // The loader's version installed on top of `mc_version`, e.g. fabric-loader-0.15.11-1.20.1
+    pub async fn find_installed_loader_version(&self, loader: Loader, mc_version: &str) -> Option<String> {
+         let prefix = format!("{}-loader", loader.id()?);
+         let suffix = format!("-{}", mc_version);
+         let mut entries = tokio::fs::read_dir(&self.config.versions_dir).await.ok()?;
+         while let Ok(Some(entry)) = entries.next_entry().await {
+             let file_name = entry.file_name();
+             let Some(name) = file_name.to_str() else { continue };
+             if name.starts_with(&prefix) && name.ends_with(&suffix) {
+                 return Some(name.to_string());
+             }
+         }
+         None
+    }
+
+    // Fabric and Quilt meta list the loader builds for a game version, an empty list means the
+    // loader doesn't support it
+    pub async fn loader_available(loader: Loader, mc_version: &str) -> Result<bool> {
+        let meta_url = match loader {
+            Loader::Vanilla => return Ok(true),
+            Loader::Fabric => FABRIC_META_URL,
+            Loader::Quilt => QUILT_META_URL,
+        };
+        let url = format!("{}/versions/loader/{}", meta_url, mc_version);
+        let response = crate::http::get(&url).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(false);
+        }
+        let loaders: Vec<serde_json::Value> = response.error_for_status()?.json().await?;
+        Ok(!loaders.is_empty())
+    }
+
+    // Installers are kept in .minecraft/cache after the first download
+    async fn download_installer(&self, url: &str, file_name: &str) -> Result<PathBuf> {
         let cache_dir = self.config.minecraft_dir.join("cache");
         fs::create_dir_all(&cache_dir).await?;
-        let installer_path = cache_dir.join("fabric-installer.jar");
+        let installer_path = cache_dir.join(file_name);
 
         if !installer_path.exists() {
-            let resp = reqwest::get(installer_url).await?;
+            let resp = crate::http::get(url).await?.error_for_status()?;
             let bytes = resp.bytes().await?.to_vec();
-            use tokio::io::AsyncWriteExt;
-            let mut out = tokio::fs::File::create(&installer_path).await?;
-            out.write_all(&bytes).await?;
+            let path = installer_path.clone();
+            tokio::task::spawn_blocking(move || crate::utils::write_atomic(&path, &bytes)).await??;
+        }
+        Ok(installer_path)
+    }
+
+    // The most recently written version directory of a loader for `mc_version`, i.e. the one
+    // the installer that just ran created
+    async fn newest_loader_version(&self, loader: Loader, mc_version: &str) -> Result<String> {
+        let prefix = format!("{}-loader", loader.id().unwrap_or_default());
+        let mut best_match: Option<String> = None;
+        let mut latest_time = std::time::SystemTime::UNIX_EPOCH;
+
+        let suffix = format!("-{}", mc_version);
+        let mut read_dir = tokio::fs::read_dir(&self.config.versions_dir).await?;
+        while let Some(entry) = read_dir.next_entry().await? {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else { continue };
+            if !name.starts_with(&prefix) || !name.ends_with(&suffix) {
+                continue;
+            }
+            let Ok(modified) = entry.metadata().await.and_then(|m| m.modified()) else { continue };
+            if modified > latest_time {
+                latest_time = modified;
+                best_match = Some(name.to_string());
+            }
         }
 
+        best_match.ok_or_else(|| anyhow!("Could not find installed {} version directory", loader.name()))
+    }
+
+    pub async fn install_fabric<L>(&self, mc_version: &str, java_path_buf: Option<PathBuf>, on_output: L) -> Result<String>
+    where L: Fn(String)
+    {
+        // 1. Download Fabric Installer
+        let installer_path = self.download_installer(FABRIC_INSTALLER_URL, "fabric-installer.jar").await?;
+
         let java_path = if let Some(p) = java_path_buf {
             p
         } else {
@@ -615,40 +1135,276 @@ impl MinecraftLauncher {
             .arg(&self.config.minecraft_dir)
             .arg("-mcversion")
             .arg(mc_version)
-            .arg("-noprofile")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+            .arg("-noprofile");
 
-        let output = command.output().await?;
+        run_installer("Fabric", command, on_output).await?;
 
-        if !output.status.success() {
-            let err = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow!("Fabric installation failed: {}", err));
-        }
+        self.newest_loader_version(Loader::Fabric, mc_version).await
+    }
 
-        let versions_dir = self.config.versions_dir.clone();
-        let mut best_match: Option<String> = None;
-        let mut latest_time = std::time::SystemTime::UNIX_EPOCH;
+    // Same as install_fabric with Quilt's installer, which takes its options differently
+    pub async fn install_quilt<L>(&self, mc_version: &str, java_path_buf: Option<PathBuf>, on_output: L) -> Result<String>
+    where L: Fn(String)
+    {
+        let installer_path = self.download_installer(QUILT_INSTALLER_URL, "quilt-installer.jar").await?;
 
-        let mut read_dir = tokio::fs::read_dir(&versions_dir).await?;
-        while let Some(entry) = read_dir.next_entry().await? {
-            let path = entry.path();
-            if path.is_dir() {
-                if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-                    if name.contains("fabric-loader") && name.ends_with(&format!("-{}", mc_version)) {
-                        if let Ok(metadata) = entry.metadata().await {
-                            if let Ok(modified) = metadata.modified() {
-                                if modified > latest_time {
-                                    latest_time = modified;
-                                    best_match = Some(name.to_string());
-                                }
-                            }
-                        }
+        let java_path = match java_path_buf {
+            Some(p) => p,
+            None => self.java_manager.find_java(None)?,
+        };
+
+        let mut command = TokioCommand::new(java_path);
+        command
+            .arg("-jar")
+            .arg(&installer_path)
+            .arg("install")
+            .arg("client")
+            .arg(mc_version)
+            .arg(format!("--install-dir={}", self.config.minecraft_dir.display()))
+            .arg("--no-profile");
+
+        run_installer("Quilt", command, on_output).await?;
+
+        self.newest_loader_version(Loader::Quilt, mc_version).await
+    }
+}
+
+// A game that was just spawned. It is killed if dropped before `claim`, which happens when the
+// launch is cancelled in the moment between spawning and the game counting as started. Claimed,
+// the game is left alone, so it outlives the launcher when that quits on launch.
+pub struct SpawnedGame(Option<tokio::process::Child>);
+
+impl SpawnedGame {
+    pub fn new(child: tokio::process::Child) -> Self {
+        Self(Some(child))
+    }
+
+    pub fn claim(mut self) -> tokio::process::Child {
+        self.0.take().expect("a game is claimed once")
+    }
+}
+
+impl Drop for SpawnedGame {
+    fn drop(&mut self) {
+        if let Some(child) = &mut self.0 {
+            tracing::info!("Launch cancelled, stopping the game");
+            let _ = child.start_kill();
+        }
+    }
+}
+
+// Runs a loader installer, handing each line of its output to `on_output` as it is printed so
+// a slow install shows what it is doing. Fails with the tail of stderr if the installer does.
+async fn run_installer<L>(name: &str, mut command: TokioCommand, on_output: L) -> Result<()>
+where L: Fn(String)
+{
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    // Cancelling the launch drops this future, the installer goes with it
+    let mut child = command.stdout(Stdio::piped()).stderr(Stdio::piped()).kill_on_drop(true).spawn()?;
+    let mut stdout = child.stdout.take().map(|s| BufReader::new(s).lines());
+    let mut stderr = child.stderr.take().map(|s| BufReader::new(s).lines());
+    let mut error_tail: Vec<String> = Vec::new();
+
+    while stdout.is_some() || stderr.is_some() {
+        tokio::select! {
+            line = async { stdout.as_mut().unwrap().next_line().await }, if stdout.is_some() => match line {
+                Ok(Some(line)) => on_output(line),
+                _ => stdout = None,
+            },
+            line = async { stderr.as_mut().unwrap().next_line().await }, if stderr.is_some() => match line {
+                Ok(Some(line)) => {
+                    if error_tail.len() == 20 {
+                        error_tail.remove(0);
                     }
+                    error_tail.push(line.clone());
+                    on_output(line);
                 }
-            }
+                _ => stderr = None,
+            },
         }
+    }
+
+    let status = child.wait().await?;
+    if !status.success() {
+        let err = error_tail.join("\n");
+        tracing::error!("{} installer exited with {}: {}", name, status, err);
+        return Err(anyhow!("{} installation failed: {}", name, err));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEMPLATE_1_8: &str = "--username ${auth_player_name} --version ${version_name} --gameDir ${game_directory} --assetsDir ${assets_root} --assetIndex ${assets_index_name} --uuid ${auth_uuid} --accessToken ${auth_access_token} --userProperties ${user_properties} --userType ${user_type}";
+
+    fn vars_1_8() -> HashMap<&'static str, String> {
+        let mut vars = HashMap::new();
+        vars.insert("auth_player_name", "Steve".to_string());
+        vars.insert("version_name", "1.8.9".to_string());
+        vars.insert("game_directory", "/home/steve/.minecraft/instances/Steve_1.8.9".to_string());
+        vars.insert("assets_root", "/home/steve/.minecraft/assets".to_string());
+        vars.insert("assets_index_name", "1.8".to_string());
+        vars.insert("auth_uuid", OFFLINE_UUID.to_string());
+        vars.insert("auth_access_token", "0".to_string());
+        vars.insert("user_properties", "{}".to_string());
+        vars.insert("user_type", "legacy".to_string());
+        vars
+    }
+
+    #[test]
+    fn substitutes_1_8_template() {
+        let args = substitute_minecraft_arguments(TEMPLATE_1_8, &vars_1_8());
+        assert_eq!(args, vec![
+            "--username", "Steve",
+            "--version", "1.8.9",
+            "--gameDir", "/home/steve/.minecraft/instances/Steve_1.8.9",
+            "--assetsDir", "/home/steve/.minecraft/assets",
+            "--assetIndex", "1.8",
+            "--uuid", OFFLINE_UUID,
+            "--accessToken", "0",
+            "--userProperties", "{}",
+            "--userType", "legacy",
+        ]);
+    }
+
+    #[test]
+    fn leaves_unknown_placeholders_untouched() {
+        let args = substitute_minecraft_arguments("--tweakClass ${unknown_token} --username ${auth_player_name}", &vars_1_8());
+        assert_eq!(args, vec!["--tweakClass", "${unknown_token}", "--username", "Steve"]);
+    }
+
+    fn instanced_context<'a>(layout: &'a AssetLayout) -> GameArgsContext<'a> {
+        GameArgsContext {
+            username: "Steve",
+            auth: &LaunchAuth::Offline,
+            version: "1.20.1",
+            game_dir: Path::new("/mnt/nvme/instances/Steve_1.20.1"),
+            assets_dir: Path::new("/home/steve/.minecraft/assets"),
+            asset_layout: layout,
+            asset_index_id: Some("5"),
+        }
+    }
+
+    fn arg_after<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+        args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).map(|s| s.as_str())
+    }
+
+    #[test]
+    fn instanced_profile_uses_shared_assets_dir() {
+        let layout = AssetLayout::Objects;
+        let args = game_arguments(&instanced_context(&layout), None);
+        assert_eq!(arg_after(&args, "--gameDir"), Some("/mnt/nvme/instances/Steve_1.20.1"));
+        assert_eq!(arg_after(&args, "--assetsDir"), Some("/home/steve/.minecraft/assets"));
+    }
+
+    #[test]
+    fn online_session_passes_account_credentials() {
+        let layout = AssetLayout::Objects;
+        let auth = LaunchAuth::Online(Account {
+            username: "Alex".to_string(),
+            uuid: "0b3c6a2e9f6d4e0b8f3a1c2d4e5f6a7b".to_string(),
+            access_token: "token".to_string(),
+            ..Default::default()
+        });
+        let ctx = GameArgsContext { auth: &auth, username: auth.username("Steve"), ..instanced_context(&layout) };
+        let args = game_arguments(&ctx, None);
+        // The account's current name, not what the profile was saved with
+        assert_eq!(arg_after(&args, "--username"), Some("Alex"));
+        assert_eq!(arg_after(&args, "--uuid"), Some("0b3c6a2e9f6d4e0b8f3a1c2d4e5f6a7b"));
+        assert_eq!(arg_after(&args, "--accessToken"), Some("token"));
+        assert_eq!(arg_after(&args, "--userType"), Some("msa"));
+
+        let offline = game_arguments(&instanced_context(&layout), None);
+        assert_eq!(arg_after(&offline, "--uuid"), Some(OFFLINE_UUID));
+        assert_eq!(arg_after(&offline, "--accessToken"), Some("0"));
+
+        // Pre-1.6 templates get the combined session after the name
+        let legacy = game_arguments(&ctx, Some("${auth_player_name} ${auth_session}"));
+        assert_eq!(legacy, vec!["Alex", "token:token:0b3c6a2e9f6d4e0b8f3a1c2d4e5f6a7b"]);
+    }
+
+    #[test]
+    fn instanced_legacy_profile_uses_shared_assets_dir() {
+        let assets_dir = Path::new("/home/steve/.minecraft/assets");
+        let game_dir = Path::new("/mnt/nvme/instances/Steve_1.5.2");
+        let index = AssetIndexFile { is_virtual: false, map_to_resources: true, objects: HashMap::new() };
+        let layout = AssetLayout::for_index(&index, "pre-1.6", assets_dir);
+
+        let mut ctx = instanced_context(&layout);
+        ctx.game_dir = game_dir;
+        let args = game_arguments(&ctx, Some("${auth_player_name} ${auth_session} --gameDir ${game_directory} --assetsDir ${game_assets}"));
+
+        let assets_arg = arg_after(&args, "--assetsDir").unwrap();
+        assert!(Path::new(assets_arg).starts_with(assets_dir));
+        assert!(!Path::new(assets_arg).starts_with(game_dir));
+        assert_eq!(layout.instance_link(game_dir), Some(game_dir.join("resources")));
+    }
+
+    #[test]
+    fn command_line_redacts_access_token() {
+        let mut command = TokioCommand::new("/opt/java/bin/java");
+        command.args(["-Xmx4096M", "--gameDir", "/home/steve/My Games", "--accessToken", "secret", "--userProperties", "{}"]);
+        assert_eq!(
+            format_command_line(&command),
+            "/opt/java/bin/java -Xmx4096M --gameDir '/home/steve/My Games' --accessToken <redacted> --userProperties '{}'"
+        );
+
+        let mut legacy = TokioCommand::new("/opt/java/bin/java");
+        legacy.args(["net.minecraft.client.Minecraft", "Alex", "token:secret:0b3c6a2e"]);
+        assert_eq!(format_command_line(&legacy), "/opt/java/bin/java net.minecraft.client.Minecraft Alex <redacted>");
+    }
+
+    #[test]
+    fn keeps_values_with_spaces_as_one_argument() {
+        let mut vars = vars_1_8();
+        vars.insert("game_directory", "/home/steve/My Games/mc".to_string());
+        let args = substitute_minecraft_arguments("--gameDir ${game_directory}", &vars);
+        assert_eq!(args, vec!["--gameDir", "/home/steve/My Games/mc"]);
+    }
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn derives_heap_flags_from_ram() {
+        assert_eq!(jvm_arguments(4096, &[]), strings(&["-Xmx4096M", "-Xms2048M"]));
+    }
+
+    #[test]
+    fn user_heap_flags_replace_derived_ones() {
+        let user = strings(&["-XX:+UseG1GC", "-Xmx6G", "-Xms1G"]);
+        assert_eq!(jvm_arguments(4096, &user), strings(&["-Xmx6G", "-Xms1G", "-XX:+UseG1GC"]));
+    }
+
+    #[test]
+    fn lowers_derived_xms_above_user_xmx() {
+        // Derived -Xms would be 4096M, more than the user's 1G
+        let user = strings(&["-Xmx1G"]);
+        assert_eq!(jvm_arguments(8192, &user), strings(&["-Xmx1G", "-Xms1G"]));
+    }
+
+    #[test]
+    fn last_repeated_heap_flag_wins() {
+        let user = strings(&["-Xmx2G", "-Xms512M", "-Xmx3072m"]);
+        assert_eq!(jvm_arguments(4096, &user), strings(&["-Xmx3072m", "-Xms512M"]));
+    }
+
+    #[test]
+    fn drops_classpath_and_session_overrides() {
+        let user = strings(&["-cp", "evil.jar", "-XX:+UseG1GC", "-classpath=other.jar", "--username", "Notch", "-Dfoo=bar", "net.example.Main", "-Xss2M"]);
+        assert_eq!(jvm_arguments(4096, &user), strings(&["-Xmx4096M", "-Xms2048M", "-XX:+UseG1GC", "-Dfoo=bar", "-Xss2M"]));
+    }
 
-        best_match.ok_or_else(|| anyhow!("Could not find installed Fabric version directory"))
+    #[test]
+    fn parses_heap_sizes() {
+        assert_eq!(parse_heap_size("2G"), Some(2048));
+        assert_eq!(parse_heap_size("512m"), Some(512));
+        assert_eq!(parse_heap_size("1048576k"), Some(1024));
+        assert_eq!(parse_heap_size("1073741824"), Some(1024));
+        assert_eq!(parse_heap_size("lots"), None);
     }
 }
@@ -1,44 +1,157 @@
 use anyhow::{Result};
-use std::path::PathBuf;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
 use tokio::fs;
-use crate::models::{VersionJson};
-use reqwest;
+use crate::version_cache::VersionJsonCache;
 use zip;
+use crate::models::{Library, LibraryArtifact};
+
+// Left in versions/ once the old natives zips have been cleaned up
+const NATIVES_CLEANED_MARKER: &str = ".natives-cleaned";
+
+// The natives jars to extract for `os_name` on a CPU whose classifiers end in `arch_suffix`
+// (natives-linux-arm64, None on x86_64), with `pointer_width` filling ${arch} in old natives
+// maps. Natives built for this CPU win over the plain natives-linux ones, and a plain natives
+// library is left out when the same artifact also ships one for this CPU.
+fn natives_for<'a>(libraries: &'a [Library], os_name: &str, arch_suffix: Option<&str>, pointer_width: &str) -> Vec<(&'a Library, LibraryArtifact)> {
+    let with_arch = |classifier: &str| arch_suffix.map(|suffix| format!("{}-{}", classifier, suffix));
+    let os_classifier = format!("natives-{}", os_name);
+    let arch_classifier = with_arch(&os_classifier);
+    let has_arch_natives: HashSet<&str> = libraries.iter()
+        .filter(|lib| lib.name.split(':').nth(3).is_some_and(|c| Some(c) == arch_classifier.as_deref()))
+        .filter_map(|lib| lib.name.rsplit_once(':').map(|(coords, _)| coords))
+        .collect();
+
+    let mut natives = Vec::new();
+    for lib in libraries {
+        let classifiers = lib.downloads.as_ref().and_then(|d| d.classifiers.as_ref());
+        let find_classifier = |classifier: &str| {
+            let arch_specific = with_arch(classifier).and_then(|c| classifiers?.get(&c));
+            arch_specific.or_else(|| classifiers?.get(classifier)).cloned()
+        };
+
+        // 1. The 'natives' map, old versions put ${arch} (32/64) in it
+        let mut native_artifact = lib.natives.as_ref()
+            .and_then(|n| n.get(os_name))
+            .and_then(|classifier| find_classifier(&classifier.replace("${arch}", pointer_width)));
+
+        // 2. classifiers named natives-{os}
+        if native_artifact.is_none() {
+            native_artifact = find_classifier(&os_classifier);
+        }
+
+        // 3. natives as their own library, org.lwjgl:lwjgl:3.3.1:natives-linux
+        if native_artifact.is_none() {
+            let for_this_cpu = match lib.name.split(':').nth(3) {
+                Some(c) if Some(c) == arch_classifier.as_deref() => true,
+                Some(c) if c == os_classifier => lib.name.rsplit_once(':').is_none_or(|(coords, _)| !has_arch_natives.contains(coords)),
+                _ => false,
+            };
+            if for_this_cpu {
+                native_artifact = lib.downloads.as_ref().and_then(|d| d.artifact.clone());
+            }
+        }
+
+        if let Some(artifact) = native_artifact {
+            natives.push((lib, artifact));
+        }
+    }
+    natives
+}
+
+// Every file a library can resolve to under libraries/, for any OS: the main artifact, its
+// native classifiers and the maven style path used when the json gives no download
+fn library_paths(lib: &Library) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(downloads) = &lib.downloads {
+        if let Some(artifact) = &downloads.artifact {
+            paths.push(PathBuf::from(&artifact.path));
+        }
+        for artifact in downloads.classifiers.iter().flat_map(|c| c.values()) {
+            paths.push(PathBuf::from(&artifact.path));
+        }
+    }
+
+    let parts: Vec<&str> = lib.name.split(':').collect();
+    if parts.len() >= 3 {
+        let group = parts[0].replace('.', "/");
+        let (artifact_id, version) = (parts[1], parts[2]);
+        let dir = format!("{}/{}/{}", group, artifact_id, version);
+        paths.push(PathBuf::from(format!("{}/{}-{}.jar", dir, artifact_id, version)));
+        if let Some(classifier) = parts.get(3) {
+            paths.push(PathBuf::from(format!("{}/{}-{}-{}.jar", dir, artifact_id, version, classifier)));
+        }
+        for classifier in lib.natives.iter().flat_map(|n| n.values()) {
+            paths.push(PathBuf::from(format!("{}/{}-{}-{}.jar", dir, artifact_id, version, classifier)));
+        }
+    }
+    paths
+}
+
+fn jars_under(dir: &Path, jars: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else { return };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else { continue };
+        if file_type.is_dir() {
+            jars_under(&entry.path(), jars);
+        } else if file_type.is_file() && entry.path().extension().is_some_and(|ext| ext == "jar") {
+            jars.push(entry.path());
+        }
+    }
+}
 
 #[derive(Clone)]
 pub struct LibraryManager {
     versions_dir: PathBuf,
     libraries_dir: PathBuf,
+    version_jsons: VersionJsonCache,
+    // LauncherConfig::verify_integrity, re-hash jars already on disk before trusting them
+    verify_integrity: Arc<AtomicBool>,
 }
 
 impl LibraryManager {
-    pub fn new(versions_dir: PathBuf) -> Self {
+    pub fn new(versions_dir: PathBuf, version_jsons: VersionJsonCache, verify_integrity: Arc<AtomicBool>) -> Self {
         let libraries_dir = versions_dir.parent().unwrap().join("libraries");
-        Self { versions_dir, libraries_dir }
+        Self { versions_dir, libraries_dir, version_jsons, verify_integrity }
     }
 
-    pub async fn check_and_download_libraries(&self, version: &str) -> Result<()> {
-        let version_file = self.versions_dir.join(version).join(format!("{}.json", version));
-        if !version_file.exists() {
+    fn verify_existing(&self) -> bool {
+        self.verify_integrity.load(Ordering::Relaxed)
+    }
+
+    // Downloads the version's missing libraries, up to http::download_concurrency() at a time.
+    // With verification on, jars already on disk that have a sha1 are re-hashed and fetched again
+    // if they don't match. `on_progress` gets the fraction of those libraries done so far.
+    pub async fn check_and_download_libraries<F>(&self, version: &str, on_progress: Option<F>) -> Result<()>
+    where F: Fn(f64, String) + Send + Sync
+    {
+        if !self.version_jsons.path(version).exists() {
             return Ok(());
         }
-        let v_data = fs::read_to_string(&version_file).await?;
-        let v_json: VersionJson = serde_json::from_str(&v_data)?;
+        let v_json = self.version_jsons.load(version).await?;
         let os_name = crate::utils::get_os_name();
+        let verify = self.verify_existing();
 
-        for lib in v_json.libraries {
-             if !crate::utils::is_library_allowed(&lib, os_name) {
+        let mut pending = Vec::new();
+        let mut seen_paths = HashSet::new();
+        for lib in &v_json.libraries {
+             if !crate::utils::is_library_allowed(lib, os_name) {
                  continue;
              }
              
              let mut url = String::new();
              let mut path = PathBuf::new();
+             let mut sha1 = None;
              
              // Try explicit artifact
              if let Some(downloads) = &lib.downloads {
                  if let Some(artifact) = &downloads.artifact {
                      url = artifact.url.clone();
                      path = self.libraries_dir.join(&artifact.path);
+                     sha1 = artifact.sha1.clone();
                  }
              }
              
@@ -57,25 +170,172 @@ impl LibraryManager {
                  }
              }
              
-             if !url.is_empty() && !path.as_os_str().is_empty() {
-                 if !path.exists() {
-                     if let Some(parent) = path.parent() {
-                         fs::create_dir_all(parent).await?;
-                     }
-                     
-                     if let Ok(resp) = reqwest::get(&url).await {
-                         if resp.status().is_success() {
-                             if let Ok(bytes) = resp.bytes().await {
-                                 fs::write(&path, &bytes).await?;
-                             }
-                         }
-                     }
-                 }
+             // Two entries naming the same jar must not be written concurrently
+             let needs_check = !path.exists() || (verify && sha1.is_some());
+             if !url.is_empty() && !path.as_os_str().is_empty() && needs_check && seen_paths.insert(path.clone()) {
+                 pending.push((lib.name.clone(), url, path, sha1));
              }
         }
+
+        let total = pending.len();
+        if total == 0 {
+            return Ok(());
+        }
+        if let Some(cb) = &on_progress {
+            let action = if verify { "Checking" } else { "Downloading" };
+            cb(0.0, format!("{} {} libraries...", action, total));
+        }
+
+        let done = Arc::new(AtomicUsize::new(0));
+        let on_progress = &on_progress;
+        stream::iter(pending)
+            .map(|(name, url, path, sha1)| {
+                let done = done.clone();
+                async move {
+                    // A guessed maven URL may just not exist; a listed artifact that fails to
+                    // download or doesn't match its hash would break the launch
+                    match crate::http::download_file(&url, &path, sha1.as_deref(), verify).await {
+                        Ok(()) => {}
+                        Err(e) if sha1.is_some() => return Err(e),
+                        Err(e) => tracing::warn!("Could not download library {}: {}", name, e),
+                    }
+                    let current = done.fetch_add(1, Ordering::SeqCst) + 1;
+                    if let Some(cb) = on_progress {
+                        let file_name = path.file_name().map(|f| f.to_string_lossy().into_owned()).unwrap_or(name);
+                        cb(current as f64 / total as f64, format!("Downloading libraries ({}/{}): {}", current, total, file_name));
+                    }
+                    Ok(())
+                }
+            })
+            .buffer_unordered(crate::http::download_concurrency())
+            .try_collect::<Vec<()>>()
+            .await?;
         Ok(())
     }
 
+    // Throws away a version's natives and the archives they came from, then extracts them again
+    // from fresh downloads. For LWJGL failing to load after a partial or corrupted install.
+    pub async fn repair_natives(&self, natives_version: &str) -> Result<()> {
+        let version_dir = self.versions_dir.join(natives_version);
+        if !version_dir.join(format!("{}.json", natives_version)).exists() {
+            return Err(anyhow::anyhow!("{} hasn't been downloaded yet, launch it once first", natives_version));
+        }
+
+        let natives_dir = version_dir.join("natives");
+        if natives_dir.exists() {
+            fs::remove_dir_all(&natives_dir).await?;
+        }
+        let mut entries = fs::read_dir(&version_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.path().extension().is_some_and(|ext| ext == "zip") {
+                fs::remove_file(entry.path()).await?;
+            }
+        }
+
+        self.check_and_extract_natives(natives_version).await
+    }
+
+    // Natives archives used to be kept next to the version after extraction. Removes the ones
+    // whose natives are in place and returns the bytes freed. Extraction deletes them nowadays,
+    // so this runs once per versions dir and leaves a marker to skip the walk from then on.
+    pub async fn remove_leftover_natives_zips(&self) -> u64 {
+        let marker = self.versions_dir.join(NATIVES_CLEANED_MARKER);
+        if fs::try_exists(&marker).await.unwrap_or(true) {
+            return 0;
+        }
+        let mut freed = 0;
+        let Ok(mut versions) = fs::read_dir(&self.versions_dir).await else { return 0 };
+        while let Ok(Some(version)) = versions.next_entry().await {
+            let natives_dir = version.path().join("natives");
+            let extracted = std::fs::read_dir(&natives_dir).is_ok_and(|mut entries| entries.next().is_some());
+            if !extracted {
+                continue;
+            }
+            let Ok(mut entries) = fs::read_dir(version.path()).await else { continue };
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                if entry.path().extension().is_none_or(|ext| ext != "zip") {
+                    continue;
+                }
+                let size = entry.metadata().await.map(|m| m.len()).unwrap_or(0);
+                if fs::remove_file(entry.path()).await.is_ok() {
+                    freed += size;
+                }
+            }
+        }
+        if let Err(e) = fs::write(&marker, b"").await {
+            tracing::debug!("Could not write {}: {}", marker.display(), e);
+        }
+        freed
+    }
+
+    // Jars under libraries/ that no installed version (or anything it inherits from) names, with
+    // their sizes. Errors rather than guess when a version json can't be read, since its
+    // libraries would otherwise look unused.
+    pub async fn find_orphaned_libraries(&self) -> Result<Vec<(PathBuf, u64)>> {
+        let mut pending: Vec<String> = Vec::new();
+        let mut entries = fs::read_dir(&self.versions_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if let Ok(name) = entry.file_name().into_string() {
+                pending.push(name);
+            }
+        }
+        if pending.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut referenced = HashSet::new();
+        let mut visited = HashSet::new();
+        while let Some(version) = pending.pop() {
+            if !visited.insert(version.clone()) || !self.version_jsons.path(&version).exists() {
+                continue;
+            }
+            let v_json = self.version_jsons.load(&version).await
+                .map_err(|e| anyhow::anyhow!("Could not read {}, nothing was removed: {}", version, e))?;
+            for lib in &v_json.libraries {
+                referenced.extend(library_paths(lib).into_iter().map(|path| self.libraries_dir.join(path)));
+            }
+            if let Some(parent) = &v_json.inherits_from {
+                pending.push(parent.clone());
+            }
+        }
+
+        let libraries_dir = self.libraries_dir.clone();
+        let jars = tokio::task::spawn_blocking(move || {
+            let mut jars = Vec::new();
+            jars_under(&libraries_dir, &mut jars);
+            jars
+        }).await?;
+
+        Ok(jars.into_iter()
+            .filter(|jar| !referenced.contains(jar))
+            .map(|jar| {
+                let size = std::fs::metadata(&jar).map(|m| m.len()).unwrap_or(0);
+                (jar, size)
+            })
+            .collect())
+    }
+
+    // Deletes the given jars, then any directories under libraries/ left empty. Returns the bytes freed.
+    pub async fn remove_libraries(&self, jars: Vec<(PathBuf, u64)>) -> u64 {
+        let mut freed = 0;
+        for (jar, size) in jars {
+            if !jar.starts_with(&self.libraries_dir) {
+                continue;
+            }
+            if fs::remove_file(&jar).await.is_ok() {
+                freed += size;
+            }
+            let mut dir = jar.parent();
+            while let Some(current) = dir.filter(|d| *d != self.libraries_dir.as_path()) {
+                if fs::remove_dir(current).await.is_err() {
+                    break;
+                }
+                dir = current.parent();
+            }
+        }
+        freed
+    }
+
     pub async fn check_and_extract_natives(&self, natives_version: &str) -> Result<()> {
         let natives_dir = self.versions_dir.join(natives_version).join("natives");
         
@@ -86,101 +346,162 @@ impl LibraryManager {
             return Ok(());
         }
 
-        println!("Natives missing for {}, attempting repair...", natives_version);
-        let version_file_native = self.versions_dir.join(natives_version).join(format!("{}.json", natives_version));
-        
-        if !version_file_native.exists() {
+        tracing::warn!("Natives missing for {}, attempting repair...", natives_version);
+        if !self.version_jsons.path(natives_version).exists() {
              return Ok(()); // Can't do anything if json missing
         }
 
-        let v_data = fs::read_to_string(&version_file_native).await?;
-        let v_json: VersionJson = serde_json::from_str(&v_data)?;
-        let os_name = crate::utils::get_os_name();
+        let v_json = self.version_jsons.load(natives_version).await?;
+        let pointer_width = if cfg!(target_pointer_width = "64") { "64" } else { "32" };
+        let natives = natives_for(&v_json.libraries, crate::utils::get_os_name(), crate::utils::natives_arch_suffix(), pointer_width);
 
-        for lib in v_json.libraries {
-            let mut native_artifact = None;
-            
-            // 1. Check strict 'natives' map
-            if let Some(natives) = &lib.natives {
-                 if let Some(classifier) = natives.get(os_name) {
-                     if let Some(downloads) = &lib.downloads {
-                         if let Some(classifiers) = &downloads.classifiers {
-                             if let Some(artifact) = classifiers.get(classifier) {
-                                 native_artifact = Some(artifact.clone());
-                             }
-                         }
-                     }
-                 }
-            }
-            
-            // 2. heuristic: check classifiers for natives-{os}
-            if native_artifact.is_none() {
-                 if let Some(downloads) = &lib.downloads {
-                     if let Some(classifiers) = &downloads.classifiers {
-                         if let Some(artifact) = classifiers.get(&format!("natives-{}", os_name)) {
-                             native_artifact = Some(artifact.clone());
-                         }
-                     }
-                 }
-            }
-            
-            // 3. heuristic: check main artifact path or name
-            if native_artifact.is_none() {
-                 if let Some(downloads) = &lib.downloads {
-                     if let Some(artifact) = &downloads.artifact {
-                         if artifact.path.contains(&format!("natives-{}", os_name)) || lib.name.contains(&format!("natives-{}", os_name)) {
-                             native_artifact = Some(artifact.clone());
-                         }
-                     }
-                 }
-            }
-            
-            if let Some(artifact) = native_artifact {
-                 let native_zip_path = self.versions_dir.join(natives_version).join(format!("{}.zip", lib.name.replace(":", "_")));
-                 
-                 // Download if missing
-                 if !native_zip_path.exists() {
-                    if let Ok(resp) = reqwest::get(&artifact.url).await {
-                        if let Ok(bytes) = resp.bytes().await {
-                             let _ = tokio::fs::write(&native_zip_path, &bytes).await;
-                        }
+        for (lib, artifact) in natives {
+            let native_zip_path = self.versions_dir.join(natives_version).join(format!("{}.zip", lib.name.replace(":", "_")));
+
+            // Download if missing
+            crate::http::download_file(&artifact.url, &native_zip_path, artifact.sha1.as_deref(), self.verify_existing()).await?;
+
+            // Extract
+            if native_zip_path.exists() {
+                let nd = natives_dir.clone();
+                let nzp = native_zip_path.clone();
+                let exclude = lib.get_extract().map(|e| e.exclude.clone()).unwrap_or_default();
+
+                // Spawn blocking for zip extraction
+                let extracted = tokio::task::spawn_blocking(move || -> Result<()> {
+                    let mut archive = zip::ZipArchive::new(std::fs::File::open(&nzp)?)?;
+                    for i in 0..archive.len() {
+                        let mut file = archive.by_index(i)?;
+                        let name = file.name().to_string();
+                        let excluded = exclude.iter().any(|ex| name.starts_with(ex));
+                        if excluded || name.ends_with("/") { continue; }
+
+                        let filename = std::path::Path::new(&name).file_name().and_then(|f| f.to_str()).unwrap_or(&name).to_string();
+                        let outpath = nd.join(&filename);
+
+                        // Create parent dirs
+                        if let Some(parent) = outpath.parent() { std::fs::create_dir_all(parent)?; }
+
+                        let mut outfile = std::fs::File::create(&outpath)?;
+                        std::io::copy(&mut file, &mut outfile)?;
                     }
-                 }
-                 
-                 // Extract
-                 if native_zip_path.exists() {
-                     let nd = natives_dir.clone();
-                     let nzp = native_zip_path.clone();
-                     let exclude = lib.get_extract().map(|e| e.exclude.clone()).unwrap_or_default();
-                     
-                     // Spawn blocking for zip extraction
-                     let _ = tokio::task::spawn_blocking(move || {
-                         if let Ok(file) = std::fs::File::open(&nzp) {
-                             if let Ok(mut archive) = zip::ZipArchive::new(file) {
-                                  for i in 0..archive.len() {
-                                     if let Ok(mut file) = archive.by_index(i) {
-                                          let name = file.name().to_string();
-                                          let excluded = exclude.iter().any(|ex| name.starts_with(ex));
-                                          if excluded || name.ends_with("/") { continue; }
-                                          
-                                          let filename = std::path::Path::new(&name).file_name().and_then(|f| f.to_str()).unwrap_or(&name).to_string();
-                                          let outpath = nd.join(&filename);
-                                          
-                                          // Create parent dirs
-                                          if let Some(parent) = outpath.parent() { let _ = std::fs::create_dir_all(parent); }
-                                          
-                                          if let Ok(mut outfile) = std::fs::File::create(&outpath) {
-                                              let _ = std::io::copy(&mut file, &mut outfile);
-                                          }
-                                     }
-                                  }
-                             }
-                         }
-                     }).await;
-                 }
+                    Ok(())
+                }).await;
+
+                // The archive is only needed again for a repair, which downloads it anew
+                match extracted {
+                    Ok(Ok(())) => { let _ = fs::remove_file(&native_zip_path).await; }
+                    Ok(Err(e)) => tracing::warn!("Could not extract {}: {}", native_zip_path.display(), e),
+                    Err(e) => tracing::warn!("Could not extract {}: {}", native_zip_path.display(), e),
+                }
             }
         }
         
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn libraries(json: serde_json::Value) -> Vec<Library> {
+        serde_json::from_value(json).unwrap()
+    }
+
+    fn artifact(path: &str) -> serde_json::Value {
+        serde_json::json!({ "url": format!("https://libraries.minecraft.net/{}", path), "path": path })
+    }
+
+    fn picked(libraries: &[Library], arch_suffix: Option<&str>) -> Vec<String> {
+        natives_for(libraries, "linux", arch_suffix, "64").into_iter().map(|(_, a)| a.path).collect()
+    }
+
+    // LWJGL 3 lists each natives jar as its own library, one per CPU
+    fn lwjgl3() -> Vec<Library> {
+        libraries(serde_json::json!([
+            { "name": "org.lwjgl:lwjgl:3.3.1", "downloads": { "artifact": artifact("lwjgl-3.3.1.jar") } },
+            { "name": "org.lwjgl:lwjgl:3.3.1:natives-linux", "downloads": { "artifact": artifact("lwjgl-3.3.1-natives-linux.jar") } },
+            { "name": "org.lwjgl:lwjgl:3.3.1:natives-linux-arm64", "downloads": { "artifact": artifact("lwjgl-3.3.1-natives-linux-arm64.jar") } },
+        ]))
+    }
+
+    #[test]
+    fn lwjgl3_natives_match_the_cpu() {
+        assert_eq!(picked(&lwjgl3(), None), vec!["lwjgl-3.3.1-natives-linux.jar"]);
+        assert_eq!(picked(&lwjgl3(), Some("arm64")), vec!["lwjgl-3.3.1-natives-linux-arm64.jar"]);
+        // No jar for this CPU, the plain one is all there is
+        assert_eq!(picked(&lwjgl3(), Some("arm32")), vec!["lwjgl-3.3.1-natives-linux.jar"]);
+    }
+
+    #[test]
+    fn natives_map_of_old_versions() {
+        let libs = libraries(serde_json::json!([{
+            "name": "org.lwjgl.lwjgl:lwjgl-platform:2.9.4-nightly-20150209",
+            "natives": { "linux": "natives-linux", "windows": "natives-windows-${arch}" },
+            "downloads": { "classifiers": {
+                "natives-linux": artifact("lwjgl-platform-natives-linux.jar"),
+                "natives-windows-64": artifact("lwjgl-platform-natives-windows-64.jar"),
+            } },
+        }, {
+            "name": "tv.twitch:twitch:6.5",
+            "downloads": { "artifact": artifact("twitch-6.5.jar") },
+        }]));
+        assert_eq!(picked(&libs, None), vec!["lwjgl-platform-natives-linux.jar"]);
+        assert_eq!(picked(&libs, Some("arm64")), vec!["lwjgl-platform-natives-linux.jar"]);
+
+        let windows: Vec<String> = natives_for(&libs, "windows", None, "64").into_iter().map(|(_, a)| a.path).collect();
+        assert_eq!(windows, vec!["lwjgl-platform-natives-windows-64.jar"]);
+    }
+
+    fn write(path: &Path, contents: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[tokio::test]
+    async fn jars_no_version_names_are_orphaned() {
+        let root = tempfile::tempdir().unwrap();
+        let versions_dir = root.path().join("versions");
+        let libraries_dir = root.path().join("libraries");
+
+        // A loader install inherits the vanilla version's libraries on top of its own
+        write(&versions_dir.join("1.20.1/1.20.1.json"), &serde_json::json!({
+            "libraries": [
+                { "name": "org.lwjgl:lwjgl:3.3.1", "downloads": {
+                    "artifact": artifact("org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1.jar"),
+                    "classifiers": { "natives-linux": artifact("org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-linux.jar") },
+                } },
+            ],
+        }).to_string());
+        write(&versions_dir.join("fabric-loader-0.15.11-1.20.1/fabric-loader-0.15.11-1.20.1.json"), &serde_json::json!({
+            "inheritsFrom": "1.20.1",
+            // No downloads, only maven coordinates
+            "libraries": [{ "name": "net.fabricmc:fabric-loader:0.15.11" }],
+        }).to_string());
+
+        for jar in [
+            "org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1.jar",
+            "org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1-natives-linux.jar",
+            "net/fabricmc/fabric-loader/0.15.11/fabric-loader-0.15.11.jar",
+            "org/lwjgl/lwjgl/3.2.2/lwjgl-3.2.2.jar",
+        ] {
+            write(&libraries_dir.join(jar), "jar");
+        }
+
+        let manager = LibraryManager::new(versions_dir.clone(), VersionJsonCache::new(versions_dir), Arc::default());
+        let orphaned = manager.find_orphaned_libraries().await.unwrap();
+        assert_eq!(orphaned, vec![(libraries_dir.join("org/lwjgl/lwjgl/3.2.2/lwjgl-3.2.2.jar"), 3)]);
+    }
+
+    #[tokio::test]
+    async fn unreadable_version_json_removes_nothing() {
+        let root = tempfile::tempdir().unwrap();
+        let versions_dir = root.path().join("versions");
+        write(&versions_dir.join("1.20.1/1.20.1.json"), "{ not json");
+        write(&root.path().join("libraries/org/lwjgl/lwjgl/3.3.1/lwjgl-3.3.1.jar"), "jar");
+
+        let manager = LibraryManager::new(versions_dir.clone(), VersionJsonCache::new(versions_dir), Arc::default());
+        assert!(manager.find_orphaned_libraries().await.is_err());
+    }
+}
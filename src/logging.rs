@@ -0,0 +1,165 @@
+// RCraft's own log (not Minecraft's). Events from the `tracing` macros go to a size-rotated
+// file under the config dir and, once the UI is up, to the Launcher tab of the Logs page.
+
+use std::fmt::Write as _;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use tracing::field::{Field, Visit};
+use tracing::level_filters::LevelFilter;
+use tracing::subscriber::Interest;
+use tracing::{span, Event, Level, Metadata, Subscriber};
+
+use crate::models::LogLevel;
+
+const LOG_FILE_NAME: &str = "rcraft.log";
+const MAX_LOG_FILE_SIZE: u64 = 2 * 1024 * 1024;
+// rcraft.log plus rcraft.log.1 .. rcraft.log.N
+const ROTATED_LOG_FILES: usize = 3;
+
+// Most verbose level still recorded, as an index into LEVELS. Changed at runtime from settings.
+static MAX_LEVEL: AtomicUsize = AtomicUsize::new(2);
+const LEVELS: [Level; 5] = [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE];
+
+type UiSink = Box<dyn Fn(String) + Send>;
+static UI_SINK: OnceLock<Mutex<UiSink>> = OnceLock::new();
+
+pub fn log_dir(config_dir: &Path) -> PathBuf {
+    config_dir.join("launcher-logs")
+}
+
+// Installs the global subscriber. Safe to call once; later calls are ignored.
+pub fn init(config_dir: &Path, level: LogLevel) {
+    set_level(level);
+    let subscriber = LauncherSubscriber {
+        path: log_dir(config_dir).join(LOG_FILE_NAME),
+        file: Mutex::new(None),
+        next_span_id: AtomicU64::new(1),
+    };
+    let _ = tracing::subscriber::set_global_default(subscriber);
+}
+
+pub fn set_level(level: LogLevel) {
+    let index = match level {
+        LogLevel::Error => 0,
+        LogLevel::Warn => 1,
+        LogLevel::Info => 2,
+        LogLevel::Debug => 3,
+        LogLevel::Trace => 4,
+    };
+    MAX_LEVEL.store(index, Ordering::Relaxed);
+}
+
+// Forward formatted lines to the UI as well as the file
+pub fn set_ui_sink(sink: impl Fn(String) + Send + 'static) {
+    let _ = UI_SINK.set(Mutex::new(Box::new(sink)));
+}
+
+struct LauncherSubscriber {
+    path: PathBuf,
+    file: Mutex<Option<File>>,
+    next_span_id: AtomicU64,
+}
+
+impl LauncherSubscriber {
+    fn write_line(&self, line: &str) {
+        let mut file = self.file.lock().unwrap();
+
+        let too_big = fs::metadata(&self.path).map(|m| m.len() >= MAX_LOG_FILE_SIZE).unwrap_or(false);
+        if too_big {
+            *file = None;
+            rotate(&self.path);
+        }
+
+        if file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            *file = OpenOptions::new().create(true).append(true).open(&self.path).ok();
+        }
+
+        if let Some(f) = file.as_mut() {
+            let _ = writeln!(f, "{}", line);
+        }
+    }
+}
+
+fn rotate(path: &Path) {
+    let numbered = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+    let _ = fs::remove_file(numbered(ROTATED_LOG_FILES));
+    for n in (1..ROTATED_LOG_FILES).rev() {
+        let _ = fs::rename(numbered(n), numbered(n + 1));
+    }
+    let _ = fs::rename(path, numbered(1));
+}
+
+impl Subscriber for LauncherSubscriber {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        // The level can change at runtime, so never let tracing cache a decision
+        Interest::sometimes()
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::TRACE)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= LEVELS[MAX_LEVEL.load(Ordering::Relaxed)]
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(self.next_span_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let metadata = event.metadata();
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let line = format!("{} {:>5} {}: {}{}", timestamp, metadata.level(), metadata.target(), visitor.message, visitor.fields);
+
+        self.write_line(&line);
+        if let Some(sink) = UI_SINK.get() {
+            (sink.lock().unwrap())(line);
+        }
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
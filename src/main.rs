@@ -7,6 +7,17 @@ mod settings;
 mod java_manager;
 mod library_manager;
 mod modrinth_client;
+mod diagnostics;
+mod mod_index;
+mod mod_presets;
+mod updates;
+mod logging;
+mod modpack;
+mod http;
+mod profile_bundle;
+mod version_cache;
+mod shared_mods;
+mod auth;
 
 use adw::Application;
 use gtk4::glib;
@@ -21,8 +32,13 @@ fn main() {
 
     glib::set_application_name("RCraft");
 
+    // GTK rejects options it doesn't know, so ours are taken out before it sees them
+    let mut args: Vec<String> = std::env::args().collect();
+    if args.iter().any(|a| a == "--portable") {
+        config::enable_portable();
+        args.retain(|a| a != "--portable");
+    }
 
-
-    let relm_app = RelmApp::from_app(app);
+    let relm_app = RelmApp::from_app(app).with_args(args);
     relm_app.run::<AppModel>(())
 }
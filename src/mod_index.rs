@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const MOD_INDEX_FILE: &str = "mods_index.json";
+// Mod loaders skip jars renamed to end in this, which is how a mod is switched off without
+// deleting it. The index keeps the enabled name either way.
+pub const DISABLED_SUFFIX: &str = ".disabled";
+
+// Where `filename` is in the mods dir right now, enabled or disabled
+pub fn installed_path(mods_dir: &Path, filename: &str) -> Option<PathBuf> {
+    let enabled = mods_dir.join(filename);
+    if enabled.exists() {
+        return Some(enabled);
+    }
+    let disabled = mods_dir.join(format!("{}{}", filename, DISABLED_SUFFIX));
+    disabled.exists().then_some(disabled)
+}
+
+// What RCraft knows about a mod it installed into a profile
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledMod {
+    pub filename: String,
+    // Pulled in as a dependency of another mod rather than picked by the user
+    #[serde(default)]
+    pub auto_installed: bool,
+    // Project ids of the required dependencies of this mod
+    #[serde(default)]
+    pub dependencies: Vec<String>,
+}
+
+// Per-profile metadata stored next to the jars in the mods dir: ProjectID -> InstalledMod
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModIndex {
+    #[serde(default)]
+    pub mods: HashMap<String, InstalledMod>,
+}
+
+impl ModIndex {
+    pub fn load(mods_dir: &Path) -> Self {
+        match std::fs::read_to_string(mods_dir.join(MOD_INDEX_FILE)) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_default(),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self, mods_dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(mods_dir)?;
+        let json = serde_json::to_string_pretty(self).unwrap_or_default();
+        std::fs::write(mods_dir.join(MOD_INDEX_FILE), json)
+    }
+
+    pub fn project_for_file(&self, filename: &str) -> Option<String> {
+        self.mods.iter().find(|(_, m)| m.filename == filename).map(|(pid, _)| pid.clone())
+    }
+
+    // Auto-installed mods that nothing else depends on anymore, assuming `removed` is gone.
+    // Anything still reachable from a mod that stays is kept, so a chain of dependencies that
+    // only served `removed` is found in one go, dependencies on each other included.
+    // Manually installed mods are never reported.
+    pub fn orphaned_dependencies(&self, removed: &str) -> Vec<String> {
+        // Only consider mods that were (transitively) pulled in by the removed one
+        let pulled_in = self.dependencies_of([removed]);
+
+        // Everything else that stays is a root, whatever it depends on is still needed
+        let roots = self.mods.iter()
+            .filter(|(pid, m)| pid.as_str() != removed && !(m.auto_installed && pulled_in.contains(pid.as_str())))
+            .map(|(pid, _)| pid.as_str());
+        let mut needed = self.dependencies_of(roots.clone());
+        needed.extend(roots);
+
+        let mut orphaned: Vec<String> = pulled_in.into_iter()
+            .filter(|pid| *pid != removed && !needed.contains(pid))
+            .filter(|pid| self.mods.get(*pid).is_some_and(|m| m.auto_installed))
+            .map(|pid| pid.to_string())
+            .collect();
+        orphaned.sort();
+        orphaned
+    }
+
+    // Project ids `from` depends on, directly or through other dependencies
+    fn dependencies_of<'a>(&'a self, from: impl IntoIterator<Item = &'a str>) -> HashSet<&'a str> {
+        let mut reached: HashSet<&str> = HashSet::new();
+        let mut stack: Vec<&str> = from.into_iter().collect();
+        while let Some(pid) = stack.pop() {
+            let Some(m) = self.mods.get(pid) else { continue };
+            for dep in &m.dependencies {
+                if reached.insert(dep.as_str()) {
+                    stack.push(dep.as_str());
+                }
+            }
+        }
+        reached
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (project id, auto installed, dependencies)
+    fn index(mods: &[(&str, bool, &[&str])]) -> ModIndex {
+        let mods = mods.iter().map(|(pid, auto_installed, deps)| {
+            let installed = InstalledMod {
+                filename: format!("{}.jar", pid),
+                auto_installed: *auto_installed,
+                dependencies: deps.iter().map(|d| d.to_string()).collect(),
+            };
+            (pid.to_string(), installed)
+        }).collect();
+        ModIndex { mods }
+    }
+
+    #[test]
+    fn removes_the_whole_chain_a_mod_pulled_in() {
+        let index = index(&[
+            ("sodium-extra", false, &["sodium"]),
+            ("sodium", true, &["fabric-api"]),
+            ("fabric-api", true, &[]),
+        ]);
+        assert_eq!(index.orphaned_dependencies("sodium-extra"), vec!["fabric-api", "sodium"]);
+    }
+
+    #[test]
+    fn keeps_dependencies_another_mod_still_needs() {
+        let index = index(&[
+            ("sodium-extra", false, &["sodium"]),
+            ("iris", false, &["sodium"]),
+            ("sodium", true, &["fabric-api"]),
+            ("fabric-api", true, &[]),
+        ]);
+        assert!(index.orphaned_dependencies("sodium-extra").is_empty());
+        // Only part of the chain is shared
+        let index = self::index(&[
+            ("modmenu", false, &["fabric-api", "cloth-config"]),
+            ("lithium", false, &["fabric-api"]),
+            ("fabric-api", true, &[]),
+            ("cloth-config", true, &[]),
+        ]);
+        assert_eq!(index.orphaned_dependencies("modmenu"), vec!["cloth-config"]);
+    }
+
+    #[test]
+    fn never_removes_mods_the_user_installed() {
+        let index = index(&[
+            ("sodium-extra", false, &["sodium"]),
+            ("sodium", false, &["fabric-api"]),
+            ("fabric-api", true, &[]),
+        ]);
+        assert!(index.orphaned_dependencies("sodium-extra").is_empty());
+    }
+
+    #[test]
+    fn dependencies_on_each_other_are_still_orphaned() {
+        let index = index(&[
+            ("create", false, &["flywheel"]),
+            ("flywheel", true, &["registrate"]),
+            ("registrate", true, &["flywheel"]),
+        ]);
+        assert_eq!(index.orphaned_dependencies("create"), vec!["flywheel", "registrate"]);
+
+        // Unless something that stays holds on to the cycle
+        let mut index = index;
+        index.mods.insert("steam-rails".to_string(), InstalledMod {
+            filename: "steam-rails.jar".to_string(),
+            auto_installed: false,
+            dependencies: vec!["registrate".to_string()],
+        });
+        assert!(index.orphaned_dependencies("create").is_empty());
+
+        // A mod depending on itself through the one removed
+        let index = self::index(&[("a", false, &["b"]), ("b", true, &["a"])]);
+        assert_eq!(index.orphaned_dependencies("a"), vec!["b"]);
+    }
+}
@@ -0,0 +1,27 @@
+// Curated sets of Modrinth projects that can be installed in one click from the Mods page.
+// Ids (not slugs) are used so they line up with the ids tracked in the mods index.
+// To add a preset, append it to PRESETS, the Mods page picks it up automatically.
+
+pub struct ModPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub project_ids: &'static [&'static str],
+}
+
+pub const PRESETS: &[ModPreset] = &[
+    ModPreset {
+        id: "performance",
+        name: "Performance",
+        description: "Sodium, Lithium and FerriteCore for better FPS, tick times and memory usage",
+        project_ids: &[
+            "AANobbMI", // Sodium
+            "gvQqBUqZ", // Lithium
+            "uXXizFIs", // FerriteCore
+        ],
+    },
+];
+
+pub fn find_preset(id: &str) -> Option<&'static ModPreset> {
+    PRESETS.iter().find(|p| p.id == id)
+}
@@ -37,6 +37,9 @@ pub struct Rule {
 pub struct LibraryArtifact {
     pub url: String,
     pub path: String,
+    // Mojang's libraries have one, loader libraries often don't
+    #[serde(default)]
+    pub sha1: Option<String>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -91,8 +94,12 @@ pub struct AssetIndexFile {
 
 
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Profile {
+    // Stable identity, also the profiles.json key and the instance folder name.
+    // Empty for profiles saved before ids existed until they are migrated on load.
+    #[serde(default)]
+    pub id: String,
     pub username: String,
     pub version: String,
     pub ram_mb: u32,
@@ -100,10 +107,175 @@ pub struct Profile {
     pub playtime_seconds: u64,
     #[serde(default)]
     pub last_launch: Option<u64>,
-    #[serde(default)]
-    pub is_fabric: bool,
+    // Profiles saved before Quilt support have `is_fabric` instead
+    #[serde(default, alias = "is_fabric", deserialize_with = "deserialize_loader")]
+    pub loader: Loader,
     #[serde(default)]
     pub game_dir: Option<String>,
+    // Free-form reminder of what the profile is for
+    #[serde(default)]
+    pub notes: Option<String>,
+    // How the last session ended. No exit code means the game was killed by a signal.
+    #[serde(default)]
+    pub last_crashed: bool,
+    #[serde(default)]
+    pub last_exit_code: Option<i32>,
+    // Folders whose mods are linked in on top of the profile's own, see shared_mods.rs
+    #[serde(default)]
+    pub shared_mod_dirs: Vec<String>,
+    // Java that ran the last session that got going, for troubleshooting
+    #[serde(default)]
+    pub last_java_path: Option<String>,
+    #[serde(default)]
+    pub last_java_version: Option<u32>,
+    // uuid of the signed in account the profile plays as, None plays offline as `username`
+    #[serde(default)]
+    pub account_id: Option<String>,
+    // Extra JVM flags (-XX:+UseG1GC...), placed after the heap flags
+    #[serde(default)]
+    pub jvm_args: Vec<String>,
+    // Unix time the profile was created. Unknown for profiles made before this was recorded
+    #[serde(default)]
+    pub created_at: Option<u64>,
+    // Modrinth modpack the profile was installed from, kept up to date by pack updates
+    #[serde(default)]
+    pub modpack: Option<ModpackSource>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModpackSource {
+    pub name: String,
+    pub version_id: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Loader {
+    #[default]
+    Vanilla,
+    Fabric,
+    Quilt,
+}
+
+impl Loader {
+    pub const ALL: [Loader; 3] = [Loader::Vanilla, Loader::Fabric, Loader::Quilt];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Loader::Vanilla => "Vanilla",
+            Loader::Fabric => "Fabric",
+            Loader::Quilt => "Quilt",
+        }
+    }
+
+    // Modrinth's loader id; the installer names its version "<id>-loader-<build>-<game version>"
+    pub fn id(self) -> Option<&'static str> {
+        match self {
+            Loader::Vanilla => None,
+            Loader::Fabric => Some("fabric"),
+            Loader::Quilt => Some("quilt"),
+        }
+    }
+}
+
+// What the Mods page browses and installs. Only mods depend on the loader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProjectType {
+    #[default]
+    Mod,
+    ResourcePack,
+    Shader,
+    Datapack,
+}
+
+impl ProjectType {
+    pub const ALL: [ProjectType; 4] = [ProjectType::Mod, ProjectType::ResourcePack, ProjectType::Shader, ProjectType::Datapack];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ProjectType::Mod => "Mods",
+            ProjectType::ResourcePack => "Resource Packs",
+            ProjectType::Shader => "Shaders",
+            ProjectType::Datapack => "Datapacks",
+        }
+    }
+
+    // Modrinth's project_type facet
+    pub fn id(self) -> &'static str {
+        match self {
+            ProjectType::Mod => "mod",
+            ProjectType::ResourcePack => "resourcepack",
+            ProjectType::Shader => "shader",
+            ProjectType::Datapack => "datapack",
+        }
+    }
+
+    // Folder the game reads these from: in the game dir, or in a world for datapacks
+    pub fn folder(self) -> &'static str {
+        match self {
+            ProjectType::Mod => "mods",
+            ProjectType::ResourcePack => "resourcepacks",
+            ProjectType::Shader => "shaderpacks",
+            ProjectType::Datapack => "datapacks",
+        }
+    }
+
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            ProjectType::Mod => ".jar",
+            _ => ".zip",
+        }
+    }
+
+    pub fn is_loader_specific(self) -> bool {
+        self == ProjectType::Mod
+    }
+}
+
+fn deserialize_loader<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Loader, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Loader(Loader),
+        IsFabric(bool),
+    }
+    Ok(match Stored::deserialize(deserializer)? {
+        Stored::Loader(loader) => loader,
+        Stored::IsFabric(true) => Loader::Fabric,
+        Stored::IsFabric(false) => Loader::Vanilla,
+    })
+}
+
+// A signed in Minecraft account. Its name can be changed on Mojang's side, the uuid never is.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Account {
+    pub username: String,
+    pub uuid: String,
+    pub access_token: String,
+    // Microsoft refresh token, traded for a new access token once that one expires
+    #[serde(default)]
+    pub refresh_token: String,
+    // Unix time the access token stops working
+    #[serde(default)]
+    pub expires_at: u64,
+}
+
+impl Account {
+    // A few minutes early, so the token can't run out while the game starts
+    pub fn needs_refresh(&self) -> bool {
+        crate::utils::unix_now() + 300 >= self.expires_at
+    }
+}
+
+impl Profile {
+    // Mod loader as shown to the user, None for vanilla
+    pub fn loader_name(&self) -> Option<&'static str> {
+        self.loader.id().map(|_| self.loader.name())
+    }
+
+    // Loader id Modrinth filters mod versions by
+    pub fn loader_id(&self) -> Option<&'static str> {
+        self.loader.id()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -115,12 +287,12 @@ pub enum Section {
     Mods,
 }
 
+// Color scheme. Transparency is a separate setting applied on top of any of these.
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Theme {
     Dark,
     Light,
     System,
-    Transparent,
 }
 
 impl Default for Theme {
@@ -135,7 +307,97 @@ impl std::fmt::Display for Theme {
             Theme::Dark => write!(f, "Dark"),
             Theme::Light => write!(f, "Light"),
             Theme::System => write!(f, "System"),
-            Theme::Transparent => write!(f, "Transparent"),
+        }
+    }
+}
+
+// Order of the profiles on the home page
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum ProfileSort {
+    #[default]
+    LastPlayed,
+    MostPlayed,
+    NameAsc,
+    RecentlyCreated,
+}
+
+impl ProfileSort {
+    pub const ALL: [ProfileSort; 4] = [ProfileSort::LastPlayed, ProfileSort::MostPlayed, ProfileSort::NameAsc, ProfileSort::RecentlyCreated];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ProfileSort::LastPlayed => "Last Played",
+            ProfileSort::MostPlayed => "Most Played",
+            ProfileSort::NameAsc => "Name",
+            ProfileSort::RecentlyCreated => "Recently Created",
+        }
+    }
+
+    // Profiles never launched (or of unknown age) go last. Ties fall back to the name, then the
+    // id, so the order is the same every time.
+    pub fn sorted<'a>(self, profiles: impl IntoIterator<Item = &'a Profile>) -> Vec<&'a Profile> {
+        let mut sorted: Vec<&Profile> = profiles.into_iter().collect();
+        sorted.sort_by(|a, b| {
+            let primary = match self {
+                ProfileSort::LastPlayed => b.last_launch.cmp(&a.last_launch),
+                ProfileSort::MostPlayed => b.playtime_seconds.cmp(&a.playtime_seconds),
+                ProfileSort::NameAsc => std::cmp::Ordering::Equal,
+                ProfileSort::RecentlyCreated => b.created_at.cmp(&a.created_at),
+            };
+            primary
+                .then_with(|| a.username.to_lowercase().cmp(&b.username.to_lowercase()))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        sorted
+    }
+}
+
+// Verbosity of RCraft's own log, see logging.rs
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum JavaVendor {
+    #[default]
+    Temurin,
+    GraalVM,
+    Zulu,
+}
+
+impl JavaVendor {
+    // Suffix used for the runtime directory. Temurin keeps the plain `java-N`
+    // name so runtimes installed before vendors existed are still picked up.
+    pub fn dir_suffix(&self) -> Option<&'static str> {
+        match self {
+            JavaVendor::Temurin => None,
+            JavaVendor::GraalVM => Some("graalvm"),
+            JavaVendor::Zulu => Some("zulu"),
+        }
+    }
+
+    // Distribution name understood by the Foojay Disco API
+    pub fn disco_distribution(&self) -> &'static str {
+        match self {
+            JavaVendor::Temurin => "temurin",
+            JavaVendor::GraalVM => "graalvm_community",
+            JavaVendor::Zulu => "zulu",
+        }
+    }
+}
+
+impl std::fmt::Display for JavaVendor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JavaVendor::Temurin => write!(f, "Temurin"),
+            JavaVendor::GraalVM => write!(f, "GraalVM"),
+            JavaVendor::Zulu => write!(f, "Zulu"),
         }
     }
 }
@@ -150,6 +412,56 @@ pub struct ModSearchResult {
     pub versions: Option<Vec<String>>,
     pub follows: u32,
     pub downloads: u32,
+    // "required", "optional", "unsupported" or "unknown", see side_support_label
+    #[serde(default)]
+    pub client_side: Option<String>,
+    #[serde(default)]
+    pub server_side: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModProject {
+    pub id: String,
+    pub slug: String,
+    pub title: String,
+    pub description: String,
+    #[serde(default)]
+    pub body: String,
+    pub icon_url: Option<String>,
+    #[serde(default)]
+    pub gallery: Vec<GalleryImage>,
+    #[serde(default)]
+    pub downloads: u32,
+    #[serde(default)]
+    pub followers: u32,
+    #[serde(default)]
+    pub client_side: Option<String>,
+    #[serde(default)]
+    pub server_side: Option<String>,
+}
+
+// Does nothing in a client profile, RCraft only launches clients
+pub fn is_server_only(client_side: Option<&str>) -> bool {
+    client_side == Some("unsupported")
+}
+
+// Short badge for where a project has to be installed, None when that's unremarkable
+pub fn side_support_label(client_side: Option<&str>, server_side: Option<&str>) -> Option<&'static str> {
+    match (client_side, server_side) {
+        (Some("unsupported"), _) => Some("Server only"),
+        (Some("required"), Some("unsupported")) => Some("Client only"),
+        (Some("required"), Some("required")) => Some("Client and server"),
+        (Some("required"), _) => Some("Client required"),
+        (_, Some("required")) => Some("Server required"),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GalleryImage {
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -161,6 +473,45 @@ pub struct ModVersion {
     pub game_versions: Vec<String>,
     pub loaders: Vec<String>,
     pub files: Vec<ModFile>,
+    #[serde(default)]
+    pub dependencies: Vec<ModDependency>,
+    // release, beta or alpha. Cached responses from before this was read count as releases
+    #[serde(default = "default_version_type")]
+    pub version_type: String,
+}
+
+fn default_version_type() -> String {
+    "release".to_string()
+}
+
+// Modrinth lists versions newest first. With `prefer_stable` the newest release wins over any
+// newer beta or alpha, which are only picked when there's no release at all.
+pub fn pick_mod_version(versions: &[ModVersion], prefer_stable: bool) -> Option<&ModVersion> {
+    let newest = versions.first();
+    if !prefer_stable {
+        return newest;
+    }
+    versions.iter().find(|v| v.version_type == "release").or(newest)
+}
+
+// Whether `picked` is an update over `installed`, both version ids out of `versions` (newest
+// first). Only one listed before the installed version is newer, so a beta left in place isn't
+// "updated" back to an older release. An installed version that's no longer listed for the
+// profile is replaced by any other.
+pub fn is_newer_version(versions: &[ModVersion], picked: &str, installed: &str) -> bool {
+    let position = |id: &str| versions.iter().position(|v| v.id == id);
+    match (position(picked), position(installed)) {
+        (Some(p), Some(i)) => p < i,
+        _ => picked != installed,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModDependency {
+    pub project_id: Option<String>,
+    pub version_id: Option<String>,
+    // required, optional, incompatible or embedded
+    pub dependency_type: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -190,6 +541,9 @@ pub struct VersionJson {
     #[serde(rename = "assetIndex")]
     pub asset_index: Option<AssetIndex>,
     pub downloads: Option<VersionDownloads>,
+    // Flat game argument template used before 1.13 (replaced by `arguments` afterwards)
+    #[serde(rename = "minecraftArguments")]
+    pub minecraft_arguments: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -212,3 +566,133 @@ pub struct JavaVersion {
     #[serde(rename = "majorVersion")]
     pub major_version: u32,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn profile_round_trip() {
+        let profile = Profile {
+            id: "3f2b9c1e-8d4a-4f6b-9a1c-2e7d5b8f0a13".to_string(),
+            username: "Steve".to_string(),
+            version: "1.20.1".to_string(),
+            ram_mb: 6144,
+            playtime_seconds: 7260,
+            last_launch: Some(1_700_000_000),
+            loader: Loader::Quilt,
+            game_dir: Some("/games/steve".to_string()),
+            notes: Some("Create modpack\nsecond line".to_string()),
+            last_crashed: true,
+            last_exit_code: Some(-1),
+            shared_mod_dirs: vec!["/games/shared-mods".to_string()],
+            last_java_path: Some("/usr/lib/jvm/java-17/bin/java".to_string()),
+            last_java_version: Some(17),
+            account_id: Some("069a79f444e94726a5befca90e38aaf5".to_string()),
+            jvm_args: vec!["-XX:+UseG1GC".to_string(), "-XX:MaxGCPauseMillis=50".to_string()],
+            created_at: Some(1_690_000_000),
+            modpack: Some(ModpackSource { name: "Fabulously Optimized".to_string(), version_id: "5.4.1".to_string() }),
+        };
+        // profiles.json is a map of id -> profile
+        let profiles = HashMap::from([(profile.id.clone(), profile)]);
+        let json = serde_json::to_string_pretty(&profiles).unwrap();
+        let loaded: HashMap<String, Profile> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, profiles);
+    }
+
+    #[test]
+    fn old_profile_gets_defaults_for_new_fields() {
+        let json = r#"{ "username": "Alex", "version": "1.8.9", "ram_mb": 2048 }"#;
+        let profile: Profile = serde_json::from_str(json).unwrap();
+        assert_eq!(profile, Profile {
+            id: String::new(),
+            username: "Alex".to_string(),
+            version: "1.8.9".to_string(),
+            ram_mb: 2048,
+            playtime_seconds: 0,
+            last_launch: None,
+            loader: Loader::Vanilla,
+            game_dir: None,
+            notes: None,
+            last_crashed: false,
+            last_exit_code: None,
+            shared_mod_dirs: Vec::new(),
+            last_java_path: None,
+            last_java_version: None,
+            account_id: None,
+            jvm_args: Vec::new(),
+            created_at: None,
+            modpack: None,
+        });
+    }
+
+    #[test]
+    fn never_played_profiles_sort_last() {
+        let profile = |id: &str, username: &str, last_launch: Option<u64>, playtime_seconds: u64| Profile {
+            id: id.to_string(),
+            username: username.to_string(),
+            last_launch,
+            playtime_seconds,
+            ..serde_json::from_str(r#"{ "username": "", "version": "1.20.1", "ram_mb": 4096 }"#).unwrap()
+        };
+        let profiles = [
+            profile("a", "zed", None, 0),
+            profile("b", "Alex", Some(100), 50),
+            profile("c", "steve", Some(200), 10),
+            profile("d", "bob", None, 0),
+        ];
+        let ids = |sort: ProfileSort| sort.sorted(&profiles).iter().map(|p| p.id.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(ids(ProfileSort::LastPlayed), ["c", "b", "d", "a"]);
+        assert_eq!(ids(ProfileSort::MostPlayed), ["b", "c", "d", "a"]);
+        assert_eq!(ids(ProfileSort::NameAsc), ["b", "d", "c", "a"]);
+    }
+
+    #[test]
+    fn old_fabric_flag_becomes_loader() {
+        let json = r#"{ "username": "Alex", "version": "1.20.1", "ram_mb": 4096, "is_fabric": true }"#;
+        let profile: Profile = serde_json::from_str(json).unwrap();
+        assert_eq!(profile.loader, Loader::Fabric);
+
+        let json = r#"{ "username": "Alex", "version": "1.20.1", "ram_mb": 4096, "is_fabric": false }"#;
+        let profile: Profile = serde_json::from_str(json).unwrap();
+        assert_eq!(profile.loader, Loader::Vanilla);
+    }
+
+    fn mod_version(id: &str, version_type: &str) -> ModVersion {
+        ModVersion {
+            id: id.to_string(),
+            project_id: "sodium".to_string(),
+            name: id.to_string(),
+            version_number: id.to_string(),
+            game_versions: vec!["1.21.1".to_string()],
+            loaders: vec!["fabric".to_string()],
+            files: Vec::new(),
+            dependencies: Vec::new(),
+            version_type: version_type.to_string(),
+        }
+    }
+
+    #[test]
+    fn prefers_newest_release_over_newer_betas() {
+        let versions = vec![mod_version("0.6.0-alpha", "alpha"), mod_version("0.5.9-beta", "beta"), mod_version("0.5.8", "release")];
+        assert_eq!(pick_mod_version(&versions, true).unwrap().id, "0.5.8");
+        assert_eq!(pick_mod_version(&versions, false).unwrap().id, "0.6.0-alpha");
+        // No release at all: the newest build is still better than nothing
+        assert_eq!(pick_mod_version(&versions[..2], true).unwrap().id, "0.6.0-alpha");
+        assert!(pick_mod_version(&[], true).is_none());
+    }
+
+    #[test]
+    fn newer_means_listed_before_the_installed_version() {
+        let versions = vec![mod_version("0.6.0-alpha", "alpha"), mod_version("0.5.9-beta", "beta"), mod_version("0.5.8", "release")];
+        assert!(is_newer_version(&versions, "0.5.9-beta", "0.5.8"));
+        assert!(!is_newer_version(&versions, "0.5.8", "0.5.8"));
+        // Preferring stable picks 0.5.8, which is older than the beta the user installed
+        assert!(!is_newer_version(&versions, "0.5.8", "0.5.9-beta"));
+        // Installed from a listing this profile no longer gets
+        assert!(is_newer_version(&versions, "0.5.8", "0.5.7"));
+    }
+
+}
@@ -0,0 +1,363 @@
+// Modrinth modpacks (.mrpack). A pack is a zip with a `modrinth.index.json` listing files to
+// download plus `overrides/` copied into the game dir as-is. What got installed is recorded in
+// the game dir so a newer version of the same pack only touches what changed.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use zip::ZipArchive;
+
+use crate::models::Loader;
+
+const INDEX_FILE: &str = "modrinth.index.json";
+const OVERRIDE_DIRS: [&str; 2] = ["overrides/", "client-overrides/"];
+const STATE_FILE: &str = ".rcraft/modpack.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackIndex {
+    pub name: String,
+    #[serde(rename = "versionId")]
+    pub version_id: String,
+    #[serde(default)]
+    pub files: Vec<PackFile>,
+    // "minecraft", "fabric-loader", "forge"...
+    #[serde(default)]
+    pub dependencies: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackFile {
+    pub path: String,
+    pub hashes: HashMap<String, String>,
+    #[serde(default)]
+    pub env: Option<PackFileEnv>,
+    pub downloads: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackFileEnv {
+    pub client: String,
+}
+
+impl PackFile {
+    fn sha1(&self) -> Option<&str> {
+        self.hashes.get("sha1").map(String::as_str)
+    }
+
+    fn wanted_on_client(&self) -> bool {
+        self.env.as_ref().is_none_or(|env| env.client != "unsupported")
+    }
+}
+
+impl PackIndex {
+    pub fn minecraft_version(&self) -> Option<&str> {
+        self.dependencies.get("minecraft").map(String::as_str)
+    }
+
+    pub fn loader(&self) -> Loader {
+        if self.dependencies.contains_key("quilt-loader") {
+            Loader::Quilt
+        } else if self.dependencies.contains_key("fabric-loader") {
+            Loader::Fabric
+        } else {
+            Loader::Vanilla
+        }
+    }
+
+    // Loaders RCraft can't install yet
+    pub fn unsupported_loader(&self) -> Option<&str> {
+        ["forge", "neoforge"].into_iter().find(|l| self.dependencies.contains_key(*l))
+    }
+}
+
+// Written next to the instance after every install, the baseline for the next update
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledPack {
+    pub index: PackIndex,
+    // Override path -> sha1 of what we wrote, to tell user edits apart from our own files
+    #[serde(default)]
+    pub overrides: HashMap<String, String>,
+}
+
+impl InstalledPack {
+    pub fn load(game_dir: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(game_dir.join(STATE_FILE)).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn save(&self, game_dir: &Path) -> Result<()> {
+        let path = game_dir.join(STATE_FILE);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        crate::utils::write_atomic(&path, serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+}
+
+// What an install or update will do to the game dir
+#[derive(Debug, Clone, Default)]
+pub struct PackPlan {
+    pub download: Vec<PackFile>,
+    pub remove: Vec<String>,
+    pub overrides: Vec<String>,
+    // Overrides whose file on disk was changed by the user and differs from the pack's copy
+    pub conflicts: Vec<String>,
+    // Files already matching the new pack, left alone
+    pub unchanged: usize,
+}
+
+pub fn read_index(pack_path: &Path) -> Result<PackIndex> {
+    let mut archive = ZipArchive::new(File::open(pack_path)?)?;
+    let mut content = String::new();
+    archive.by_name(INDEX_FILE)
+        .map_err(|_| anyhow!("{} is not a Modrinth modpack", pack_path.display()))?
+        .read_to_string(&mut content)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+// Pack paths come from a downloaded file, never let one escape the game dir
+fn safe_relative(path: &str) -> Option<PathBuf> {
+    let p = Path::new(path);
+    p.components().all(|c| matches!(c, Component::Normal(_))).then(|| p.to_path_buf())
+}
+
+fn file_sha1(path: &Path) -> Option<String> {
+    crate::utils::sha1_file(path).ok()
+}
+
+fn override_entries(archive: &mut ZipArchive<File>) -> Result<Vec<(String, Vec<u8>)>> {
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let Some(relative) = OVERRIDE_DIRS.iter().find_map(|dir| name.strip_prefix(dir)) else { continue };
+        let relative = relative.to_string();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        entries.push((relative, bytes));
+    }
+    Ok(entries)
+}
+
+pub fn plan(pack_path: &Path, index: &PackIndex, game_dir: &Path) -> Result<PackPlan> {
+    let installed = InstalledPack::load(game_dir);
+    let mut plan = PackPlan::default();
+
+    let new_files: HashMap<&str, &PackFile> = index.files.iter()
+        .filter(|f| f.wanted_on_client())
+        .map(|f| (f.path.as_str(), f))
+        .collect();
+
+    for file in new_files.values() {
+        let target = safe_relative(&file.path).ok_or_else(|| anyhow!("Unsafe path in modpack: {}", file.path))?;
+        let on_disk = file_sha1(&game_dir.join(target));
+        match (on_disk, file.sha1()) {
+            (Some(actual), Some(expected)) if actual.eq_ignore_ascii_case(expected) => plan.unchanged += 1,
+            _ => plan.download.push((*file).clone()),
+        }
+    }
+
+    if let Some(installed) = &installed {
+        plan.remove = installed.index.files.iter()
+            .map(|f| f.path.clone())
+            .filter(|path| !new_files.contains_key(path.as_str()))
+            .filter(|path| safe_relative(path).is_some())
+            .collect();
+    }
+
+    let mut archive = ZipArchive::new(File::open(pack_path)?)?;
+    for (relative, bytes) in override_entries(&mut archive)? {
+        let target = game_dir.join(safe_relative(&relative).ok_or_else(|| anyhow!("Unsafe path in modpack: {}", relative))?);
+        let new_hash = crate::utils::sha1_hex(&bytes);
+        let Some(current) = file_sha1(&target) else {
+            plan.overrides.push(relative);
+            continue;
+        };
+        if current == new_hash {
+            continue;
+        }
+        // Untouched since we wrote it, safe to replace. Anything else is the user's.
+        let ours = installed.as_ref().and_then(|i| i.overrides.get(&relative)).is_some_and(|h| *h == current);
+        if ours {
+            plan.overrides.push(relative);
+        } else {
+            plan.conflicts.push(relative);
+        }
+    }
+
+    Ok(plan)
+}
+
+async fn download_verified(file: &PackFile, target: &Path) -> Result<()> {
+    let mut last_error = anyhow!("No download URL for {}", file.path);
+    for url in &file.downloads {
+        let bytes = match reqwest::get(url).await.and_then(|r| r.error_for_status()) {
+            Ok(response) => response.bytes().await?,
+            Err(e) => {
+                last_error = e.into();
+                continue;
+            }
+        };
+        if file.sha1().is_some_and(|expected| !crate::utils::sha1_hex(&bytes).eq_ignore_ascii_case(expected)) {
+            last_error = anyhow!("Checksum mismatch for {}", file.path);
+            continue;
+        }
+        if let Some(parent) = target.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let target = target.to_path_buf();
+        tokio::task::spawn_blocking(move || crate::utils::write_atomic(&target, &bytes)).await??;
+        return Ok(());
+    }
+    Err(last_error)
+}
+
+// Applies a plan from `plan()`. Conflicting overrides are only written when `overwrite_conflicts`.
+pub async fn apply<F>(pack_path: &Path, index: &PackIndex, plan: &PackPlan, game_dir: &Path, overwrite_conflicts: bool, on_progress: F) -> Result<()>
+where F: Fn(f64, String)
+{
+    let total = plan.download.len().max(1) as f64;
+    for (i, file) in plan.download.iter().enumerate() {
+        on_progress(i as f64 / total * 0.9, format!("Downloading {}", file.path));
+        let target = game_dir.join(safe_relative(&file.path).ok_or_else(|| anyhow!("Unsafe path in modpack: {}", file.path))?);
+        download_verified(file, &target).await?;
+    }
+
+    for path in &plan.remove {
+        if let Some(relative) = safe_relative(path) {
+            let _ = tokio::fs::remove_file(game_dir.join(relative)).await;
+        }
+    }
+
+    on_progress(0.95, "Copying overrides...".into());
+    let previous = InstalledPack::load(game_dir);
+    let mut recorded = HashMap::new();
+    let mut archive = ZipArchive::new(File::open(pack_path)?)?;
+    for (relative, bytes) in override_entries(&mut archive)? {
+        let Some(target) = safe_relative(&relative).map(|p| game_dir.join(p)) else { continue };
+        let write = plan.overrides.contains(&relative) || (overwrite_conflicts && plan.conflicts.contains(&relative));
+        if write {
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            crate::utils::write_atomic(&target, &bytes)?;
+            recorded.insert(relative, crate::utils::sha1_hex(&bytes));
+        } else if let Some(hash) = previous.as_ref().and_then(|p| p.overrides.get(&relative)) {
+            // Kept the user's copy, remember our last version so it stays a conflict next time
+            recorded.insert(relative, hash.clone());
+        } else if file_sha1(&target).as_deref() == Some(crate::utils::sha1_hex(&bytes).as_str()) {
+            recorded.insert(relative, crate::utils::sha1_hex(&bytes));
+        }
+    }
+
+    InstalledPack { index: index.clone(), overrides: recorded }.save(game_dir)?;
+    on_progress(1.0, "Modpack installed".into());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn pack_file(path: &str, content: &[u8]) -> PackFile {
+        PackFile {
+            path: path.to_string(),
+            hashes: HashMap::from([("sha1".to_string(), crate::utils::sha1_hex(content))]),
+            env: None,
+            downloads: vec![format!("https://cdn.example/{}", path)],
+        }
+    }
+
+    fn index(files: Vec<PackFile>) -> PackIndex {
+        PackIndex {
+            name: "Test Pack".to_string(),
+            version_id: "1.0.0".to_string(),
+            files,
+            dependencies: HashMap::from([("minecraft".to_string(), "1.20.1".to_string())]),
+        }
+    }
+
+    fn write_pack(path: &Path, index: &PackIndex, overrides: &[(&str, &[u8])]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        let options = zip::write::FileOptions::default();
+        zip.start_file(INDEX_FILE, options).unwrap();
+        zip.write_all(serde_json::to_string(index).unwrap().as_bytes()).unwrap();
+        for (name, bytes) in overrides {
+            zip.start_file(format!("overrides/{}", name), options).unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn put(game_dir: &Path, relative: &str, bytes: &[u8]) {
+        let path = game_dir.join(relative);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    #[test]
+    fn paths_outside_the_game_dir_are_rejected() {
+        assert_eq!(safe_relative("mods/sodium.jar"), Some(PathBuf::from("mods/sodium.jar")));
+        assert_eq!(safe_relative("../outside.jar"), None);
+        assert_eq!(safe_relative("mods/../../outside.jar"), None);
+        assert_eq!(safe_relative("/home/steve/.bashrc"), None);
+        assert_eq!(safe_relative("./mods/sodium.jar"), None);
+    }
+
+    #[test]
+    fn plan_downloads_removes_and_keeps() {
+        let tmp = tempfile::tempdir().unwrap();
+        let game_dir = tmp.path().join("game");
+        let pack_path = tmp.path().join("pack.mrpack");
+
+        put(&game_dir, "mods/keep.jar", b"keep");
+        put(&game_dir, "mods/old.jar", b"old");
+        put(&game_dir, "mods/outdated.jar", b"v1");
+        // Written by the last install and untouched since, vs. edited by the user
+        put(&game_dir, "config/ours.txt", b"ours v1");
+        put(&game_dir, "config/edited.txt", b"user edit");
+        InstalledPack {
+            index: index(vec![pack_file("mods/keep.jar", b"keep"), pack_file("mods/old.jar", b"old"), pack_file("mods/outdated.jar", b"v1")]),
+            overrides: HashMap::from([
+                ("config/ours.txt".to_string(), crate::utils::sha1_hex(b"ours v1")),
+                ("config/edited.txt".to_string(), crate::utils::sha1_hex(b"pack v1")),
+            ]),
+        }.save(&game_dir).unwrap();
+
+        let mut server_only = pack_file("mods/server.jar", b"server");
+        server_only.env = Some(PackFileEnv { client: "unsupported".to_string() });
+        let new = index(vec![pack_file("mods/keep.jar", b"keep"), pack_file("mods/outdated.jar", b"v2"), pack_file("mods/new.jar", b"new"), server_only]);
+        write_pack(&pack_path, &new, &[("config/ours.txt", b"ours v2"), ("config/edited.txt", b"pack v2"), ("config/fresh.txt", b"fresh")]);
+
+        let mut plan = plan(&pack_path, &new, &game_dir).unwrap();
+        let mut downloads: Vec<&str> = plan.download.iter().map(|f| f.path.as_str()).collect();
+        downloads.sort();
+        plan.overrides.sort();
+        assert_eq!(downloads, ["mods/new.jar", "mods/outdated.jar"]);
+        assert_eq!(plan.remove, ["mods/old.jar"]);
+        assert_eq!(plan.unchanged, 1);
+        assert_eq!(plan.overrides, ["config/fresh.txt", "config/ours.txt"]);
+        assert_eq!(plan.conflicts, ["config/edited.txt"]);
+    }
+
+    #[test]
+    fn plan_refuses_unsafe_file_paths() {
+        let tmp = tempfile::tempdir().unwrap();
+        let pack_path = tmp.path().join("pack.mrpack");
+        let evil = index(vec![pack_file("../../.bashrc", b"evil")]);
+        write_pack(&pack_path, &evil, &[]);
+        assert!(plan(&pack_path, &evil, &tmp.path().join("game")).is_err());
+
+        let evil_override = index(Vec::new());
+        write_pack(&pack_path, &evil_override, &[("../escape.txt", b"evil")]);
+        assert!(plan(&pack_path, &evil_override, &tmp.path().join("game")).is_err());
+    }
+}
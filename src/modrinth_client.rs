@@ -1,11 +1,97 @@
 use reqwest::Client;
-use std::path::PathBuf;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 use std::fs::File;
 use std::io::Write;
-use crate::models::{ModSearchResult, ModVersion};
+use crate::models::{is_newer_version, pick_mod_version, ModProject, ModSearchResult, ModVersion, ProjectType};
+use crate::mod_index::{InstalledMod, ModIndex};
 
 const MODRINTH_API_URL: &str = "https://api.modrinth.com/v2";
 
+// Search results and version lists from successful requests, served when Modrinth is unreachable
+fn response_cache_dir() -> PathBuf {
+    crate::config::cache_dir().join("modrinth")
+}
+
+fn cache_path(key: &str) -> PathBuf {
+    response_cache_dir().join(format!("{}.json", crate::utils::sha1_hex(key.as_bytes())))
+}
+
+fn read_cache<T: serde::de::DeserializeOwned>(key: &str) -> Option<T> {
+    let content = std::fs::read(cache_path(key)).ok()?;
+    serde_json::from_slice(&content).ok()
+}
+
+fn write_cache<T: serde::Serialize>(key: &str, value: &T) {
+    let Ok(json) = serde_json::to_vec(value) else { return };
+    let _ = std::fs::create_dir_all(response_cache_dir());
+    if let Err(e) = crate::utils::write_atomic(&cache_path(key), &json) {
+        tracing::debug!("Could not cache Modrinth response: {}", e);
+    }
+}
+
+// What a pasted project URL points at, from its first path segment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkKind {
+    Project(ProjectType),
+    Modpack,
+    Plugin,
+}
+
+// Slug or id from what a user pasted: a bare slug, or a project URL such as
+// https://modrinth.com/mod/sodium/versions. The kind is None for bare slugs and /project/
+// links, which don't say.
+pub fn parse_project_ref(input: &str) -> Option<(String, Option<LinkKind>)> {
+    let input = input.trim().trim_end_matches('/');
+    let (candidate, kind) = match input.split_once("modrinth.com/") {
+        Some((_, path)) => {
+            let mut segments = path.split(['/', '?', '#']);
+            let kind = match segments.next()? {
+                "project" => None,
+                "mod" => Some(LinkKind::Project(ProjectType::Mod)),
+                "resourcepack" => Some(LinkKind::Project(ProjectType::ResourcePack)),
+                "shader" => Some(LinkKind::Project(ProjectType::Shader)),
+                "datapack" => Some(LinkKind::Project(ProjectType::Datapack)),
+                "modpack" => Some(LinkKind::Modpack),
+                "plugin" => Some(LinkKind::Plugin),
+                _ => return None,
+            };
+            (segments.next()?, kind)
+        }
+        None => (input, None),
+    };
+    let valid = !candidate.is_empty() && candidate.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+    valid.then(|| (candidate.to_string(), kind))
+}
+
+// A response, possibly an older cached copy because the live request failed
+#[derive(Debug, Clone)]
+pub struct Fetched<T> {
+    pub data: T,
+    pub stale: bool,
+}
+
+// Result of installing a set of mods together with their required dependencies
+#[derive(Debug, Default)]
+pub struct ModInstallOutcome {
+    pub installed: Vec<(String, InstalledMod)>,
+    // Requested or required projects without a file for this version/loader
+    pub unavailable: Vec<String>,
+    // Projects whose lookup or download failed, with the error
+    pub failed: Vec<(String, String)>,
+}
+
+// Result of looking for newer versions of the jars in a mods dir
+#[derive(Debug, Default)]
+pub struct ModUpdateCheck {
+    // Jars the mod index didn't know yet, matched to their project by hash
+    pub identified: Vec<(String, InstalledMod)>,
+    // Installed filename -> newer compatible version
+    pub updates: Vec<(String, ModVersion)>,
+    // Jars that couldn't be checked, with why
+    pub failed: Vec<(String, String)>,
+}
+
 #[derive(Clone)]
 pub struct ModrinthClient {
     client: Client,
@@ -21,7 +107,17 @@ impl ModrinthClient {
         }
     }
 
-    pub async fn search_mods(&self, query: &str, limit: u32, version: Option<&str>, loader: Option<&str>) -> Result<Vec<ModSearchResult>, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn search_mods(&self, query: &str, limit: u32, project_type: ProjectType, version: Option<&str>, loader: Option<&str>) -> Result<Fetched<Vec<ModSearchResult>>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search(query, "relevance", limit, project_type, version, loader).await
+    }
+
+    // Most downloaded projects, what the browse list shows before anything is searched
+    pub async fn popular_mods(&self, limit: u32, project_type: ProjectType, version: Option<&str>, loader: Option<&str>) -> Result<Fetched<Vec<ModSearchResult>>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search("", "downloads", limit, project_type, version, loader).await
+    }
+
+    // `loader` is ignored for project types that work with any loader
+    async fn search(&self, query: &str, index: &str, limit: u32, project_type: ProjectType, version: Option<&str>, loader: Option<&str>) -> Result<Fetched<Vec<ModSearchResult>>, Box<dyn std::error::Error + Send + Sync>> {
         let url = format!("{}/search", MODRINTH_API_URL);
 
         #[derive(serde::Deserialize)]
@@ -29,15 +125,14 @@ impl ModrinthClient {
             hits: Vec<ModSearchResult>,
         }
 
-        let retries = 3;
-        let mut delay = 1;
+        let retries = crate::http::max_retries();
         let mut last_error = None;
 
-        let mut facets = Vec::new();
+        let mut facets = vec![format!("project_type:{}", project_type.id())];
         if let Some(v) = version {
             facets.push(format!("versions:{}", v));
         }
-        if let Some(l) = loader {
+        if let Some(l) = loader.filter(|_| project_type.is_loader_specific()) {
             facets.push(format!("categories:{}", l));
         }
 
@@ -47,10 +142,11 @@ impl ModrinthClient {
         } else {
              String::new()
         };
+        let cache_key = format!("search|{}|{}|{}|{}", query, index, limit, facets_json);
 
-        for _ in 0..=retries {
+        for attempt in 0..=retries {
              let mut request = self.client.get(&url)
-                .query(&[("query", query), ("limit", &limit.to_string())]);
+                .query(&[("query", query), ("index", index), ("limit", &limit.to_string())]);
 
              if !facets_json.is_empty() {
                  request = request.query(&[("facets", &facets_json)]);
@@ -60,12 +156,14 @@ impl ModrinthClient {
                 Ok(response) => {
                     if response.status().is_success() {
                          let resp = response.json::<SearchResponse>().await?;
-                         return Ok(resp.hits);
+                         write_cache(&cache_key, &resp.hits);
+                         return Ok(Fetched { data: resp.hits, stale: false });
                     } else if response.status().is_server_error() {
                         // 5xx error, retry
                         let status = response.status();
                         if status.as_u16() == 503 {
-                            return Err("Modrinth Service Unavailable (503). Please try again later.".into());
+                            last_error = Some("Modrinth Service Unavailable (503). Please try again later.".to_string());
+                            break;
                         }
                         let text = response.text().await.unwrap_or_default();
                         last_error = Some(format!("Modrinth API error: {} - {}", status, text));
@@ -84,19 +182,24 @@ impl ModrinthClient {
                 }
             }
 
-            tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-            delay *= 2;
+            if attempt < retries {
+                tokio::time::sleep(crate::http::backoff_delay(attempt)).await;
+            }
         }
 
+        if let Some(cached) = read_cache::<Vec<ModSearchResult>>(&cache_key) {
+            tracing::warn!("Modrinth search failed, showing cached results: {}", last_error.unwrap_or_default());
+            return Ok(Fetched { data: cached, stale: true });
+        }
         Err(last_error.unwrap_or_else(|| "Unknown error".to_string()).into())
     }
 
     pub async fn get_versions(&self, project_id: &str, loader: Option<&str>, game_version: Option<&str>) -> Result<Vec<ModVersion>, Box<dyn std::error::Error + Send + Sync>> {
-        let retries = 3;
-        let mut delay = 1;
+        let retries = crate::http::max_retries();
         let mut last_error = None;
+        let cache_key = format!("versions|{}|{:?}|{:?}", project_id, loader, game_version);
 
-        for _ in 0..=retries {
+        for attempt in 0..=retries {
              let url = format!("{}/project/{}/version", MODRINTH_API_URL, project_id);
              let mut request = self.client.get(&url);
 
@@ -114,11 +217,13 @@ impl ModrinthClient {
                 Ok(response) => {
                      if response.status().is_success() {
                          let resp = response.json::<Vec<ModVersion>>().await?;
+                         write_cache(&cache_key, &resp);
                          return Ok(resp);
                      } else if response.status().is_server_error() {
                          let status = response.status();
                          if status.as_u16() == 503 {
-                            return Err("Modrinth Service Unavailable (503). Please try again later.".into());
+                            last_error = Some("Modrinth Service Unavailable (503). Please try again later.".to_string());
+                            break;
                          }
                          let text = response.text().await.unwrap_or_default();
                          last_error = Some(format!("Modrinth API error: {} - {}", status, text));
@@ -136,29 +241,236 @@ impl ModrinthClient {
                 }
             }
 
-            tokio::time::sleep(std::time::Duration::from_secs(delay)).await;
-            delay *= 2;
+            if attempt < retries {
+                tokio::time::sleep(crate::http::backoff_delay(attempt)).await;
+            }
         }
 
+        // Version lists change rarely, an older copy still installs the right file
+        if let Some(cached) = read_cache::<Vec<ModVersion>>(&cache_key) {
+            tracing::warn!("Modrinth version lookup for {} failed, using cached list: {}", project_id, last_error.unwrap_or_default());
+            return Ok(cached);
+        }
         Err(last_error.unwrap_or_else(|| "Unknown error".to_string()).into())
     }
 
-    pub async fn download_mod(&self, url: &str, destination: &PathBuf) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn get_project(&self, id_or_slug: &str) -> Result<ModProject, Box<dyn std::error::Error + Send + Sync>> {
+        let url = format!("{}/project/{}", MODRINTH_API_URL, id_or_slug);
+        let response = self.client.get(&url).send().await?;
+
+        if response.status().as_u16() == 404 {
+            return Err(format!("Project '{}' not found on Modrinth", id_or_slug).into());
+        }
+        if !response.status().is_success() {
+            return Err(format!("Modrinth API error: {}", response.status()).into());
+        }
+
+        Ok(response.json::<ModProject>().await?)
+    }
+
+    // The versions files were published as, keyed by their sha1, in one request. Files Modrinth
+    // doesn't host are left out of the map.
+    pub async fn versions_from_hashes(&self, hashes: &[String]) -> Result<HashMap<String, ModVersion>, Box<dyn std::error::Error + Send + Sync>> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let url = format!("{}/version_files", MODRINTH_API_URL);
+        let body = serde_json::json!({ "hashes": hashes, "algorithm": "sha1" });
+        let response = self.client.post(&url).json(&body).send().await?;
+
+        if !response.status().is_success() {
+            return Err(format!("Modrinth API error: {}", response.status()).into());
+        }
+
+        Ok(response.json::<HashMap<String, ModVersion>>().await?)
+    }
+
+    // Finds newer versions of `jars` (filenames in `mods_dir`) for this loader and game version.
+    // Every jar is identified by hash in one request, which also tells which version is installed
+    // right now; jars Modrinth doesn't know are skipped. A jar that can't be read or whose
+    // project can't be looked up lands in `failed` without stopping the others.
+    pub async fn check_updates(
+        &self,
+        mods_dir: &Path,
+        jars: &[String],
+        index: &ModIndex,
+        loader: Option<&str>,
+        game_version: Option<&str>,
+        prefer_stable: bool,
+    ) -> Result<ModUpdateCheck, Box<dyn std::error::Error + Send + Sync>> {
+        let mut check = ModUpdateCheck::default();
+
+        let dir = mods_dir.to_path_buf();
+        let names = jars.to_vec();
+        let hashed = tokio::task::spawn_blocking(move || {
+            names.into_iter()
+                .map(|filename| {
+                    let hash = crate::utils::sha1_file(&dir.join(&filename));
+                    (filename, hash)
+                })
+                .collect::<Vec<_>>()
+        }).await?;
+
+        let mut jar_hashes = Vec::new();
+        for (filename, hash) in hashed {
+            match hash {
+                Ok(hash) => jar_hashes.push((filename, hash)),
+                Err(e) => check.failed.push((filename, e.to_string())),
+            }
+        }
+        let hashes: Vec<String> = jar_hashes.iter().map(|(_, hash)| hash.clone()).collect();
+        let installed = self.versions_from_hashes(&hashes).await?;
+
+        for (filename, hash) in jar_hashes {
+            let Some(current) = installed.get(&hash) else { continue };
+
+            if index.project_for_file(&filename).is_none() {
+                check.identified.push((current.project_id.clone(), InstalledMod {
+                    filename: filename.clone(),
+                    auto_installed: false,
+                    dependencies: current.dependencies.iter()
+                        .filter(|d| d.dependency_type == "required")
+                        .filter_map(|d| d.project_id.clone())
+                        .collect(),
+                }));
+            }
+
+            let versions = match self.get_versions(&current.project_id, loader, game_version).await {
+                Ok(versions) => versions,
+                Err(e) => {
+                    check.failed.push((filename, e.to_string()));
+                    continue;
+                }
+            };
+            let Some(latest) = pick_mod_version(&versions, prefer_stable) else { continue };
+            if is_newer_version(&versions, &latest.id, &current.id) {
+                check.updates.push((filename, latest.clone()));
+            }
+        }
+
+        Ok(check)
+    }
+
+    // Streams the file to disk, calling `on_progress` with the downloaded fraction whenever it
+    // passes another percent. Nothing is reported when the server doesn't send a length.
+    pub async fn download_mod<F>(&self, url: &str, destination: &PathBuf, on_progress: F) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+    where F: Fn(f64)
+    {
         if let Some(parent) = destination.parent() {
             tokio::fs::create_dir_all(parent).await?;
         }
 
-        let response = self.client.get(url).send().await?;
-        let bytes = response.bytes().await?;
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        let total_size = response.content_length().unwrap_or(0);
 
+        let mut stream = response.bytes_stream();
+        let mut downloaded: u64 = 0;
+        let mut last_percent = 0;
+        // A jar linked in from a shared folder, writing through it would overwrite the shared copy
+        if tokio::fs::symlink_metadata(destination).await.is_ok_and(|m| m.file_type().is_symlink()) {
+            tokio::fs::remove_file(destination).await?;
+        }
         let mut file = File::create(destination)?;
-        file.write_all(&bytes)?;
+
+        use futures::StreamExt;
+        while let Some(chunk) = stream.next().await {
+            let written = chunk.map_err(Into::into).and_then(|chunk| {
+                file.write_all(&chunk)?;
+                Ok::<_, Box<dyn std::error::Error + Send + Sync>>(chunk.len() as u64)
+            });
+            let len = match written {
+                Ok(len) => len,
+                Err(e) => {
+                    // Don't leave a truncated jar for the game to choke on
+                    drop(file);
+                    let _ = std::fs::remove_file(destination);
+                    return Err(e);
+                }
+            };
+            downloaded += len;
+
+            // Stays 0 without a length
+            let percent = (downloaded * 100).checked_div(total_size).unwrap_or(0);
+            if percent > last_percent {
+                last_percent = percent;
+                on_progress(downloaded as f64 / total_size as f64);
+            }
+        }
 
         Ok(())
     }
 
 
 
+    // Installs `project_ids` and, with `resolve_dependencies`, recursively their required
+    // dependencies into `mods_dir`. Anything in `already_installed` is skipped; a visited set
+    // keeps circular graphs finite.
+    // `on_progress` gets the project being downloaded and its progress, see download_mod.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn install_with_dependencies<F>(
+        &self,
+        project_ids: &[String],
+        mods_dir: &Path,
+        loader: Option<&str>,
+        game_version: Option<&str>,
+        already_installed: &HashSet<String>,
+        prefer_stable: bool,
+        resolve_dependencies: bool,
+        on_progress: F,
+    ) -> ModInstallOutcome
+    where F: Fn(&str, f64)
+    {
+        let mut outcome = ModInstallOutcome::default();
+        let mut visited: HashSet<String> = already_installed.clone();
+        let mut queue: VecDeque<(String, bool)> = project_ids.iter().map(|id| (id.clone(), false)).collect();
+
+        while let Some((project_id, auto_installed)) = queue.pop_front() {
+            if !visited.insert(project_id.clone()) {
+                continue;
+            }
+
+            // A failure skips this project and its dependencies but keeps what already made it to
+            // disk, so it still gets into the mod index
+            let versions = match self.get_versions(&project_id, loader, game_version).await {
+                Ok(versions) => versions,
+                Err(e) => {
+                    outcome.failed.push((project_id, e.to_string()));
+                    continue;
+                }
+            };
+            let Some(version) = pick_mod_version(&versions, prefer_stable) else {
+                outcome.unavailable.push(project_id);
+                continue;
+            };
+            let Some(file) = version.files.iter().find(|f| f.primary).or(version.files.first()) else {
+                outcome.unavailable.push(project_id);
+                continue;
+            };
+
+            if let Err(e) = self.download_mod(&file.url, &mods_dir.join(&file.filename), |fraction| on_progress(&project_id, fraction)).await {
+                outcome.failed.push((project_id, e.to_string()));
+                continue;
+            }
+
+            let dependencies: Vec<String> = version.dependencies.iter()
+                .filter(|_| resolve_dependencies)
+                .filter(|d| d.dependency_type == "required")
+                .filter_map(|d| d.project_id.clone())
+                .collect();
+            for dep in &dependencies {
+                queue.push_back((dep.clone(), true));
+            }
+
+            outcome.installed.push((project_id, InstalledMod {
+                filename: file.filename.clone(),
+                auto_installed,
+                dependencies,
+            }));
+        }
+
+        outcome
+    }
+
     pub async fn download_icon_bytes(&self, url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error + Send + Sync>> {
         let response = self.client.get(url).send().await?;
         let bytes = response.bytes().await?;
@@ -0,0 +1,39 @@
+// Profiles shared between installs: a profiles.json (same format RCraft saves) on its own or
+// inside a zip. Only the profile definitions travel, instances stay where they are.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+use crate::models::Profile;
+
+const PROFILES_FILE: &str = "profiles.json";
+
+pub fn read(path: &Path) -> Result<Vec<Profile>> {
+    let content = if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip")) {
+        let mut archive = ZipArchive::new(File::open(path)?)?;
+        let mut content = String::new();
+        archive.by_name(PROFILES_FILE)
+            .map_err(|_| anyhow!("{} has no {}", path.display(), PROFILES_FILE))?
+            .read_to_string(&mut content)?;
+        content
+    } else {
+        std::fs::read_to_string(path)?
+    };
+
+    let profiles: HashMap<String, Profile> = serde_json::from_str(&content)
+        .map_err(|e| anyhow!("{} is not an RCraft profile list: {}", path.display(), e))?;
+    let mut profiles: Vec<Profile> = profiles.into_values().collect();
+    profiles.sort_by(|a, b| (&a.username, &a.version).cmp(&(&b.username, &b.version)));
+    Ok(profiles)
+}
+
+// Whether a zip looks like a profile bundle rather than e.g. a modpack
+pub fn is_bundle(path: &Path) -> bool {
+    File::open(path).ok()
+        .and_then(|file| ZipArchive::new(file).ok())
+        .is_some_and(|mut archive| archive.by_name(PROFILES_FILE).is_ok())
+}
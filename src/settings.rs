@@ -2,32 +2,166 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use tokio::fs;
 
-use crate::models::Theme;
+use crate::models::{JavaVendor, LogLevel, ProfileSort, Theme};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Settings {
     pub theme: Theme,
+    // Translucent window background, on top of whichever theme is selected
+    #[serde(default)]
+    pub transparent: bool,
     pub hide_logs: bool,
     pub sidebar_collapsed: bool,
     pub hide_mods_button: bool,
+    #[serde(default)]
+    pub java_vendor: JavaVendor,
+    #[serde(default = "default_true")]
+    pub verify_files: bool,
+    // None keeps instances in .minecraft/instances
+    #[serde(default)]
+    pub instances_dir: Option<String>,
+    // Replaces ~/.minecraft (versions, assets, libraries, runtimes), None uses the default
+    #[serde(default)]
+    pub custom_data_dir: Option<String>,
+    // Warn about missing GL/X11 libraries before launching
+    #[serde(default)]
+    pub check_system_libraries: bool,
+    // What to do with the launcher window once the game is up, at most one of these is set
+    #[serde(default)]
+    pub minimize_on_launch: bool,
+    #[serde(default)]
+    pub quit_on_launch: bool,
+    #[serde(default)]
+    pub log_level: LogLevel,
+    // Render ANSI colors from game output, otherwise the codes are just stripped
+    #[serde(default = "default_true")]
+    pub colorize_logs: bool,
+    // Prefix game output with the local time it was received
+    #[serde(default = "default_true")]
+    pub log_timestamps: bool,
+    // Network retries, see http.rs
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_max_backoff_secs")]
+    pub max_backoff_secs: u64,
+    #[serde(default = "default_download_concurrency")]
+    pub download_concurrency: usize,
+    // Game log lines kept on the Logs page, older ones are dropped
+    #[serde(default = "default_max_log_lines")]
+    pub max_log_lines: usize,
+    // options.txt for instances launched for the first time
+    #[serde(default)]
+    pub options_template: Option<String>,
+    // Starred on the create page, see the Favorites version filter
+    #[serde(default)]
+    pub favorite_versions: Vec<String>,
+    // Pre-filled in the create form for new profiles
+    #[serde(default)]
+    pub default_username: Option<String>,
+    #[serde(default = "default_ram_mb")]
+    pub default_ram_mb: u32,
+    // Share of the system's memory new profiles get instead of default_ram_mb
+    #[serde(default)]
+    pub ram_percent: Option<u32>,
+    // Skip beta and alpha builds when picking which version of a mod to install
+    #[serde(default = "default_true")]
+    pub prefer_stable_mods: bool,
+    // Order of the home page's profile list
+    #[serde(default)]
+    pub profile_sort: ProfileSort,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_max_retries() -> u32 {
+    crate::http::DEFAULT_MAX_RETRIES
+}
+
+fn default_max_backoff_secs() -> u64 {
+    crate::http::DEFAULT_MAX_BACKOFF_SECS
+}
+
+fn default_download_concurrency() -> usize {
+    crate::http::DEFAULT_DOWNLOAD_CONCURRENCY
+}
+
+pub const DEFAULT_RAM_MB: u32 = 4096;
+// Smallest heap the create form offers
+pub const MIN_RAM_MB: u32 = 2048;
+
+fn default_ram_mb() -> u32 {
+    DEFAULT_RAM_MB
+}
+
+pub const DEFAULT_MAX_LOG_LINES: usize = 5000;
+
+fn default_max_log_lines() -> usize {
+    DEFAULT_MAX_LOG_LINES
+}
+
+// "Transparent" used to be a fourth theme that always meant dark
+fn migrate_legacy_theme(mut value: serde_json::Value) -> serde_json::Value {
+    if value.get("theme").and_then(|t| t.as_str()) == Some("Transparent") {
+        value["theme"] = "Dark".into();
+        value["transparent"] = true.into();
+    }
+    value
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
             theme: Theme::System,
+            transparent: false,
             hide_logs: false,
             sidebar_collapsed: false,
             hide_mods_button: false,
+            java_vendor: JavaVendor::Temurin,
+            verify_files: true,
+            instances_dir: None,
+            custom_data_dir: None,
+            check_system_libraries: false,
+            minimize_on_launch: false,
+            quit_on_launch: false,
+            log_level: LogLevel::Info,
+            colorize_logs: true,
+            log_timestamps: true,
+            max_retries: crate::http::DEFAULT_MAX_RETRIES,
+            max_backoff_secs: crate::http::DEFAULT_MAX_BACKOFF_SECS,
+            download_concurrency: crate::http::DEFAULT_DOWNLOAD_CONCURRENCY,
+            max_log_lines: DEFAULT_MAX_LOG_LINES,
+            options_template: None,
+            favorite_versions: Vec::new(),
+            default_username: None,
+            default_ram_mb: DEFAULT_RAM_MB,
+            ram_percent: None,
+            prefer_stable_mods: true,
+            profile_sort: ProfileSort::LastPlayed,
         }
     }
 }
 
 impl Settings {
+    // Memory a new profile starts with on a machine with `total_mb`, kept within the create
+    // form's range. A share of the memory is rounded down to the form's 256 MB steps
+    pub fn new_profile_ram_mb(&self, total_mb: u64) -> u32 {
+        let wanted = match self.ram_percent {
+            Some(percent) => (total_mb * percent as u64 / 100) as u32 / 256 * 256,
+            None => self.default_ram_mb,
+        };
+        let max = (total_mb as u32).max(MIN_RAM_MB);
+        wanted.clamp(MIN_RAM_MB, max)
+    }
+
     pub async fn load(config_dir: &PathBuf) -> Self {
         let path = config_dir.join("settings.json");
         if let Ok(content) = fs::read_to_string(&path).await {
-            serde_json::from_str(&content).unwrap_or_default()
+            serde_json::from_str(&content)
+                .map(migrate_legacy_theme)
+                .and_then(serde_json::from_value)
+                .unwrap_or_default()
         } else {
             Self::default()
         }
@@ -42,3 +176,111 @@ impl Settings {
         fs::write(path, json).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_config_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("rcraft-settings-test-{}", uuid::Uuid::new_v4()))
+    }
+
+    // Every field away from its default, so a field that doesn't survive the trip shows up
+    fn populated() -> Settings {
+        Settings {
+            theme: Theme::Light,
+            transparent: true,
+            hide_logs: true,
+            sidebar_collapsed: true,
+            hide_mods_button: true,
+            java_vendor: JavaVendor::Zulu,
+            verify_files: false,
+            instances_dir: Some("/games/instances".to_string()),
+            custom_data_dir: Some("/games/minecraft".to_string()),
+            check_system_libraries: true,
+            minimize_on_launch: true,
+            quit_on_launch: false,
+            log_level: LogLevel::Trace,
+            colorize_logs: false,
+            log_timestamps: false,
+            max_retries: 7,
+            max_backoff_secs: 30,
+            download_concurrency: 16,
+            max_log_lines: 20000,
+            options_template: Some("/games/options.txt".to_string()),
+            favorite_versions: vec!["1.20.1".to_string(), "1.8.9".to_string()],
+            default_username: Some("Steve".to_string()),
+            default_ram_mb: 6144,
+            ram_percent: Some(40),
+            prefer_stable_mods: false,
+            profile_sort: ProfileSort::MostPlayed,
+        }
+    }
+
+    #[tokio::test]
+    async fn save_and_load_round_trip() {
+        let dir = temp_config_dir();
+        let settings = populated();
+        settings.save(&dir).await.unwrap();
+        let loaded = Settings::load(&dir).await;
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(loaded, settings);
+    }
+
+    #[tokio::test]
+    async fn missing_file_loads_defaults() {
+        assert_eq!(Settings::load(&temp_config_dir()).await, Settings::default());
+    }
+
+    #[tokio::test]
+    async fn old_settings_get_defaults_for_new_fields() {
+        let dir = temp_config_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("settings.json"), r#"{
+            "theme": "Light",
+            "hide_logs": true,
+            "sidebar_collapsed": false,
+            "hide_mods_button": true
+        }"#).unwrap();
+        let loaded = Settings::load(&dir).await;
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded, Settings {
+            theme: Theme::Light,
+            hide_logs: true,
+            hide_mods_button: true,
+            ..Settings::default()
+        });
+    }
+
+    #[test]
+    fn new_profile_ram_uses_share_of_system_memory() {
+        let mut settings = Settings { default_ram_mb: 6144, ..Settings::default() };
+        assert_eq!(settings.new_profile_ram_mb(16384), 6144);
+        // Never more than the machine has, never less than the form allows
+        assert_eq!(settings.new_profile_ram_mb(4000), 4000);
+        settings.default_ram_mb = 1024;
+        assert_eq!(settings.new_profile_ram_mb(16384), MIN_RAM_MB);
+
+        settings.ram_percent = Some(25);
+        assert_eq!(settings.new_profile_ram_mb(16000), 3840);
+        assert_eq!(settings.new_profile_ram_mb(4096), MIN_RAM_MB);
+    }
+
+    #[tokio::test]
+    async fn transparent_theme_becomes_dark_and_transparent() {
+        let dir = temp_config_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("settings.json"), r#"{
+            "theme": "Transparent",
+            "hide_logs": false,
+            "sidebar_collapsed": false,
+            "hide_mods_button": false
+        }"#).unwrap();
+        let loaded = Settings::load(&dir).await;
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(loaded.theme, Theme::Dark);
+        assert!(loaded.transparent);
+    }
+}
@@ -0,0 +1,144 @@
+// Extra mod folders a profile pulls in on top of its own mods dir, e.g. a set of library mods
+// kept once for several packs. Their jars are linked into the mods dir right before launch
+// (copied where symlinks aren't available). What was linked is recorded next to the jars so the
+// next launch can take it back out before linking the current set.
+
+use anyhow::Result;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+const LINKS_FILE: &str = ".rcraft-shared-mods.json";
+
+#[derive(Debug, Default)]
+pub struct LinkReport {
+    pub linked: usize,
+    // Jars left out because the profile or an earlier folder already has one by that name
+    pub conflicts: Vec<String>,
+}
+
+// Filename -> folder it was linked from
+fn load_links(mods_dir: &Path) -> BTreeMap<String, String> {
+    std::fs::read_to_string(mods_dir.join(LINKS_FILE)).ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn jars_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut jars: Vec<String> = entries.flatten()
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| name.ends_with(".jar"))
+        .collect();
+    jars.sort();
+    jars
+}
+
+// Jars in the mods dir that came from a shared folder rather than the profile itself
+pub fn linked_names(mods_dir: &Path) -> HashSet<String> {
+    load_links(mods_dir).into_keys().collect()
+}
+
+// Whether `path` is still a link we made. A jar the user put in its place since is theirs.
+// Copies (no symlinks) can't be told apart from the user's own files.
+fn is_our_link(path: &Path) -> bool {
+    #[cfg(target_family = "unix")]
+    return std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_symlink());
+    #[cfg(not(target_family = "unix"))]
+    return path.exists();
+}
+
+// Replaces the previous launch's links with the jars of `sources`. An empty list just
+// cleans up. Folders earlier in the list win over later ones.
+pub fn link(mods_dir: &Path, sources: &[PathBuf]) -> Result<LinkReport> {
+    let previous = load_links(mods_dir);
+    for name in previous.keys() {
+        let path = mods_dir.join(name);
+        if is_our_link(&path) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+
+    let mut report = LinkReport::default();
+    let mut links = BTreeMap::new();
+    if !sources.is_empty() {
+        std::fs::create_dir_all(mods_dir)?;
+    }
+    for source in sources {
+        for name in jars_in(source) {
+            if let Some(from) = links.get(&name) {
+                report.conflicts.push(format!("{} in {} (already linked from {})", name, source.display(), from));
+                continue;
+            }
+            let target = mods_dir.join(&name);
+            if std::fs::symlink_metadata(&target).is_ok() {
+                report.conflicts.push(format!("{} in {} (the profile has its own copy)", name, source.display()));
+                continue;
+            }
+
+            #[cfg(target_family = "unix")]
+            std::os::unix::fs::symlink(source.join(&name), &target)?;
+            #[cfg(not(target_family = "unix"))]
+            std::fs::copy(source.join(&name), &target)?;
+
+            links.insert(name, source.display().to_string());
+            report.linked += 1;
+        }
+    }
+
+    let path = mods_dir.join(LINKS_FILE);
+    if links.is_empty() {
+        let _ = std::fs::remove_file(path);
+    } else {
+        crate::utils::write_atomic(&path, serde_json::to_string_pretty(&links)?.as_bytes())?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn folder_with(root: &Path, name: &str, jars: &[&str]) -> PathBuf {
+        let dir = root.join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        for jar in jars {
+            std::fs::write(dir.join(jar), name).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn earlier_folders_and_the_profile_win() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mods_dir = folder_with(tmp.path(), "mods", &["own.jar"]);
+        let first = folder_with(tmp.path(), "first", &["lib.jar", "own.jar"]);
+        let second = folder_with(tmp.path(), "second", &["lib.jar", "extra.jar"]);
+
+        let report = link(&mods_dir, &[first.clone(), second]).unwrap();
+        assert_eq!(report.linked, 2);
+        assert_eq!(report.conflicts.len(), 2);
+        assert_eq!(std::fs::read_link(mods_dir.join("lib.jar")).unwrap(), first.join("lib.jar"));
+        assert_eq!(std::fs::read_to_string(mods_dir.join("own.jar")).unwrap(), "mods");
+        assert_eq!(linked_names(&mods_dir), HashSet::from(["lib.jar".to_string(), "extra.jar".to_string()]));
+    }
+
+    #[test]
+    fn relinking_removes_only_our_links() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mods_dir = folder_with(tmp.path(), "mods", &[]);
+        let shared = folder_with(tmp.path(), "shared", &["lib.jar", "replaced.jar"]);
+        link(&mods_dir, std::slice::from_ref(&shared)).unwrap();
+
+        // The user swaps one link for a jar of their own
+        std::fs::remove_file(mods_dir.join("replaced.jar")).unwrap();
+        std::fs::write(mods_dir.join("replaced.jar"), "user").unwrap();
+
+        let report = link(&mods_dir, &[]).unwrap();
+        assert_eq!(report.linked, 0);
+        assert!(std::fs::symlink_metadata(mods_dir.join("lib.jar")).is_err());
+        assert_eq!(std::fs::read_to_string(mods_dir.join("replaced.jar")).unwrap(), "user");
+        assert!(!mods_dir.join(LINKS_FILE).exists());
+        // The shared folder itself is never touched
+        assert_eq!(jars_in(&shared), ["lib.jar", "replaced.jar"]);
+    }
+}
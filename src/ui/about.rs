@@ -0,0 +1,43 @@
+use relm4::gtk;
+use adw::prelude::*;
+
+const REPO_URL: &str = "https://github.com/vdkvdev/rcraft";
+
+// Crates RCraft is built on, listed for attribution in the About window
+const BUILT_WITH: &[&str] = &[
+    "gtk4-rs https://gtk-rs.org",
+    "libadwaita-rs https://gitlab.gnome.org/World/Rust/libadwaita-rs",
+    "Relm4 https://relm4.org",
+    "Tokio https://tokio.rs",
+    "reqwest https://github.com/seanmonstar/reqwest",
+    "Serde https://serde.rs",
+    "anyhow https://github.com/dtolnay/anyhow",
+    "zip https://github.com/zip-rs/zip2",
+    "flate2 https://github.com/rust-lang/flate2-rs",
+    "tar https://github.com/alexcrichton/tar-rs",
+    "xz2 https://github.com/alexcrichton/xz2-rs",
+    "RustCrypto sha1 https://github.com/RustCrypto/hashes",
+    "image https://github.com/image-rs/image",
+    "futures-rs https://github.com/rust-lang/futures-rs",
+    "dirs https://github.com/dirs-dev/dirs-rs",
+    "open https://github.com/Byron/open-rs",
+];
+
+pub fn show_about_window(parent: &adw::ApplicationWindow) {
+    let about = adw::AboutWindow::builder()
+        .transient_for(parent)
+        .modal(true)
+        .application_name("RCraft")
+        .application_icon("dev.vdkv.RCraft")
+        .version(env!("CARGO_PKG_VERSION"))
+        .comments(env!("CARGO_PKG_DESCRIPTION"))
+        .developer_name("vdkvdev")
+        .developers(vec!["vdkvdev".to_string()])
+        .license_type(gtk::License::Gpl30)
+        .website(REPO_URL)
+        .issue_url(format!("{}/issues", REPO_URL))
+        .build();
+
+    about.add_acknowledgement_section(Some("Built With"), BUILT_WITH);
+    about.present();
+}
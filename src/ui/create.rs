@@ -3,16 +3,24 @@ use relm4::ComponentSender;
 use gtk::prelude::*;
 use adw::prelude::*;
 use adw::{ComboRow, EntryRow, SpinRow};
+use crate::models::Loader;
 use crate::ui::model::AppModel;
 use crate::ui::msg::AppMsg;
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_create_instance_page(
     sender: &ComponentSender<AppModel>,
     username_entry: &EntryRow,
+    account_combo: &ComboRow,
     version_combo: &ComboRow,
+    favorite_version_button: &gtk::ToggleButton,
     ram_scale: &SpinRow,
-    fabric_switch: &adw::SwitchRow,
-) -> gtk::Box {
+    jvm_args_entry: &EntryRow,
+    loader_combo: &ComboRow,
+    game_dir_row: &adw::ActionRow,
+    shared_mods_row: &adw::ActionRow,
+    notes_view: &gtk::TextView,
+) -> (gtk::Box, gtk::Label, gtk::Button, gtk::Button) {
     let main_box = gtk::Box::builder()
         .orientation(gtk::Orientation::Vertical)
         .hexpand(true)
@@ -64,30 +72,167 @@ pub fn create_create_instance_page(
         }
     });
 
+    // Clicked rather than toggled, the button also follows the selected version
+    let sender_clone = sender.clone();
+    favorite_version_button.connect_clicked(move |button| {
+        sender_clone.input(AppMsg::SetVersionFavorite(button.is_active()));
+    });
+    version_combo.add_suffix(favorite_version_button);
+
+    // Filters for the version selector
+    let filter_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .halign(gtk::Align::Start)
+        .build();
+
+    let installed_filter = gtk::ToggleButton::builder()
+        .label("Installed")
+        .tooltip_text("Only versions that are already downloaded")
+        .css_classes(vec!["pill".to_string()])
+        .build();
+    let sender_clone = sender.clone();
+    installed_filter.connect_toggled(move |button| {
+        sender_clone.input(AppMsg::ToggleInstalledVersionsFilter(button.is_active()));
+    });
+
+    let favorites_filter = gtk::ToggleButton::builder()
+        .label("Favorites")
+        .tooltip_text("Only starred versions")
+        .css_classes(vec!["pill".to_string()])
+        .build();
+    let sender_clone = sender.clone();
+    favorites_filter.connect_toggled(move |button| {
+        sender_clone.input(AppMsg::ToggleFavoriteVersionsFilter(button.is_active()));
+    });
+
+    filter_box.append(&installed_filter);
+    filter_box.append(&favorites_filter);
+
     // RAM adjustment
     let sender_clone = sender.clone();
     ram_scale.adjustment().connect_value_changed(move |adj| {
         sender_clone.input(AppMsg::RamChanged(adj.value() as u32));
     });
 
+    let loader_names: Vec<&str> = Loader::ALL.iter().map(|l| l.name()).collect();
+    loader_combo.set_model(Some(&gtk::StringList::new(&loader_names)));
+
+    let sender_clone = sender.clone();
+    loader_combo.connect_notify(Some("selected"), move |combo: &ComboRow, _| {
+        if let Some(loader) = Loader::ALL.get(combo.selected() as usize) {
+            sender_clone.input(AppMsg::LoaderSelected(*loader));
+        }
+    });
+
+    let sender_clone = sender.clone();
+    jvm_args_entry.connect_changed(move |entry: &EntryRow| {
+        sender_clone.input(AppMsg::JvmArgsChanged(entry.text().to_string()));
+    });
+
+    // Choices are filled in from the signed in accounts by update_view
     let sender_clone = sender.clone();
-    fabric_switch.connect_active_notify(move |switch| {
-        sender_clone.input(AppMsg::ToggleFabric(switch.is_active()));
+    account_combo.connect_notify(Some("selected"), move |combo: &ComboRow, _| {
+        sender_clone.input(AppMsg::AccountSelected(combo.selected()));
     });
 
+    // Game directory picker
+    let choose_dir_button = gtk::Button::builder()
+        .icon_name("folder-open-symbolic")
+        .tooltip_text("Choose Folder")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    choose_dir_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::ChooseGameDir);
+    });
+
+    let clear_dir_button = gtk::Button::builder()
+        .icon_name("edit-clear-symbolic")
+        .tooltip_text("Use Per-Profile Instance")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    clear_dir_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::GameDirSelected(None));
+    });
+
+    game_dir_row.add_suffix(&clear_dir_button);
+    game_dir_row.add_suffix(&choose_dir_button);
+
+    // Shared mod folders
+    let add_shared_button = gtk::Button::builder()
+        .icon_name("list-add-symbolic")
+        .tooltip_text("Add Folder")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    add_shared_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::ChooseSharedModDir);
+    });
+
+    let clear_shared_button = gtk::Button::builder()
+        .icon_name("edit-clear-symbolic")
+        .tooltip_text("Remove All Folders")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    clear_shared_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::ClearSharedModDirs);
+    });
+
+    shared_mods_row.add_suffix(&clear_shared_button);
+    shared_mods_row.add_suffix(&add_shared_button);
+
     // Configure rows
     username_entry.set_hexpand(true);
+    account_combo.set_hexpand(true);
     version_combo.set_hexpand(true);
     ram_scale.set_hexpand(true);
-    fabric_switch.set_hexpand(true);
+    jvm_args_entry.set_hexpand(true);
+    loader_combo.set_hexpand(true);
+    game_dir_row.set_hexpand(true);
+    shared_mods_row.set_hexpand(true);
 
     input_list.append(username_entry);
+    input_list.append(account_combo);
     input_list.append(version_combo);
     input_list.append(ram_scale);
-    input_list.append(fabric_switch);
+    input_list.append(jvm_args_entry);
+    input_list.append(loader_combo);
+    input_list.append(game_dir_row);
+    input_list.append(shared_mods_row);
 
+    content_container.append(&filter_box);
     content_container.append(&input_list);
 
+    // Notes, shown as the profile's tooltip on the Home page
+    let notes_label = gtk::Label::builder()
+        .label("Notes")
+        .halign(gtk::Align::Start)
+        .css_classes(vec!["heading".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    notes_view.buffer().connect_changed(move |buffer| {
+        let text = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        sender_clone.input(AppMsg::NotesChanged(text.to_string()));
+    });
+
+    let notes_frame = gtk::Frame::builder()
+        .child(notes_view)
+        .css_classes(vec!["view".to_string()])
+        .build();
+
+    content_container.append(&notes_label);
+    content_container.append(&notes_frame);
+
     // Buttons
     let button_box = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
@@ -108,10 +253,23 @@ pub fn create_create_instance_page(
         sender_clone.input(AppMsg::SaveProfile);
     });
 
+    // Only shown while editing an existing profile
+    let cancel_button = gtk::Button::builder()
+        .label("Cancel")
+        .height_request(40)
+        .visible(false)
+        .build();
+
+    let sender_clone = sender.clone();
+    cancel_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::CancelEditProfile);
+    });
+
+    button_box.append(&cancel_button);
     button_box.append(&save_button);
 
     content_container.append(&button_box);
 
     main_box.append(&content_container);
-    main_box
+    (main_box, title_label, save_button, cancel_button)
 }
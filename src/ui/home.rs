@@ -3,9 +3,10 @@ use relm4::ComponentSender;
 use gtk::prelude::*;
 use crate::ui::model::AppModel;
 use crate::ui::msg::AppMsg;
-use crate::models::Profile;
+use crate::models::{Profile, ProfileSort};
+use crate::ui::model::LoadStatus;
 
-pub fn create_home_page(_sender: &ComponentSender<AppModel>, profile_list: &gtk::ListBox) -> gtk::Box {
+pub fn create_home_page(sender: &ComponentSender<AppModel>, profile_list: &gtk::ListBox, sort_dropdown: &gtk::DropDown) -> gtk::Box {
     let main_box = gtk::Box::builder()
         .orientation(gtk::Orientation::Vertical)
         .hexpand(true)
@@ -27,10 +28,26 @@ pub fn create_home_page(_sender: &ComponentSender<AppModel>, profile_list: &gtk:
     let title_label = gtk::Label::builder()
         .label("Home")
         .halign(gtk::Align::Start)
+        .hexpand(true)
         .css_classes(vec!["title-1".to_string()])
         .build();
 
-    content_container.append(&title_label);
+    let sort_names: Vec<&str> = ProfileSort::ALL.iter().map(|s| s.name()).collect();
+    sort_dropdown.set_model(Some(&gtk::StringList::new(&sort_names)));
+    sort_dropdown.set_valign(gtk::Align::Center);
+    sort_dropdown.set_tooltip_text(Some("Sort profiles"));
+    let sender_clone = sender.clone();
+    sort_dropdown.connect_selected_notify(move |dropdown| {
+        if let Some(&sort) = ProfileSort::ALL.get(dropdown.selected() as usize) {
+            sender_clone.input(AppMsg::ProfileSortChanged(sort));
+        }
+    });
+
+    let header = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    header.append(&title_label);
+    header.append(sort_dropdown);
+
+    content_container.append(&header);
 
     // Use the provided profile list
     profile_list.set_selection_mode(gtk::SelectionMode::None);
@@ -42,29 +59,35 @@ pub fn create_home_page(_sender: &ComponentSender<AppModel>, profile_list: &gtk:
     main_box
 }
 
-pub fn update_profile_list(profile_list: &gtk::ListBox, profiles: &std::collections::HashMap<String, Profile>, sender: &ComponentSender<AppModel>) {
+pub fn update_profile_list(profile_list: &gtk::ListBox, profiles: &std::collections::HashMap<String, Profile>, sort: ProfileSort, sizes: &std::collections::HashMap<String, u64>, status: &LoadStatus, sender: &ComponentSender<AppModel>) {
     // Clear existing children
     while let Some(child) = profile_list.first_child() {
         profile_list.remove(&child);
     }
 
     if profiles.is_empty() {
+        let message = match status {
+            LoadStatus::Pending => "Loading profiles...".to_string(),
+            LoadStatus::Failed(e) => format!("Could not load profiles: {}", e),
+            LoadStatus::Loaded => "No profiles yet. Create one to get started!".to_string(),
+        };
         let no_profiles_label = gtk::Label::builder()
-            .label("No profiles yet. Create one to get started!")
+            .label(&message)
+            .wrap(true)
             .halign(gtk::Align::Center)
             .margin_top(24)
             .margin_bottom(24)
             .build();
         profile_list.append(&no_profiles_label);
     } else {
-        for (name, profile) in profiles {
-            let row = create_profile_row(name, profile, sender);
+        for profile in sort.sorted(profiles.values()) {
+            let row = create_profile_row(&profile.id, profile, sizes.get(&profile.id).copied(), sender);
             profile_list.append(&row);
         }
     }
 }
 
-fn create_profile_row(name: &str, profile: &Profile, sender: &ComponentSender<AppModel>) -> gtk::ListBoxRow {
+fn create_profile_row(name: &str, profile: &Profile, size_bytes: Option<u64>, sender: &ComponentSender<AppModel>) -> gtk::ListBoxRow {
     let row = gtk::ListBoxRow::new();
 
     let box_container = gtk::Box::builder()
@@ -101,27 +124,86 @@ fn create_profile_row(name: &str, profile: &Profile, sender: &ComponentSender<Ap
         "No playtime".to_string()
     };
 
-    let version_display = if profile.is_fabric {
-        format!("{} (Fabric)", profile.version)
-    } else {
-        profile.version.clone()
+    let version_display = match profile.loader_name() {
+        Some(loader) => format!("{} ({})", profile.version, loader),
+        None => profile.version.clone(),
     };
 
+    let mut details = format!("{} • {} MB • {}", version_display, profile.ram_mb, playtime_str);
+    if let Some(bytes) = size_bytes {
+        details.push_str(&format!(" • {} on disk", crate::utils::format_size(bytes)));
+    }
+
     let details_label = gtk::Label::builder()
-        .label(format!("{} • {} MB • {}", version_display, profile.ram_mb, playtime_str))
+        .label(details)
         .halign(gtk::Align::Start)
         .css_classes(vec!["dim-label".to_string()])
         .build();
 
-    info_box.append(&name_label);
+    let name_box = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .spacing(6)
+        .build();
+    name_box.append(&name_label);
+
+    if profile.last_crashed {
+        let reason = match profile.last_exit_code {
+            Some(code) => format!("exit code {}", code),
+            None => "killed by a signal".to_string(),
+        };
+        let crash_button = gtk::Button::builder()
+            .icon_name("dialog-warning-symbolic")
+            .tooltip_text(format!("The last session crashed ({}). Click to view its log.", reason))
+            .css_classes(vec!["flat".to_string(), "warning".to_string()])
+            .valign(gtk::Align::Center)
+            .build();
+        let sender_clone = sender.clone();
+        let name_clone = name.to_string();
+        crash_button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::OpenSessionLog(name_clone.clone()));
+        });
+        name_box.append(&crash_button);
+    }
+
+    info_box.append(&name_box);
     info_box.append(&details_label);
 
+    if let Some(modpack) = &profile.modpack {
+        let modpack_label = gtk::Label::builder()
+            .label(format!("Modpack: {} {}", modpack.name, modpack.version_id))
+            .halign(gtk::Align::Start)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .max_width_chars(50)
+            .css_classes(vec!["dim-label".to_string(), "caption".to_string()])
+            .build();
+        info_box.append(&modpack_label);
+    }
+
+    if let Some(notes) = &profile.notes {
+        let notes_label = gtk::Label::builder()
+            .label(notes.lines().next().unwrap_or_default())
+            .halign(gtk::Align::Start)
+            .ellipsize(gtk::pango::EllipsizeMode::End)
+            .max_width_chars(50)
+            .css_classes(vec!["dim-label".to_string(), "caption".to_string()])
+            .build();
+        info_box.append(&notes_label);
+        info_box.set_tooltip_text(Some(notes));
+    }
+
     // Buttons
     let button_box = gtk::Box::builder()
         .orientation(gtk::Orientation::Horizontal)
         .spacing(6)
         .build();
 
+    let details_button = gtk::MenuButton::builder()
+        .icon_name("dialog-information-symbolic")
+        .tooltip_text("Details")
+        .valign(gtk::Align::Center)
+        .popover(&profile_details_popover(profile))
+        .build();
+
     let launch_button = gtk::Button::builder()
         .label("Launch")
         .css_classes(vec!["suggested-action".to_string()])
@@ -134,6 +216,54 @@ fn create_profile_row(name: &str, profile: &Profile, sender: &ComponentSender<Ap
         sender_clone.input(AppMsg::LaunchProfile(name_clone.clone()));
     });
 
+    let copy_command_button = gtk::Button::builder()
+        .icon_name("edit-copy-symbolic")
+        .tooltip_text("Copy Launch Command")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    let name_clone = name.to_string();
+    copy_command_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::CopyLaunchCommand(name_clone.clone()));
+    });
+
+    let repair_button = gtk::Button::builder()
+        .icon_name("applications-engineering-symbolic")
+        .tooltip_text("Repair Natives")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    let version_clone = profile.version.clone();
+    repair_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::RepairNatives(version_clone.clone()));
+    });
+
+    let migrate_button = gtk::Button::builder()
+        .icon_name("go-up-symbolic")
+        .tooltip_text("Duplicate to Another Version")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    let name_clone = name.to_string();
+    migrate_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::RequestMigrateProfile(name_clone.clone()));
+    });
+
+    let edit_button = gtk::Button::builder()
+        .icon_name("document-edit-symbolic")
+        .tooltip_text("Edit Profile")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    let name_clone = name.to_string();
+    edit_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::EditProfile(name_clone.clone()));
+    });
+
     let delete_button = gtk::Button::builder()
         .icon_name("user-trash-symbolic")
         .css_classes(vec!["destructive-action".to_string()])
@@ -146,7 +276,12 @@ fn create_profile_row(name: &str, profile: &Profile, sender: &ComponentSender<Ap
         sender_clone.input(AppMsg::RequestDeleteProfile(name_clone.clone()));
     });
 
+    button_box.append(&details_button);
     button_box.append(&launch_button);
+    button_box.append(&copy_command_button);
+    button_box.append(&repair_button);
+    button_box.append(&migrate_button);
+    button_box.append(&edit_button);
     button_box.append(&delete_button);
 
     box_container.append(&info_box);
@@ -156,3 +291,55 @@ fn create_profile_row(name: &str, profile: &Profile, sender: &ComponentSender<Ap
     row.set_activatable(false);
     row
 }
+
+// What ran last time and how it ended, for troubleshooting and support requests
+fn profile_details_popover(profile: &Profile) -> gtk::Popover {
+    let grid = gtk::Grid::builder()
+        .row_spacing(6)
+        .column_spacing(12)
+        .margin_top(12)
+        .margin_bottom(12)
+        .margin_start(12)
+        .margin_end(12)
+        .build();
+
+    let last_session = match (profile.last_launch, profile.last_crashed, profile.last_exit_code) {
+        (None, _, _) => "Never launched".to_string(),
+        (Some(_), false, _) => "Exited normally".to_string(),
+        (Some(_), true, Some(code)) => format!("Crashed (exit code {})", code),
+        (Some(_), true, None) => "Killed by a signal".to_string(),
+    };
+    let java = match (&profile.last_java_path, profile.last_java_version) {
+        (Some(path), Some(version)) => format!("Java {} ({})", version, path),
+        (Some(path), None) => path.clone(),
+        (None, _) => "Not launched yet".to_string(),
+    };
+    let rows = [
+        ("Version", match profile.loader_name() {
+            Some(loader) => format!("{} ({})", profile.version, loader),
+            None => profile.version.clone(),
+        }),
+        ("Memory", format!("{} MB", profile.ram_mb)),
+        ("Java", java),
+        ("Last Session", last_session),
+    ];
+
+    for (row, (title, value)) in rows.into_iter().enumerate() {
+        let title_label = gtk::Label::builder()
+            .label(title)
+            .halign(gtk::Align::Start)
+            .css_classes(vec!["dim-label".to_string()])
+            .build();
+        let value_label = gtk::Label::builder()
+            .label(value)
+            .halign(gtk::Align::Start)
+            .selectable(true)
+            .wrap(true)
+            .max_width_chars(48)
+            .build();
+        grid.attach(&title_label, 0, row as i32, 1, 1);
+        grid.attach(&value_label, 1, row as i32, 1, 1);
+    }
+
+    gtk::Popover::builder().child(&grid).build()
+}
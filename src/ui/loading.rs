@@ -1,9 +1,14 @@
 use relm4::gtk;
+use relm4::ComponentSender;
 use gtk::prelude::*;
 
 use adw::StatusPage;
 
-pub fn create_loading_widgets() -> (StatusPage, gtk::Spinner, gtk::ProgressBar, gtk::Label) {
+use crate::ui::model::AppModel;
+use crate::ui::msg::AppMsg;
+
+#[allow(clippy::type_complexity)]
+pub fn create_loading_widgets(sender: &ComponentSender<AppModel>) -> (StatusPage, gtk::Spinner, gtk::ProgressBar, gtk::Label, gtk::Stack, gtk::Label, gtk::Label, gtk::Button) {
     let status_page = adw::StatusPage::builder()
         .title("Loading RCraft")
         .description("Please wait while the launcher initializes...")
@@ -15,14 +20,55 @@ pub fn create_loading_widgets() -> (StatusPage, gtk::Spinner, gtk::ProgressBar,
 
     let progress_bar = gtk::ProgressBar::new();
     progress_bar.set_show_text(true);
+    // Long file names lose their middle rather than the percentage in front
+    progress_bar.set_ellipsize(gtk::pango::EllipsizeMode::Middle);
 
     progress_bar.set_margin_top(12);
     progress_bar.set_size_request(300, -1);
 
     let label = gtk::Label::new(Some("Initializing..."));
 
-    // Add spinner to status page
-    status_page.set_child(Some(&spinner));
+    // Spinner or progress bar, depending on whether the operation reports progress
+    let indicator_stack = gtk::Stack::new();
+    indicator_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+    indicator_stack.add_named(&spinner, Some("spinner"));
+    indicator_stack.add_named(&progress_bar, Some("progress"));
+    indicator_stack.set_visible_child_name("spinner");
+
+    let elapsed_label = gtk::Label::builder()
+        .css_classes(vec!["dim-label", "numeric"])
+        .visible(false)
+        .build();
+
+    // Shown when nothing has been reported for a while
+    let stall_label = gtk::Label::builder()
+        .label("No progress for a while, the connection may have dropped.")
+        .css_classes(vec!["warning"])
+        .wrap(true)
+        .justify(gtk::Justification::Center)
+        .visible(false)
+        .build();
+
+    let cancel_button = gtk::Button::builder()
+        .label("Cancel")
+        .halign(gtk::Align::Center)
+        .css_classes(vec!["pill"])
+        .visible(false)
+        .build();
+
+    let sender_clone = sender.clone();
+    cancel_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::CancelOperation);
+    });
+
+    let activity_box = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    activity_box.set_halign(gtk::Align::Center);
+    activity_box.append(&indicator_stack);
+    activity_box.append(&elapsed_label);
+    activity_box.append(&stall_label);
+    activity_box.append(&cancel_button);
+
+    status_page.set_child(Some(&activity_box));
 
-    (status_page, spinner, progress_bar, label)
+    (status_page, spinner, progress_bar, label, indicator_stack, elapsed_label, stall_label, cancel_button)
 }
@@ -3,8 +3,44 @@ use relm4::ComponentSender;
 use gtk::prelude::*;
 
 use crate::ui::model::AppModel;
+use crate::ui::msg::AppMsg;
 
-pub fn create_logs_page(_sender: &ComponentSender<AppModel>, logs_buffer: &gtk::TextBuffer) -> (gtk::Box, gtk::TextView) {
+// Foreground colors for ANSI codes 30-37, readable on both light and dark backgrounds
+const ANSI_COLORS: [&str; 8] = ["#77767b", "#e01b24", "#2ec27e", "#e5a50a", "#3584e4", "#c061cb", "#33c7de", "#9a9996"];
+
+pub const LOG_TIMESTAMP_TAG: &str = "timestamp";
+
+const LOG_ERROR_TAG: &str = "log-error";
+const LOG_WARN_TAG: &str = "log-warn";
+
+// Whole-line highlight for stderr (tagged "[ERR]" by the launch task) and for lines the game
+// logged at WARN/ERROR, e.g. "[12:00:00] [Render thread/WARN]: ..."
+pub fn severity_tag(line: &str) -> Option<&'static str> {
+    if line.starts_with("[ERR]") || line.contains("/ERROR]") || line.contains("[ERROR]") || line.contains("/FATAL]") {
+        Some(LOG_ERROR_TAG)
+    } else if line.contains("/WARN]") || line.contains("[WARN]") {
+        Some(LOG_WARN_TAG)
+    } else {
+        None
+    }
+}
+
+pub fn ansi_tag_name(color: u8) -> String {
+    format!("ansi-{}", color)
+}
+
+// Tags used by the Log handler to color game output, created once per buffer
+pub fn create_log_tags(buffer: &gtk::TextBuffer) {
+    for (i, color) in ANSI_COLORS.iter().enumerate() {
+        buffer.create_tag(Some(&ansi_tag_name(i as u8)), &[("foreground", color)]);
+    }
+    buffer.create_tag(Some(LOG_TIMESTAMP_TAG), &[("foreground", &"#9a9996")]);
+    // Created last so they win over ANSI colors on the same text
+    buffer.create_tag(Some(LOG_WARN_TAG), &[("foreground", &"#c88800")]);
+    buffer.create_tag(Some(LOG_ERROR_TAG), &[("foreground", &"#e01b24"), ("weight", &700i32)]);
+}
+
+pub fn create_logs_page(sender: &ComponentSender<AppModel>, logs_buffer: &gtk::TextBuffer, launcher_logs_buffer: &gtk::TextBuffer) -> (gtk::Box, gtk::TextView, gtk::Button, gtk::Button) {
     let container = gtk::Box::builder()
         .orientation(gtk::Orientation::Vertical)
         .spacing(24)
@@ -21,7 +57,78 @@ pub fn create_logs_page(_sender: &ComponentSender<AppModel>, logs_buffer: &gtk::
         .css_classes(vec!["title-1".to_string()])
         .build();
 
-    container.append(&title_label);
+    // Game output and RCraft's own log
+    let log_stack = gtk::Stack::new();
+    log_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+    log_stack.set_vexpand(true);
+
+    let switcher = gtk::StackSwitcher::builder()
+        .stack(&log_stack)
+        .halign(gtk::Align::Start)
+        .build();
+
+    let header_box = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    title_label.set_hexpand(true);
+    header_box.append(&title_label);
+
+    // Copies what the game printed, without the timestamps and colors added here
+    let copy_button = gtk::Button::builder()
+        .icon_name("edit-copy-symbolic")
+        .tooltip_text("Copy Game Logs")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    copy_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::CopyLogs);
+    });
+
+    // For bug reports, headed by when and what was played like the copy
+    let save_button = gtk::Button::builder()
+        .icon_name("document-save-symbolic")
+        .tooltip_text("Save Game Logs")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    save_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::SaveLogs);
+    });
+
+    let clear_button = gtk::Button::builder()
+        .icon_name("edit-clear-all-symbolic")
+        .tooltip_text("Clear Game Logs")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    clear_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::ClearLogs);
+    });
+
+    // The logs/ folder of the last launched profile, also on F12
+    let folder_button = gtk::Button::builder()
+        .icon_name("folder-open-symbolic")
+        .tooltip_text("Open Logs Folder (F12)")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    folder_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::OpenLogsFolder);
+    });
+
+    header_box.append(&copy_button);
+    header_box.append(&save_button);
+    header_box.append(&clear_button);
+    header_box.append(&folder_button);
+    header_box.append(&switcher);
+
+    container.append(&header_box);
 
     let scrolled_window = gtk::ScrolledWindow::builder()
         .hscrollbar_policy(gtk::PolicyType::Automatic)
@@ -44,7 +151,35 @@ pub fn create_logs_page(_sender: &ComponentSender<AppModel>, logs_buffer: &gtk::
     // Let's stick to the request: padding and title.
 
     scrolled_window.set_child(Some(&text_view));
-    container.append(&scrolled_window);
 
-    (container, text_view)
+    // Follow new output, also once old lines are trimmed off the top. The mark moves along
+    // with text inserted at the end.
+    let end_mark = logs_buffer.create_mark(Some("log-end"), &logs_buffer.end_iter(), false);
+    let text_view_clone = text_view.clone();
+    logs_buffer.connect_changed(move |_| {
+        text_view_clone.scroll_mark_onscreen(&end_mark);
+    });
+
+    log_stack.add_titled(&scrolled_window, Some("game"), "Game");
+
+    let launcher_scrolled_window = gtk::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk::PolicyType::Automatic)
+        .vscrollbar_policy(gtk::PolicyType::Automatic)
+        .vexpand(true)
+        .hexpand(true)
+        .build();
+
+    let launcher_text_view = gtk::TextView::builder()
+        .buffer(launcher_logs_buffer)
+        .editable(false)
+        .monospace(true)
+        .wrap_mode(gtk::WrapMode::Word)
+        .build();
+
+    launcher_scrolled_window.set_child(Some(&launcher_text_view));
+    log_stack.add_titled(&launcher_scrolled_window, Some("launcher"), "Launcher");
+
+    container.append(&log_stack);
+
+    (container, text_view, copy_button, save_button)
 }
@@ -8,6 +8,8 @@ pub mod settings;
 pub mod logs;
 pub mod loading;
 pub mod mods;
+pub mod mod_detail;
+pub mod about;
 
 pub use model::AppModel;
 pub use msg::AppMsg;
@@ -17,7 +19,7 @@ use relm4::prelude::*;
 use relm4::gtk;
 // use gtk::prelude::*;
 use relm4::{ComponentParts, ComponentSender, SimpleComponent};
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::io::Read;
 use std::fs::File;
 use tokio::io::AsyncBufReadExt;
@@ -25,18 +27,20 @@ use tokio::io::BufReader;
 use tokio::runtime::Runtime;
 use zip::ZipArchive;
 
-use crate::launcher::MinecraftLauncher;
-use crate::modrinth_client::ModrinthClient;
-use crate::models::{Profile, Section, Theme};
+use crate::launcher::{LaunchAuth, MinecraftLauncher, PreparedLaunch, SpawnedGame};
+use crate::modrinth_client::{LinkKind, ModrinthClient};
+use crate::mod_index::{installed_path, InstalledMod, ModIndex, DISABLED_SUFFIX};
+use crate::models::{is_server_only, Account, JavaVendor, Loader, LogLevel, ModSearchResult, ModpackSource, Profile, ProfileSort, ProjectType, Section, Theme};
 use crate::settings::Settings;
 use crate::ui::create::create_create_instance_page;
 use crate::ui::home::{create_home_page, update_profile_list};
 use crate::ui::loading::create_loading_widgets;
-use crate::ui::logs::create_logs_page;
-use crate::ui::model::AppState;
+use crate::ui::logs::{ansi_tag_name, create_log_tags, create_logs_page, severity_tag, LOG_TIMESTAMP_TAG};
+use crate::ui::model::{AppState, LoadStatus, ModpackJob, RetryAction};
 use crate::ui::mods::{create_mods_page, create_mod_search_result_row};
-use crate::ui::settings::create_settings_page;
-use crate::ui::sidebar::create_sidebar;
+use crate::ui::mod_detail::create_mod_detail_page;
+use crate::ui::settings::{create_settings_page, profiles_using, update_accounts_list, update_installed_versions_list, update_java_runtimes_list};
+use crate::ui::sidebar::{create_bottom_bar, create_sidebar};
 use crate::ui::widgets::AppWidgets;
 
 impl SimpleComponent for AppModel {
@@ -57,7 +61,7 @@ impl SimpleComponent for AppModel {
         // Load CSS for transparency
         let provider = gtk::CssProvider::new();
         provider.load_from_data("
-            .transparent-window { background-color: rgba(30, 30, 30, 0.85); }
+            .transparent-window { background-color: alpha(@window_bg_color, 0.85); }
             .transparent-window navigation-split-view { background-color: transparent; }
             .transparent-window navigation-split-view > sidebar { background-color: transparent; border: none; }
             .transparent-window navigation-split-view > content { background-color: transparent; }
@@ -65,15 +69,15 @@ impl SimpleComponent for AppModel {
             .transparent-window .view { background-color: transparent; }
             .transparent-window .sidebar-pane { background-color: transparent; }
             
-            /* Apply sidebar color (solid lighter gray) to content containers */
-            .transparent-window list { background-color: #383838; }
+            /* Apply sidebar color (solid, slightly towards the text color) to content containers */
+            .transparent-window list { background-color: mix(@window_bg_color, @window_fg_color, 0.08); }
             .transparent-window row { background-color: transparent; }
             
             /* Ensure sidebar buttons don't have opaque backgrounds unless active */
             .transparent-window .navigation-sidebar-item { background-color: transparent; }
 
             /* Semi-transparent lighter gray interactive elements (0.9 alpha) */
-            .transparent-window button { background-color: alpha(#383838, 0.9); }
+            .transparent-window button { background-color: alpha(mix(@window_bg_color, @window_fg_color, 0.08), 0.9); }
             .transparent-window entry { background-color: alpha(@theme_base_color, 0.9); }
 
             /* Active states */
@@ -120,33 +124,65 @@ impl SimpleComponent for AppModel {
             modrinth: ModrinthClient::new(),
             window: Some(root.clone()),
             profiles: HashMap::new(),
+            accounts: Vec::new(),
             available_versions: Vec::new(),
             sorted_versions: Vec::new(),
+            shown_versions: Vec::new(),
+            installed_versions: HashSet::new(),
+            version_filter_installed: false,
+            version_filter_favorites: false,
             input_username: String::new(),
             input_version: None,
 
             input_ram: 4096, // Default 4GB
-            input_install_fabric: false,
-            fabric_switch_enabled: false,
+            input_loader: Loader::Vanilla,
+            input_game_dir: None,
+            input_shared_mod_dirs: Vec::new(),
+            input_notes: String::new(),
+            input_account: None,
+            input_jvm_args: String::new(),
+            editing_profile: None,
+            loader_available: true,
+            loader_checking: false,
+            loader_support: HashMap::new(),
             error_message: None,
             sidebar_collapsed: false,
+            settings_loaded: false,
             is_searching: false,
 
             // Initialize settings
             settings: Settings::default(), // Async load triggered later
             logs: gtk::TextBuffer::new(None),
+            launcher_logs: gtk::TextBuffer::new(None),
+            raw_logs: VecDeque::new(),
 
             versions_updated: false,
+            versions_status: LoadStatus::Pending,
+            profiles_status: LoadStatus::Pending,
+            pending_modpack: None,
+            popular_mods_profile: None,
             version_list_model: None,
 
             mod_search_results: Vec::new(),
+            mod_search_query: String::new(),
             mod_search_entry: None,
             mod_browse_list: None,
+            mod_results_stale: false,
             mod_installed_list: None,
+            mod_project_type: ProjectType::default(),
+            mod_check_updates_button: None,
+            mod_updates: HashMap::new(),
+            updating_mods: HashSet::new(),
             selected_mod_profile: None,
             mod_profile_list_model: None,
+            mod_profile_ids: Vec::new(),
+            mod_page_stack: None,
+            mod_detail: None,
 
             installed_mods: HashMap::new(),
+            installing_mods: HashSet::new(),
+            mod_download_progress: HashMap::new(),
+            mod_action_buttons: HashMap::new(),
 
             toast_overlay: None,
 
@@ -154,16 +190,47 @@ impl SimpleComponent for AppModel {
             is_downloading_icon: false,
             pending_mod_selection: None,
             pending_launch_profile: None,
+            offline_launch: None,
             mod_profile_list_updated: false,
 
             sender: sender.clone(),
             java_dialog_request: None,
+            java_runtimes: Vec::new(),
+            profile_sizes: HashMap::new(),
+            profile_sizes_requested: HashSet::new(),
+            java_updates_checking: false,
+            java_runtimes_list: None,
+            downloaded_versions: Vec::new(),
+            installed_versions_list: None,
+            accounts_list: None,
+            sign_in_task: None,
+            operation_started: None,
+            last_progress_at: None,
+            operation_timer: None,
+            current_operation: None,
+            java_download_cancel: None,
+            running_java: None,
+            installed_mod_icons: HashMap::new(),
             rt: std::sync::Arc::new(Runtime::new().unwrap()),
+            settings_save_generation: std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0)),
+            pending_settings_save: std::sync::Arc::new(std::sync::Mutex::new(None)),
         };
 
         // Set window title
         root.set_title(Some("RCraft"));
 
+        // A change made just before quitting is still waiting out the debounce, write it now
+        let pending_settings_save = model.pending_settings_save.clone();
+        let rt_clone = model.rt.clone();
+        root.connect_close_request(move |_| {
+            let pending = pending_settings_save.lock().ok().and_then(|mut p| p.take());
+            let Some((config_dir, settings)) = pending else { return gtk::glib::Propagation::Proceed };
+            if let Err(e) = rt_clone.block_on(settings.save(&config_dir)) {
+                tracing::warn!("Could not save settings on close: {}", e);
+            }
+            gtk::glib::Propagation::Proceed
+        });
+
         // Create navigation split view for sidebar navigation
         let navigation_split_view = adw::NavigationSplitView::new();
         navigation_split_view.set_collapsed(false);
@@ -187,6 +254,10 @@ impl SimpleComponent for AppModel {
             .title("Username")
             .build();
 
+        let jvm_args_entry = adw::EntryRow::builder()
+            .title("JVM Arguments (e.g. -XX:+UseG1GC)")
+            .build();
+
         let version_list_model = gtk::StringList::new(&[]);
         let version_combo = {
             let combo = adw::ComboRow::builder()
@@ -195,17 +266,50 @@ impl SimpleComponent for AppModel {
             combo.set_model(Some(&version_list_model));
             combo
         };
+        let favorite_version_button = gtk::ToggleButton::builder()
+            .icon_name("starred-symbolic")
+            .tooltip_text("Favorite Version")
+            .valign(gtk::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
         model.version_list_model = Some(version_list_model.clone());
 
-        let max_ram = crate::utils::get_total_memory_mb();
+        let max_ram = crate::utils::get_total_memory_mb().max(crate::settings::MIN_RAM_MB as u64);
         let ram_scale = adw::SpinRow::builder()
             .title("RAM (MB)")
-            .adjustment(&gtk::Adjustment::new(4096.0, 2048.0, max_ram as f64, 256.0, 256.0, 0.0))
+            .adjustment(&gtk::Adjustment::new(crate::settings::DEFAULT_RAM_MB as f64, crate::settings::MIN_RAM_MB as f64, max_ram as f64, 256.0, 256.0, 0.0))
+            .build();
+
+        let account_combo = adw::ComboRow::builder()
+            .title("Account")
+            .subtitle(ACCOUNT_HINT)
             .build();
 
-        let fabric_switch = adw::SwitchRow::builder()
-            .title("Install Fabric")
-            .subtitle("Install Fabric Modloader for this version")
+        let loader_combo = adw::ComboRow::builder()
+            .title("Mod Loader")
+            .subtitle(LOADER_HINT)
+            .build();
+
+        let game_dir_row = adw::ActionRow::builder()
+            .title("Game Directory")
+            .subtitle(GAME_DIR_HINT)
+            .use_markup(false)
+            .build();
+
+        let shared_mods_row = adw::ActionRow::builder()
+            .title("Shared Mod Folders")
+            .subtitle(SHARED_MODS_HINT)
+            .use_markup(false)
+            .build();
+
+        let notes_view = gtk::TextView::builder()
+            .wrap_mode(gtk::WrapMode::WordChar)
+            .accepts_tab(false)
+            .top_margin(12)
+            .bottom_margin(12)
+            .left_margin(12)
+            .right_margin(12)
+            .height_request(96)
             .build();
 
         let hide_logs_switch = adw::SwitchRow::builder()
@@ -217,20 +321,50 @@ impl SimpleComponent for AppModel {
             .subtitle("Hide the Mods button in the sidebar")
             .build();
 
+        let transparent_switch = adw::SwitchRow::new();
+        let colorize_logs_switch = adw::SwitchRow::new();
+        let stable_mods_switch = adw::SwitchRow::new();
+        let log_timestamps_switch = adw::SwitchRow::new();
+        let minimize_on_launch_switch = adw::SwitchRow::new();
+        let quit_on_launch_switch = adw::SwitchRow::new();
+        let log_level_combo = adw::ComboRow::new();
+        let max_retries_row = adw::SpinRow::with_range(0.0, 10.0, 1.0);
+        let max_backoff_row = adw::SpinRow::with_range(1.0, 120.0, 1.0);
+        let download_concurrency_row = adw::SpinRow::with_range(1.0, 32.0, 1.0);
+        let max_log_lines_row = adw::SpinRow::with_range(500.0, 100000.0, 500.0);
+        let options_template_row = adw::ActionRow::new();
+        let default_username_row = adw::EntryRow::new();
+        let default_ram_row = adw::SpinRow::with_range(1024.0, 65536.0, 256.0);
+        let ram_percent_row = adw::SpinRow::with_range(0.0, 90.0, 5.0);
+        let installed_versions_list = gtk::ListBox::new();
+        let java_runtimes_list = gtk::ListBox::new();
+        let accounts_list = gtk::ListBox::new();
+        let mod_stale_banner = adw::Banner::new("Showing cached results, Modrinth is unavailable");
+        model.java_runtimes_list = Some(java_runtimes_list.clone());
+        model.installed_versions_list = Some(installed_versions_list.clone());
+        model.accounts_list = Some(accounts_list.clone());
+
         let profile_list = gtk::ListBox::new();
-        let loading_widgets = create_loading_widgets();
+        let loading_widgets = create_loading_widgets(&sender);
 
         // Create pages for each section
-        let home_page = create_home_page(&sender, &profile_list);
-        let create_page = create_create_instance_page(&sender, &username_entry, &version_combo, &ram_scale, &fabric_switch);
-        let (settings_page, theme_combo) = create_settings_page(&sender, &hide_logs_switch, &hide_mods_switch);
-        let (logs_page, logs_view) = create_logs_page(&sender, &model.logs);
-        let (mods_page, mod_search_entry, mod_search_button, mod_search_stack, mod_installed_list, mod_browse_list, mod_profile_dropdown) = create_mods_page(&sender);
+        let profile_sort_dropdown = gtk::DropDown::builder().build();
+        let home_page = create_home_page(&sender, &profile_list, &profile_sort_dropdown);
+        let (create_page, create_title_label, save_button, cancel_button) = create_create_instance_page(&sender, &username_entry, &account_combo, &version_combo, &favorite_version_button, &ram_scale, &jvm_args_entry, &loader_combo, &game_dir_row, &shared_mods_row, &notes_view);
+        let (settings_page, theme_combo, java_vendor_combo, verify_files_switch, data_dir_row, instances_dir_row, check_libraries_switch) = create_settings_page(&sender, &transparent_switch, &hide_logs_switch, &colorize_logs_switch, &log_timestamps_switch, &max_log_lines_row, &hide_mods_switch, &stable_mods_switch, &minimize_on_launch_switch, &quit_on_launch_switch, &log_level_combo, &max_retries_row, &max_backoff_row, &download_concurrency_row, &options_template_row, &default_username_row, &default_ram_row, &ram_percent_row, &installed_versions_list, &java_runtimes_list, &accounts_list);
+        create_log_tags(&model.logs);
+        let (logs_page, logs_view, copy_logs_button, save_logs_button) = create_logs_page(&sender, &model.logs, &model.launcher_logs);
+        let (mods_page, mod_search_entry, mod_search_button, mod_search_stack, mod_installed_list, mod_browse_list, mod_profile_dropdown, mod_page_stack, mod_check_updates_button) = create_mods_page(&sender, &mod_stale_banner);
+        let mod_detail = create_mod_detail_page(&sender);
+        mod_page_stack.add_named(&mod_detail.container, Some("detail"));
+        model.mod_page_stack = Some(mod_page_stack);
+        model.mod_detail = Some(mod_detail);
 
         // Store references to separate widgets for logic
         model.mod_search_entry = Some(mod_search_entry.clone());
         model.mod_browse_list = Some(mod_browse_list.clone());
         model.mod_installed_list = Some(mod_installed_list.clone());
+        model.mod_check_updates_button = Some(mod_check_updates_button);
 
         // Connect Search Logic
         let sender_clone = sender.clone();
@@ -287,8 +421,33 @@ impl SimpleComponent for AppModel {
             sender_clone.input(AppMsg::BackToMainMenu);
         });
 
+        // Only shown when the failed action can be re-dispatched
+        let retry_button = gtk::Button::builder()
+            .label("Retry")
+            .halign(gtk::Align::Center)
+            .visible(false)
+            .build();
+
+        let sender_clone = sender.clone();
+        retry_button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::RetryFailedAction);
+        });
+
+        let diagnostics_button = gtk::Button::builder()
+            .label("Export Diagnostics")
+            .halign(gtk::Align::Center)
+            .tooltip_text("Save a local report to attach to a bug report. Nothing is uploaded.")
+            .build();
+
+        let sender_clone = sender.clone();
+        diagnostics_button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::ExportDiagnostics);
+        });
+
         error_box.append(&error_label);
+        error_box.append(&retry_button);
         error_box.append(&back_button);
+        error_box.append(&diagnostics_button);
 
         let error_status_page = adw::StatusPage::builder()
             .title("Error")
@@ -329,6 +488,48 @@ impl SimpleComponent for AppModel {
 
         header_bar.pack_start(&sidebar_toggle_button);
 
+        // Primary menu, backed by app actions so they also work as keyboard shortcuts
+        let app = relm4::main_application();
+        let add_app_action = |name: &str, msg: fn() -> AppMsg| {
+            let action = gtk::gio::SimpleAction::new(name, None);
+            let sender_clone = sender.clone();
+            action.connect_activate(move |_, _| {
+                sender_clone.input(msg());
+            });
+            app.add_action(&action);
+        };
+        add_app_action("about", || AppMsg::ShowAboutWindow);
+        add_app_action("open-minecraft-folder", || AppMsg::OpenMinecraftFolder);
+        add_app_action("open-logs-folder", || AppMsg::OpenLogsFolder);
+        add_app_action("check-updates", || AppMsg::CheckForUpdates);
+        add_app_action("install-modpack", || AppMsg::ChooseModpack);
+        add_app_action("preferences", || AppMsg::NavigateToSection(Section::Settings));
+        add_app_action("quit", || AppMsg::CloseWindow);
+        app.set_accels_for_action("app.preferences", &["<Control>comma"]);
+        app.set_accels_for_action("app.quit", &["<Control>q"]);
+        app.set_accels_for_action("app.open-logs-folder", &["F12"]);
+
+        let primary_menu = gtk::gio::Menu::new();
+        let actions_section = gtk::gio::Menu::new();
+        actions_section.append(Some("Install Modpack..."), Some("app.install-modpack"));
+        actions_section.append(Some("Open .minecraft Folder"), Some("app.open-minecraft-folder"));
+        actions_section.append(Some("Open Logs Folder"), Some("app.open-logs-folder"));
+        actions_section.append(Some("Check for Updates"), Some("app.check-updates"));
+        primary_menu.append_section(None, &actions_section);
+        let app_section = gtk::gio::Menu::new();
+        app_section.append(Some("Preferences"), Some("app.preferences"));
+        app_section.append(Some("About RCraft"), Some("app.about"));
+        app_section.append(Some("Quit"), Some("app.quit"));
+        primary_menu.append_section(None, &app_section);
+
+        let menu_button = gtk::MenuButton::builder()
+            .icon_name("open-menu-symbolic")
+            .tooltip_text("Main Menu")
+            .menu_model(&primary_menu)
+            .primary(true)
+            .build();
+        header_bar.pack_end(&menu_button);
+
         // Create main container
         let main_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
         main_box.set_vexpand(true);
@@ -337,8 +538,51 @@ impl SimpleComponent for AppModel {
         main_box.append(&header_bar);
         main_box.append(&toast_overlay);
 
+        // Mini mode: below the breakpoint the split view shows only the content and
+        // navigation moves to a bar at the bottom of the window
+        let (bottom_bar, bottom_nav_buttons) = create_bottom_bar(&sender);
+        for (section, button) in &bottom_nav_buttons {
+            // Follow the sidebar for the sections that can be hidden or disabled
+            let sidebar_button = match section {
+                Section::Home => &home_button,
+                Section::CreateInstance => &create_sidebar_button,
+                Section::Mods => &mods_button,
+                Section::Logs => &logs_button,
+                Section::Settings => &settings_button,
+            };
+            sidebar_button.bind_property("visible", button, "visible").sync_create().build();
+            sidebar_button.bind_property("sensitive", button, "sensitive").sync_create().build();
+        }
+        main_box.append(&bottom_bar);
+
+        let mini_mode = adw::Breakpoint::new(adw::BreakpointCondition::new_length(
+            adw::BreakpointConditionLengthType::MaxWidth,
+            MINI_MODE_WIDTH,
+            adw::LengthUnit::Sp,
+        ));
+        mini_mode.add_setter(&navigation_split_view, "collapsed", Some(&true.to_value()));
+        mini_mode.add_setter(&navigation_split_view, "show-content", Some(&true.to_value()));
+        mini_mode.add_setter(&sidebar_toggle_button, "visible", Some(&false.to_value()));
+        mini_mode.add_setter(&bottom_bar, "visible", Some(&true.to_value()));
+        root.add_breakpoint(mini_mode);
+        // Small enough for the breakpoint to ever apply
+        root.set_size_request(360, 400);
+
         root.set_content(Some(&main_box));
 
+        // Dropped modpacks, profile bundles and mods, dispatched by what the file turns out to be
+        let drop_target = gtk::DropTarget::new(gtk::gdk::FileList::static_type(), gtk::gdk::DragAction::COPY);
+        let sender_clone = sender.clone();
+        drop_target.connect_drop(move |_, value, _, _| {
+            let Ok(files) = value.get::<gtk::gdk::FileList>() else { return false };
+            let paths: Vec<std::path::PathBuf> = files.files().iter().filter_map(|f| f.path()).collect();
+            for path in &paths {
+                sender_clone.input(AppMsg::FileDropped(path.clone()));
+            }
+            !paths.is_empty()
+        });
+        root.add_controller(drop_target);
+
         // Create Java Confirmation Dialog
         let java_dialog = adw::MessageDialog::builder()
             .heading("Java Missing")
@@ -366,6 +610,9 @@ impl SimpleComponent for AppModel {
             window: root.clone(),
             header_bar,
             navigation_split_view,
+            sidebar_applied: None,
+            bottom_nav_buttons,
+            sidebar_animation: None,
             navigation_page,
             content_stack,
             home_page,
@@ -377,23 +624,51 @@ impl SimpleComponent for AppModel {
             loading_spinner: loading_widgets.1,
             loading_progress: loading_widgets.2,
             loading_label: loading_widgets.3,
+            loading_indicator_stack: loading_widgets.4,
+            loading_elapsed_label: loading_widgets.5,
+            loading_stall_label: loading_widgets.6,
+            loading_cancel_button: loading_widgets.7,
 
             mod_profile_dropdown,
             mod_search_stack,
+            mod_stale_banner,
 
             profile_list,
+            profile_sort_dropdown,
             username_entry,
+            jvm_args_entry,
+            notes_view,
             version_combo,
+            favorite_version_button,
             ram_scale,
-            fabric_switch,
+            loader_combo,
+            account_combo,
+            game_dir_row,
+            shared_mods_row,
 
+            transparent_switch,
             hide_logs_switch,
+            colorize_logs_switch,
+            stable_mods_switch,
+            log_timestamps_switch,
             hide_mods_switch,
+            minimize_on_launch_switch,
+            quit_on_launch_switch,
+            log_level_combo,
+            max_retries_row,
+            max_backoff_row,
+            download_concurrency_row,
+            max_log_lines_row,
+            options_template_row,
+            default_username_row,
+            default_ram_row,
+            ram_percent_row,
             launch_button: gtk::Button::with_label("Launch"),
             create_button: gtk::Button::with_label("Create"),
             delete_button: gtk::Button::with_label("Delete"),
-            save_button: gtk::Button::with_label("Save"),
-            cancel_button: gtk::Button::with_label("Cancel"),
+            create_title_label,
+            save_button,
+            cancel_button,
             home_button,
             create_sidebar_button,
             mods_button,
@@ -411,15 +686,24 @@ impl SimpleComponent for AppModel {
             logs_box,
             sidebar_toggle_button,
             theme_combo,
+            java_vendor_combo,
+            verify_files_switch,
+            data_dir_row,
+            instances_dir_row,
+            check_libraries_switch,
             status_label: gtk::Label::new(None),
             error_label,
+            retry_button,
 
             toast_overlay,
             java_dialog,
             logs_view,
+            copy_logs_button,
+            save_logs_button,
         };
 
-        // Start loading data
+        // Home is usable right away: profiles come from disk and render as soon as they're read,
+        // the version manifest is only needed for creating profiles and may never arrive offline
         sender.input(AppMsg::NavigateToSection(Section::Home));
 
         // Load versions
@@ -436,7 +720,18 @@ impl SimpleComponent for AppModel {
 
         // Load settings
         let sender_clone = sender.clone();
-        let config_dir_clone = if let Some(l) = &model.launcher { l.config.minecraft_dir.clone() } else { std::path::PathBuf::from(".") };
+        let config_dir_clone = if let Some(l) = &model.launcher { l.config.config_dir.clone() } else { std::path::PathBuf::from(".") };
+
+        // Launcher's own log, the level is corrected once settings are loaded. Kept with the
+        // config, which stays put when the game data dir is moved in settings.
+        crate::logging::init(&config_dir_clone, LogLevel::default());
+        let sender_log = sender.clone();
+        crate::logging::set_ui_sink(move |line| sender_log.input(AppMsg::LauncherLog(line)));
+        tracing::info!("RCraft {} starting", env!("CARGO_PKG_VERSION"));
+        // Before anything reads settings or profiles
+        if let Some(launcher) = &model.launcher {
+            launcher.config.migrate_legacy_config();
+        }
         model.rt.spawn(async move {
             let settings = Settings::load(&config_dir_clone).await;
             sender_clone.input(AppMsg::SettingsLoaded(settings));
@@ -445,7 +740,7 @@ impl SimpleComponent for AppModel {
         // Load profiles
         let sender_clone = sender.clone();
         if let Some(launcher) = &model.launcher {
-            let config_dir = launcher.config.minecraft_dir.clone();
+            let config_dir = launcher.config.config_dir.clone();
             model.rt.spawn(async move {
                 let path = config_dir.join("profiles.json");
                 let profiles = if tokio::fs::try_exists(&path).await.unwrap_or(false) {
@@ -458,6 +753,12 @@ impl SimpleComponent for AppModel {
                 };
                 sender_clone.input(AppMsg::ProfilesLoaded(Ok(profiles)));
             });
+
+            let config_dir = launcher.config.config_dir.clone();
+            let sender_clone = sender.clone();
+            model.rt.spawn(async move {
+                sender_clone.input(AppMsg::AccountsLoaded(crate::auth::load_accounts(&config_dir).await));
+            });
         }
 
         ComponentParts { model, widgets }
@@ -467,26 +768,232 @@ impl SimpleComponent for AppModel {
         // Implementation of update logic
         match msg {
             AppMsg::NavigateToSection(section) => {
+                let was_mods = matches!(self.state, AppState::Ready { current_section: Section::Mods });
+                // Leaving the form abandons an edit, the next visit starts a fresh profile
+                let was_create = matches!(self.state, AppState::Ready { current_section: Section::CreateInstance });
+                if was_create && section != Section::CreateInstance && self.editing_profile.is_some() {
+                    self.reset_profile_form();
+                }
                 self.state = AppState::Ready { current_section: section };
+                if section == Section::Home {
+                    self.request_profile_sizes();
+                }
+                if section == Section::Settings {
+                    sender.input(AppMsg::RefreshInstalledVersions);
+                }
+                if section == Section::CreateInstance {
+                    // A new profile starts out with the default username and memory
+                    let fresh_form = self.editing_profile.is_none() && self.input_username.is_empty();
+                    if fresh_form {
+                        self.input_ram = self.settings.new_profile_ram_mb(crate::utils::get_total_memory_mb());
+                    }
+                    if let Some(username) = self.settings.default_username.clone().filter(|_| fresh_form) {
+                        self.input_username = username;
+                    }
+                    // Launches since the last visit may have downloaded versions
+                    if self.version_filter_installed {
+                        self.refresh_version_list();
+                    }
+                }
+
+                // Land in the search entry so typing searches right away.
+                // Deferred until the page is actually shown by update_view.
+                let entering_mods = section == Section::Mods && !was_mods;
+                if let Some(entry) = self.mod_search_entry.clone().filter(|_| entering_mods) {
+                    gtk::glib::idle_add_local_once(move || {
+                        entry.grab_focus();
+                    });
+                }
             }
 
             AppMsg::SettingsLoaded(settings) => {
                 self.settings = settings.clone();
                 // Apply loaded settings
                 self.sidebar_collapsed = settings.sidebar_collapsed;
+                self.settings_loaded = true;
+                self.apply_data_dir();
+                self.apply_instances_dir();
+                self.apply_options_template();
+                // Needs the data dir from settings, or it would clean up the default one
+                self.remove_leftover_natives_zips();
+                self.rt.spawn_blocking(|| {
+                    let freed = crate::config::evict_stale_cache();
+                    if freed > 0 {
+                        tracing::info!("Removed old cached files, freed {}", crate::utils::format_size(freed));
+                    }
+                });
+                if let Some(launcher) = &self.launcher {
+                    self.java_runtimes = launcher.java_manager.managed_runtimes();
+                }
+                self.refresh_java_runtimes_list();
+                self.migrate_legacy_profiles(&self.sender.clone());
+                self.request_profile_sizes();
                 self.sender.input(AppMsg::ToggleHideLogs(settings.hide_logs));
                 self.sender.input(AppMsg::ToggleHideMods(settings.hide_mods_button));
+                self.sender.input(AppMsg::ToggleVerifyFiles(settings.verify_files));
+                self.sender.input(AppMsg::LogLevelSelected(settings.log_level));
+                crate::http::configure(settings.max_retries, settings.max_backoff_secs);
+                crate::http::set_download_concurrency(settings.download_concurrency);
 
-                // Delay theme application to ensure window is fully realized or just apply it
-                let theme = settings.theme.clone();
-                let sender = self.sender.clone();
-                // Apply immediately
-                sender.input(AppMsg::ThemeSelected(theme));
+                self.apply_appearance();
             }
             AppMsg::ToggleHideMods(hide) => {
                 self.settings.hide_mods_button = hide;
                 self.save_settings();
             }
+            AppMsg::ToggleVerifyFiles(verify) => {
+                if self.settings.verify_files != verify {
+                    self.settings.verify_files = verify;
+                    self.save_settings();
+                }
+                if let Some(launcher) = &self.launcher {
+                    launcher.config.set_verify_integrity(verify);
+                }
+            }
+            AppMsg::DefaultUsernameChanged(username) => {
+                let username = Some(username.trim().to_string()).filter(|u| !u.is_empty());
+                if self.settings.default_username != username {
+                    self.settings.default_username = username;
+                    self.save_settings();
+                }
+            }
+            AppMsg::MaxRetriesChanged(retries) => {
+                self.settings.max_retries = retries;
+                crate::http::configure(self.settings.max_retries, self.settings.max_backoff_secs);
+                self.save_settings();
+            }
+            AppMsg::MaxBackoffChanged(secs) => {
+                self.settings.max_backoff_secs = secs;
+                crate::http::configure(self.settings.max_retries, self.settings.max_backoff_secs);
+                self.save_settings();
+            }
+            AppMsg::DownloadConcurrencyChanged(concurrency) => {
+                self.settings.download_concurrency = concurrency;
+                crate::http::set_download_concurrency(concurrency);
+                self.save_settings();
+            }
+            AppMsg::MaxLogLinesChanged(max_lines) => {
+                self.settings.max_log_lines = max_lines;
+                self.trim_logs();
+                self.save_settings();
+            }
+            AppMsg::ClearLogs => {
+                self.logs.set_text("");
+                self.raw_logs.clear();
+            }
+            AppMsg::ToggleLogTimestamps(timestamps) => {
+                self.settings.log_timestamps = timestamps;
+                self.save_settings();
+            }
+            AppMsg::CopyLogs => {
+                // The raw game output, without launcher timestamps or color codes
+                if let Some(window) = &self.window {
+                    window.clipboard().set_text(&format!("{}{}", self.logs_header(), self.raw_logs.iter().map(String::as_str).collect::<Vec<_>>().join("\n")));
+                }
+                sender.input(AppMsg::ShowToast("Logs copied to clipboard".to_string()));
+            }
+            AppMsg::SaveLogs => {
+                 let stamp = gtk::glib::DateTime::now_local().and_then(|now| now.format("%Y-%m-%d_%H-%M-%S")).unwrap_or_default();
+                 let sender_clone = sender.clone();
+                 self.pick_save_file("Save Game Logs", &format!("rcraft-{}.log", stamp), move |path| {
+                     sender_clone.input(AppMsg::WriteLogs(path));
+                 });
+            }
+            AppMsg::WriteLogs(path) => {
+                 // What the Game tab shows, timestamps included
+                 let (start, end) = self.logs.bounds();
+                 let text = format!("{}{}", self.logs_header(), self.logs.text(&start, &end, false));
+                 match crate::utils::write_atomic(&path, text.as_bytes()) {
+                     Ok(()) => sender.input(AppMsg::ShowToast(format!("Logs saved to {}", path.display()))),
+                     Err(e) => sender.input(AppMsg::ShowToast(format!("Could not save logs: {}", e))),
+                 }
+            }
+            AppMsg::ToggleColorizeLogs(colorize) => {
+                self.settings.colorize_logs = colorize;
+                self.save_settings();
+            }
+            AppMsg::DefaultRamChanged(ram_mb) => {
+                if self.settings.default_ram_mb == ram_mb { return; }
+                self.settings.default_ram_mb = ram_mb;
+                self.save_settings();
+            }
+            AppMsg::RamPercentChanged(percent) => {
+                if self.settings.ram_percent == percent { return; }
+                self.settings.ram_percent = percent;
+                self.save_settings();
+            }
+            AppMsg::ProfileSortChanged(sort) => {
+                // Also fired when update_view applies the loaded setting
+                if self.settings.profile_sort == sort { return; }
+                self.settings.profile_sort = sort;
+                self.save_settings();
+            }
+            AppMsg::TogglePreferStableMods(prefer) => {
+                self.settings.prefer_stable_mods = prefer;
+                self.save_settings();
+            }
+            AppMsg::ToggleCheckSystemLibraries(check) => {
+                self.settings.check_system_libraries = check;
+                self.save_settings();
+            }
+            AppMsg::ToggleMinimizeOnLaunch(minimize) => {
+                self.settings.minimize_on_launch = minimize;
+                if minimize {
+                    self.settings.quit_on_launch = false;
+                }
+                self.save_settings();
+            }
+            AppMsg::ToggleQuitOnLaunch(quit) => {
+                // Output is piped into the launcher, nothing is left to read it once we're gone
+                if quit && !self.settings.quit_on_launch && !self.settings.hide_logs {
+                    self.sender.input(AppMsg::ShowToast("Game logs won't be captured when the launcher quits on game start".to_string()));
+                }
+                self.settings.quit_on_launch = quit;
+                if quit {
+                    self.settings.minimize_on_launch = false;
+                }
+                self.save_settings();
+            }
+            AppMsg::ShowAboutWindow => {
+                if let Some(window) = &self.window {
+                    crate::ui::about::show_about_window(window);
+                }
+            }
+            AppMsg::CheckForUpdates => {
+                let sender_clone = sender.clone();
+                self.rt.spawn(async move {
+                    let result = crate::updates::check_for_update().await.map_err(|e| e.to_string());
+                    sender_clone.input(AppMsg::UpdateCheckFinished(result));
+                });
+            }
+            AppMsg::UpdateCheckFinished(result) => {
+                let Some(toast_overlay) = &self.toast_overlay else { return };
+                match result {
+                    Ok(Some(version)) => {
+                        let toast = adw::Toast::builder()
+                            .title(format!("RCraft {} is available", version))
+                            .button_label("Download")
+                            .timeout(0)
+                            .build();
+                        toast.connect_button_clicked(|_| {
+                            let _ = open::that(crate::updates::RELEASES_PAGE_URL);
+                        });
+                        toast_overlay.add_toast(toast);
+                    }
+                    Ok(None) => sender.input(AppMsg::ShowToast("RCraft is up to date".to_string())),
+                    Err(e) => sender.input(AppMsg::ShowToast(format!("Could not check for updates: {}", e))),
+                }
+            }
+            AppMsg::MinimizeWindow => {
+                if let Some(window) = &self.window {
+                    window.minimize();
+                }
+            }
+            AppMsg::CloseWindow => {
+                if let Some(window) = &self.window {
+                    window.close();
+                }
+            }
             AppMsg::ToggleHideLogs(hide) => {
                 self.settings.hide_logs = hide;
                 self.save_settings();
@@ -499,7 +1006,34 @@ impl SimpleComponent for AppModel {
             }
             AppMsg::Log(log_line) => {
                  let mut end_iter = self.logs.end_iter();
-                 self.logs.insert(&mut end_iter, &format!("{}\n", log_line));
+                 if self.settings.log_timestamps {
+                     let stamp = gtk::glib::DateTime::now_local().and_then(|now| now.format("%H:%M:%S")).unwrap_or_default();
+                     self.logs.insert_with_tags_by_name(&mut end_iter, &format!("[{}] ", stamp), &[LOG_TIMESTAMP_TAG]);
+                 }
+                 let line_start = end_iter.offset();
+                 let plain_line = crate::utils::strip_ansi(&log_line);
+                 let severity = severity_tag(&plain_line);
+                 self.raw_logs.push_back(plain_line);
+                 if self.settings.colorize_logs {
+                     for (text, color) in crate::utils::ansi_segments(&log_line) {
+                         match color {
+                             Some(color) => self.logs.insert_with_tags_by_name(&mut end_iter, &text, &[&ansi_tag_name(color)]),
+                             None => self.logs.insert(&mut end_iter, &text),
+                         }
+                     }
+                     self.logs.insert(&mut end_iter, "\n");
+                 } else {
+                     self.logs.insert(&mut end_iter, &format!("{}\n", crate::utils::strip_ansi(&log_line)));
+                 }
+                 if let Some(tag) = severity {
+                     self.logs.apply_tag_by_name(tag, &self.logs.iter_at_offset(line_start), &self.logs.end_iter());
+                 }
+                 self.trim_logs();
+            }
+            AppMsg::LauncherLog(log_line) => {
+                 let mut end_iter = self.launcher_logs.end_iter();
+                 self.launcher_logs.insert(&mut end_iter, &format!("{}\n", log_line));
+                 self.trim_logs();
             }
             AppMsg::VersionsLoaded(result) => {
                 match result {
@@ -515,18 +1049,14 @@ impl SimpleComponent for AppModel {
                         self.sorted_versions = filtered.iter().map(|v| v.id.clone()).collect();
                         self.available_versions = filtered;
                         self.versions_updated = true;
-
-                        if let Some(string_list) = &self.version_list_model {
-                            while string_list.n_items() > 0 {
-                                string_list.remove(0);
-                            }
-                            for version in &self.sorted_versions {
-                                string_list.append(version);
-                            }
-                        }
+                        self.versions_status = LoadStatus::Loaded;
+                        self.refresh_version_list();
                     }
                     Err(e) => {
+                        tracing::warn!("Failed to load versions: {}", e);
                         self.error_message = Some(format!("Failed to load versions: {}", e));
+                        self.versions_status = LoadStatus::Failed(e);
+                        sender.input(AppMsg::ShowToast("Could not load the version list, existing profiles can still be launched".to_string()));
                     }
                 }
             }
@@ -534,57 +1064,131 @@ impl SimpleComponent for AppModel {
                 match result {
                     Ok(profiles) => {
                         self.profiles = profiles;
+                        self.profiles_status = LoadStatus::Loaded;
+                        self.migrate_legacy_profiles(&sender);
                         self.refresh_mod_profile_dropdown(sender.clone());
+                        self.request_profile_sizes();
                     }
                     Err(e) => {
                         self.error_message = Some(format!("Failed to load profiles: {}", e));
+                        self.profiles_status = LoadStatus::Failed(e);
                     }
                 }
             }
             AppMsg::LaunchProfile(profile_name) => {
+                // Only the launch right after choosing to play offline skips signing in
+                let play_offline = self.offline_launch.take().is_some_and(|name| name == profile_name);
                 if let Some(profile) = self.profiles.get(&profile_name) {
                     if let Some(launcher) = &self.launcher {
                         let launcher_clone = launcher.clone();
-                        let profile_clone = profile.clone();
+                        let mut profile_clone = profile.clone();
                         let sender_clone = sender.clone();
 
+                        // Pre-flight: without the version files the launch has to download them,
+                        // which can't work if the version manifest couldn't even be fetched
+                        let downloaded = launcher.is_version_downloaded(&profile.version);
+                        let offline = matches!(self.versions_status, LoadStatus::Failed(_));
+                        if !downloaded && offline {
+                            self.state = AppState::Error {
+                                message: offline_message(&profile.version),
+                                retry: Some(RetryAction::LaunchProfile(profile_name.clone())),
+                            };
+                            return;
+                        }
+
                         self.state = AppState::Launching { version: profile_clone.version.clone() };
                         self.pending_launch_profile = Some(profile_name.clone());
+                        self.begin_operation(&sender);
+
+                        // Advisory only, the launch goes ahead either way
+                        if self.settings.check_system_libraries {
+                            let sender_check = sender.clone();
+                            self.rt.spawn_blocking(move || {
+                                let missing = crate::utils::missing_system_libraries();
+                                if !missing.is_empty() {
+                                    let details: Vec<String> = missing.iter().map(|(lib, hint)| format!("{} ({})", lib, hint)).collect();
+                                    sender_check.input(AppMsg::ShowToast(format!("Missing system libraries, the game may crash or show a black screen: {}", details.join(", "))));
+                                }
+                            });
+                        }
 
                         let profile_name_clone = profile_name.clone();
+                        let game_dir = self.get_game_dir(&profile_name);
+                        let account = profile_clone.account_id.as_ref()
+                            .and_then(|id| self.accounts.iter().find(|a| &a.uuid == id))
+                            .cloned();
+                        // Playing offline after a failed sign-in keeps the account's name
+                        let account = match account {
+                            Some(account) if play_offline => {
+                                profile_clone.username = account.username;
+                                None
+                            }
+                            account => account,
+                        };
 
-                        std::thread::spawn(move || {
-                            let rt = tokio::runtime::Runtime::new().unwrap(); // Should use shared runtime, but we inside update which is sync.
-                            // We can use self.rt if we clone it? We can't access self inside closure.
-                            // But we are in `update`, which has `&mut self`.
-                            // So we shouldn't use std::thread::spawn at all.
-                            // We should use self.rt.spawn.
-                            // But we are in a match arm block where we can't easily change the structure
-                            // effectively in this replacement_chunk without referencing `self`.
-                            // Wait, the block above `if let Some(profile)` allows us to access `self.rt`.
-                            // But `AppMsg::LaunchProfile` implementation is huge.
-                            // I will replace the whole block.
-                        });
-                        
-                        let rt = self.rt.clone();
-                        rt.spawn(async move {
+                        let task = self.rt.spawn(async move {
                             let sender_progress = sender_clone.clone();
                             let on_progress = move |pct: f64, msg: String| {
                                 sender_progress.input(AppMsg::DownloadProgress(pct, msg));
                             };
+                            let sender_installer = sender_clone.clone();
+
+                            let auth = match account {
+                                Some(account) if account.needs_refresh() => {
+                                    on_progress(0.05, format!("Signing in as {}...", account.username));
+                                    match crate::auth::refresh(&account).await {
+                                        Ok(fresh) => {
+                                            sender_clone.input(AppMsg::AccountRefreshed(fresh.clone()));
+                                            LaunchAuth::Online(fresh)
+                                        }
+                                        Err(e) => {
+                                            sender_clone.input(AppMsg::SignInFailed(profile_name_clone, account.username, e.to_string()));
+                                            return;
+                                        }
+                                    }
+                                }
+                                Some(account) => LaunchAuth::Online(account),
+                                None => LaunchAuth::Offline,
+                            };
+
+                            // Swap in the current set of shared mods, the profile may have changed since the last launch
+                            if let Some(dir) = &game_dir {
+                                let sources: Vec<std::path::PathBuf> = profile_clone.shared_mod_dirs.iter().map(std::path::PathBuf::from).collect();
+                                match crate::shared_mods::link(&dir.join("mods"), &sources) {
+                                    Ok(report) if !report.conflicts.is_empty() => {
+                                        for conflict in &report.conflicts {
+                                            tracing::warn!("Skipped shared mod {}", conflict);
+                                        }
+                                        sender_clone.input(AppMsg::ShowToast(format!("{} shared mods skipped because of name clashes, see the launcher log", report.conflicts.len())));
+                                    }
+                                    Ok(_) => {}
+                                    Err(e) => {
+                                        tracing::warn!("Could not link shared mods: {}", e);
+                                        sender_clone.input(AppMsg::ShowToast(format!("Could not add shared mods: {}", e)));
+                                    }
+                                }
+                            }
                             
                             // 1. Prepare and Launch
                             match launcher_clone.prepare_and_launch(
                                 profile_clone.version.clone(),
                                 profile_clone.username.clone(),
+                                auth,
                                 profile_clone.ram_mb,
-                                profile_clone.is_fabric,
-                                profile_clone.game_dir.as_ref().map(std::path::PathBuf::from),
-                                on_progress
+                                profile_clone.jvm_args.clone(),
+                                profile_clone.loader,
+                                game_dir,
+                                on_progress,
+                                move |installer, line| sender_installer.input(AppMsg::InstallerOutput(installer, line)),
                             ).await {
-                                Ok(mut command) => {
+                                Ok(PreparedLaunch { mut command, java_version }) => {
+                                    let java_path = std::path::PathBuf::from(command.as_std().get_program());
                                     match command.spawn() {
-                                        Ok(mut child) => {
+                                        Ok(child) => {
+                                            // Still cancellable until GameStarted, the game mustn't outlive that
+                                            let spawned = SpawnedGame::new(child);
+                                            sender_clone.input(AppMsg::JavaUsed(profile_name_clone.clone(), java_path.display().to_string(), Some(java_version)));
+                                            let mut child = spawned.claim();
                                             sender_clone.input(AppMsg::GameStarted);
                                             let start_time = std::time::Instant::now();
                                             let stdout = child.stdout.take();
@@ -609,14 +1213,18 @@ impl SimpleComponent for AppModel {
                                                 });
                                             }
 
-                                            let _ = child.wait().await;
+                                            let status = child.wait().await.ok();
                                             let duration = start_time.elapsed().as_secs();
-                                            sender_clone.input(AppMsg::SessionEnded(profile_name_clone, duration));
+                                            sender_clone.input(AppMsg::SessionEnded(profile_name_clone, duration, status));
                                             sender_clone.input(AppMsg::LaunchCompleted);
                                         }
-                                        Err(e) => sender_clone.input(AppMsg::Error(format!("Failed to spawn: {}", e))),
+                                        Err(e) => sender_clone.input(AppMsg::ErrorWithRetry(format!("Failed to spawn: {}", e), RetryAction::LaunchProfile(profile_name_clone))),
                                     }
                                 }
+                                // The network went away since startup
+                                Err(e) if !downloaded && is_network_error(&e) => {
+                                     sender_clone.input(AppMsg::ErrorWithRetry(offline_message(&profile_clone.version), RetryAction::LaunchProfile(profile_name_clone)));
+                                }
                                 Err(e) => {
                                      let err_str = e.to_string();
                                      if err_str.contains("Java Runtime") && err_str.contains("is missing") {
@@ -632,21 +1240,332 @@ impl SimpleComponent for AppModel {
                                              }
                                          }
                                      } 
-                                     sender_clone.input(AppMsg::Error(format!("Launch Failed: {}", e)));
+                                     sender_clone.input(AppMsg::ErrorWithRetry(format!("Launch Failed: {}", e), RetryAction::LaunchProfile(profile_name_clone)));
                                 }
                             }
                         });
+                        self.current_operation = Some(task.abort_handle());
+                    }
+                }
+            }
+            AppMsg::CopyLaunchCommand(profile_name) => {
+                let (Some(profile), Some(launcher)) = (self.profiles.get(&profile_name), &self.launcher) else { return };
+                let launcher_clone = launcher.clone();
+                let profile_clone = profile.clone();
+                let game_dir = self.get_game_dir(&profile_name);
+                let sender_clone = sender.clone();
+
+                sender.input(AppMsg::ShowToast("Resolving launch command...".to_string()));
+                self.rt.spawn(async move {
+                    // Same resolution as a real launch (may download missing files), but never spawned.
+                    // Always offline, an account's access token must not end up on the clipboard.
+                    let result = launcher_clone.prepare_and_launch(
+                        profile_clone.version.clone(),
+                        profile_clone.username.clone(),
+                        LaunchAuth::Offline,
+                        profile_clone.ram_mb,
+                        profile_clone.jvm_args.clone(),
+                        profile_clone.loader,
+                        game_dir,
+                        |_, _| {},
+                        |_, _| {},
+                    ).await
+                        .map(|launch| crate::launcher::format_command_line(&launch.command))
+                        .map_err(|e| e.to_string());
+                    sender_clone.input(AppMsg::LaunchCommandResolved(result));
+                });
+            }
+            AppMsg::RepairNatives(version) => {
+                let Some(launcher) = &self.launcher else { return };
+                let library_manager = launcher.library_manager.clone();
+                self.state = AppState::Downloading {
+                    title: "Repairing Natives".to_string(),
+                    version: version.clone(),
+                    progress: 0.0,
+                    status: format!("Downloading native libraries for {}...", version),
+                };
+                self.begin_operation(&sender);
+
+                let sender_clone = sender.clone();
+                let task = self.rt.spawn(async move {
+                    let result = library_manager.repair_natives(&version).await.map_err(|e| e.to_string());
+                    sender_clone.input(AppMsg::NativesRepaired(version, result));
+                });
+                self.current_operation = Some(task.abort_handle());
+            }
+            AppMsg::RefreshInstalledVersions => {
+                let Some(launcher) = &self.launcher else { return };
+                let launcher = launcher.clone();
+                let sender_clone = sender.clone();
+                self.rt.spawn(async move {
+                    sender_clone.input(AppMsg::InstalledVersionsLoaded(launcher.list_installed_versions().await));
+                });
+            }
+            AppMsg::InstalledVersionsLoaded(versions) => {
+                self.downloaded_versions = versions;
+                self.refresh_installed_versions_list();
+            }
+            AppMsg::RequestDeleteVersion(version) => {
+                let Some(window) = &self.window else { return };
+                let Some(installed) = self.downloaded_versions.iter().find(|v| v.id == version) else { return };
+                let users: Vec<&str> = profiles_using(installed, &self.profiles).iter().map(|p| p.username.as_str()).collect();
+                let mut body = format!("Frees {}. Worlds and mods in profiles are not touched.", crate::utils::format_size(installed.size));
+                if !users.is_empty() {
+                    body.push_str(&format!("\n\nUsed by {}. It is downloaded again the next time one of them is launched.", users.join(", ")));
+                }
+                let dialog = adw::MessageDialog::builder()
+                    .heading(format!("Delete {}?", version))
+                    .body(body)
+                    .transient_for(window)
+                    .modal(true)
+                    .build();
+                dialog.add_response("cancel", "Cancel");
+                dialog.add_response("delete", "Delete");
+                dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+                let sender_clone = sender.clone();
+                dialog.connect_response(None, move |d, response| {
+                    if response == "delete" {
+                        sender_clone.input(AppMsg::DeleteVersion(version.clone()));
+                    }
+                    d.close();
+                });
+                dialog.present();
+            }
+            AppMsg::DeleteVersion(version) => {
+                if matches!(self.state, AppState::Launching { .. } | AppState::Downloading { .. } | AppState::GameRunning { .. }) {
+                    sender.input(AppMsg::ShowToast("Versions can't be deleted while the game is running or downloading".to_string()));
+                    return;
+                }
+                let Some(launcher) = &self.launcher else { return };
+                let launcher = launcher.clone();
+                let sender_clone = sender.clone();
+                self.rt.spawn(async move {
+                    let result = launcher.delete_version(&version).await.map_err(|e| e.to_string());
+                    sender_clone.input(AppMsg::VersionDeleted(version, result));
+                });
+            }
+            AppMsg::VersionDeleted(version, result) => {
+                match result {
+                    Ok(()) => {
+                        tracing::info!("Deleted version {}", version);
+                        sender.input(AppMsg::ShowToast(format!("Deleted {}", version)));
+                    }
+                    Err(e) => sender.input(AppMsg::ShowToast(format!("Could not delete {}: {}", version, e))),
+                }
+                sender.input(AppMsg::RefreshInstalledVersions);
+            }
+            AppMsg::VerifyVersion(version, reinstall) => {
+                if matches!(self.state, AppState::Launching { .. } | AppState::Downloading { .. } | AppState::GameRunning { .. }) {
+                    sender.input(AppMsg::ShowToast("Wait for the game or the current download to finish".to_string()));
+                    return;
+                }
+                let Some(launcher) = &self.launcher else { return };
+                let launcher = launcher.clone();
+                self.state = AppState::Downloading {
+                    title: if reinstall { "Reinstalling".to_string() } else { "Verifying Files".to_string() },
+                    version: version.clone(),
+                    progress: 0.0,
+                    status: format!("Checking {}...", version),
+                };
+                self.begin_operation(&sender);
+
+                let sender_clone = sender.clone();
+                let task = self.rt.spawn(async move {
+                    let result = if reinstall {
+                        launcher.reinstall_version(&version).await
+                    } else {
+                        launcher.verify_version(&version).await
+                    }.map_err(|e| e.to_string());
+                    sender_clone.input(AppMsg::VersionVerified(version, reinstall, result));
+                });
+                self.current_operation = Some(task.abort_handle());
+            }
+            AppMsg::VersionVerified(version, reinstall, result) => {
+                self.state = AppState::Ready { current_section: Section::Settings };
+                match result {
+                    Ok(()) if reinstall => sender.input(AppMsg::ShowToast(format!("Reinstalled {}", version))),
+                    Ok(()) => sender.input(AppMsg::ShowToast(format!("{} verified", version))),
+                    Err(e) => {
+                        tracing::error!("Checking {} failed: {}", version, e);
+                        sender.input(AppMsg::ShowToast(format!("Could not check {}: {}", version, e)));
+                    }
+                }
+                sender.input(AppMsg::RefreshInstalledVersions);
+            }
+            AppMsg::NativesRepaired(version, result) => {
+                self.state = AppState::Ready { current_section: Section::Home };
+                match result {
+                    Ok(()) => sender.input(AppMsg::ShowToast(format!("Natives for {} repaired", version))),
+                    Err(e) => {
+                        tracing::error!("Repairing natives for {} failed: {}", version, e);
+                        sender.input(AppMsg::ShowToast(format!("Could not repair natives for {}: {}", version, e)));
+                    }
+                }
+            }
+            AppMsg::AccountsLoaded(accounts) => {
+                self.accounts = accounts;
+                self.refresh_accounts_list();
+            }
+            AppMsg::AddAccount => {
+                if self.sign_in_task.is_some() {
+                    sender.input(AppMsg::ShowToast("A sign in is already in progress".to_string()));
+                    return;
+                }
+                let sender_clone = sender.clone();
+                let task = self.rt.spawn(async move {
+                    let result = crate::auth::request_device_code().await.map_err(|e| e.to_string());
+                    sender_clone.input(AppMsg::DeviceCodeReceived(result));
+                });
+                self.sign_in_task = Some(task.abort_handle());
+                self.refresh_accounts_list();
+            }
+            AppMsg::DeviceCodeReceived(result) => {
+                let code = match result {
+                    Ok(code) => code,
+                    Err(e) => {
+                        self.sign_in_task = None;
+                        self.refresh_accounts_list();
+                        sender.input(AppMsg::ShowToast(format!("Could not start signing in: {}", e)));
+                        return;
+                    }
+                };
+                let Some(window) = &self.window else { return };
+                let dialog = adw::MessageDialog::builder()
+                    .heading("Sign in with Microsoft")
+                    .body(format!("Open {} in a browser and enter this code:\n\n{}\n\nRCraft waits in the background until the code has been entered.", code.verification_uri, code.user_code))
+                    .transient_for(window)
+                    .modal(true)
+                    .build();
+                dialog.add_response("cancel", "Cancel");
+                dialog.add_response("open", "Copy Code and Open");
+                dialog.set_response_appearance("open", adw::ResponseAppearance::Suggested);
+                dialog.set_default_response(Some("open"));
+                dialog.set_close_response("cancel");
+                let sender_clone = sender.clone();
+                let (user_code, uri) = (code.user_code.clone(), code.verification_uri.clone());
+                dialog.connect_response(None, move |d, response| {
+                    if response == "open" {
+                        d.clipboard().set_text(&user_code);
+                        let _ = open::that(&uri);
+                    } else {
+                        sender_clone.input(AppMsg::CancelSignIn);
+                    }
+                    d.close();
+                });
+                dialog.present();
+
+                let sender_clone = sender.clone();
+                let task = self.rt.spawn(async move {
+                    let result = crate::auth::complete_device_code(&code).await.map_err(|e| e.to_string());
+                    sender_clone.input(AppMsg::AccountSignedIn(result));
+                });
+                self.sign_in_task = Some(task.abort_handle());
+            }
+            AppMsg::CancelSignIn => {
+                if let Some(task) = self.sign_in_task.take() {
+                    task.abort();
+                }
+                self.refresh_accounts_list();
+            }
+            AppMsg::AccountSignedIn(result) => {
+                self.sign_in_task = None;
+                match result {
+                    Ok(account) => {
+                        sender.input(AppMsg::ShowToast(format!("Signed in as {}", account.username)));
+                        self.store_account(account);
+                    }
+                    Err(e) => {
+                        tracing::warn!("Sign in failed: {}", e);
+                        sender.input(AppMsg::ShowToast(format!("Sign in failed: {}", e)));
+                    }
+                }
+                self.refresh_accounts_list();
+            }
+            AppMsg::AccountRefreshed(account) => {
+                self.store_account(account);
+                self.refresh_accounts_list();
+            }
+            AppMsg::RemoveAccount(uuid) => {
+                self.accounts.retain(|a| a.uuid != uuid);
+                self.save_accounts();
+                // Profiles that played as it go back to offline
+                let mut profiles_changed = false;
+                for profile in self.profiles.values_mut().filter(|p| p.account_id.as_deref() == Some(uuid.as_str())) {
+                    profile.account_id = None;
+                    profiles_changed = true;
+                }
+                if profiles_changed {
+                    self.save_profiles(sender.clone());
+                }
+                if self.input_account.as_deref() == Some(uuid.as_str()) {
+                    self.input_account = None;
+                }
+                self.refresh_accounts_list();
+            }
+            AppMsg::AccountSelected(position) => {
+                self.input_account = position.checked_sub(1)
+                    .and_then(|i| self.accounts.get(i as usize))
+                    .map(|a| a.uuid.clone());
+            }
+            AppMsg::LaunchCommandResolved(result) => {
+                match result {
+                    Ok(command_line) => {
+                        if let Some(window) = &self.window {
+                            window.clipboard().set_text(&command_line);
+                        }
+                        sender.input(AppMsg::ShowToast("Launch command copied to clipboard".to_string()));
                     }
+                    Err(e) => sender.input(AppMsg::ShowToast(format!("Could not resolve launch command: {}", e))),
+                }
+            }
+            AppMsg::InstallerOutput(installer, line) => {
+                 // A loader installer runs before the game, shown as its own phase of the launch
+                 self.last_progress_at = Some(std::time::Instant::now());
+                 let version = match &self.state {
+                     AppState::Launching { version } | AppState::Downloading { version, .. } => version.clone(),
+                     _ => return,
+                 };
+                 let Some(line) = line else {
+                     self.state = AppState::Launching { version };
+                     return;
+                 };
+                 self.state = AppState::Downloading {
+                     title: format!("Installing {}", installer),
+                     version,
+                     progress: 0.3,
+                     status: crate::utils::strip_ansi(&line),
+                 };
+                 sender.input(AppMsg::Log(format!("[{} installer] {}", installer, line)));
+            }
+            AppMsg::JavaUsed(profile_name, path, version) => {
+                self.running_java = Some(std::path::PathBuf::from(&path));
+                if let Some(profile) = self.profiles.get_mut(&profile_name) {
+                    profile.last_java_path = Some(path);
+                    profile.last_java_version = version;
+                    self.save_profiles(sender.clone());
                 }
             }
             AppMsg::GameStarted => {
-                if let AppState::Launching { version } = &self.state {
+                if let AppState::Launching { version } | AppState::Downloading { version, .. } = &self.state {
                     self.state = AppState::GameRunning { version: version.clone() };
                 }
+                if self.settings.quit_on_launch {
+                    sender.input(AppMsg::CloseWindow);
+                } else if self.settings.minimize_on_launch {
+                    sender.input(AppMsg::MinimizeWindow);
+                }
             }
             AppMsg::DownloadProgress(progress, status) => {
-                 if let AppState::Downloading { version, .. } = &self.state {
-                      self.state = AppState::Downloading { version: version.clone(), progress, status };
+                 self.last_progress_at = Some(std::time::Instant::now());
+                 match &self.state {
+                      AppState::Downloading { title, version, .. } => {
+                           self.state = AppState::Downloading { title: title.clone(), version: version.clone(), progress, status };
+                      }
+                      // A launch reporting progress (libraries, assets...) trades the spinner for the bar
+                      AppState::Launching { version } => {
+                           self.state = AppState::Downloading { title: "Launching...".to_string(), version: version.clone(), progress, status };
+                      }
+                      _ => {}
                  }
             }
             AppMsg::ShowJavaDialog(version) => {
@@ -670,90 +1589,701 @@ impl SimpleComponent for AppModel {
                          
                          if let Some(profile) = self.profiles.get(profile_name) {
                              let version_id = profile.version.clone();
-                             self.state = AppState::Downloading { version: version_id.clone(), progress: 0.0, status: "Downloading Java...".to_string() };
+                             let vendor = self.settings.java_vendor;
+                             self.state = AppState::Downloading { title: "Downloading...".to_string(), version: version_id.clone(), progress: 0.0, status: "Downloading Java...".to_string() };
+                             self.begin_operation(&sender);
+
+                             let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+                             self.java_download_cancel = Some(cancel.clone());
 
                              self.rt.spawn(async move {
                                   let sender_clone_2 = sender_clone.clone();
-                                  match launcher_clone.prepare_java(&version_id, move |pct, msg| {
+                                  match launcher_clone.prepare_java(&version_id, vendor, cancel.clone(), move |pct, msg| {
                                        sender_clone_2.input(AppMsg::DownloadProgress(pct, msg));
                                   }).await {
                                        Ok(_) => sender_clone.input(AppMsg::LaunchProfile(profile_name_clone)),
-                                       Err(e) => sender_clone.input(AppMsg::Error(format!("Failed to download Java: {}", e))),
+                                       // CancelOperation already went back to Ready
+                                       Err(_) if cancel.load(std::sync::atomic::Ordering::Relaxed) => {}
+                                       Err(e) => sender_clone.input(AppMsg::ErrorWithRetry(format!("Failed to download Java: {}", e), RetryAction::InstallJavaAndLaunch(profile_name_clone))),
                                   }
                              });
                          }
                      }
                  }
             }
-            AppMsg::LaunchCompleted => {
-                self.state = AppState::Ready { current_section: Section::Home };
-            }
-            AppMsg::UsernameChanged(username) => {
-                self.input_username = username;
+            AppMsg::CheckJavaUpdates => {
+                 let Some(launcher) = &self.launcher else { return };
+                 let java_manager = launcher.java_manager.clone();
+                 self.java_updates_checking = true;
+                 self.refresh_java_runtimes_list();
+                 let sender_clone = sender.clone();
+                 self.rt.spawn(async move {
+                     sender_clone.input(AppMsg::JavaUpdatesChecked(java_manager.check_runtime_updates().await));
+                 });
             }
-            AppMsg::RamChanged(ram) => {
-                self.input_ram = ram;
+            AppMsg::JavaUpdatesChecked(runtimes) => {
+                 self.java_updates_checking = false;
+                 let failed = runtimes.iter().all(|r| r.latest_build.is_none());
+                 let updates = runtimes.iter().filter(|r| r.update_available()).count();
+                 self.java_runtimes = runtimes;
+                 self.refresh_java_runtimes_list();
+                 let message = match (failed && !self.java_runtimes.is_empty(), updates) {
+                     (true, _) => "Could not check for Java updates".to_string(),
+                     (false, 0) => "Java runtimes are up to date".to_string(),
+                     (false, 1) => "A Java update is available".to_string(),
+                     (false, n) => format!("{} Java updates are available", n),
+                 };
+                 sender.input(AppMsg::ShowToast(message));
             }
-            AppMsg::VersionSelected(version) => {
-                use crate::utils::is_at_least_1_14;
-                if is_at_least_1_14(&version) {
-                    self.fabric_switch_enabled = true;
-                } else {
-                    self.fabric_switch_enabled = false;
-                    self.input_install_fabric = false;
-                }
-                self.input_version = Some(version);
+            AppMsg::UpdateJavaRuntime(version, vendor) => {
+                 let Some(launcher) = &self.launcher else { return };
+                 if matches!(self.state, AppState::Launching { .. } | AppState::Downloading { .. }) {
+                     sender.input(AppMsg::ShowToast("Wait for the current download to finish".to_string()));
+                     return;
+                 }
+                 let java_manager = launcher.java_manager.clone();
+                 // The update replaces the runtime's folder, which a running game still has open
+                 let runtime_dir = java_manager.runtime_dir(version, vendor);
+                 if self.running_java.as_ref().is_some_and(|java| java.starts_with(&runtime_dir)) {
+                     sender.input(AppMsg::ShowToast(format!("Close the game before updating Java {}, it's running on it", version)));
+                     return;
+                 }
+                 self.state = AppState::Downloading { title: "Downloading...".to_string(), version: format!("Java {}", version), progress: 0.0, status: "Downloading Java...".to_string() };
+                 self.begin_operation(&sender);
+
+                 let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+                 self.java_download_cancel = Some(cancel.clone());
+
+                 let sender_clone = sender.clone();
+                 self.rt.spawn(async move {
+                     let sender_progress = sender_clone.clone();
+                     let result = java_manager.update_java(version, vendor, cancel.clone(), move |pct, msg| {
+                         sender_progress.input(AppMsg::DownloadProgress(pct, msg));
+                     }).await;
+                     if !cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                         sender_clone.input(AppMsg::JavaRuntimeUpdated(version, vendor, result.map(|_| ()).map_err(|e| e.to_string())));
+                     }
+                 });
             }
-            AppMsg::ClearPendingSelection => {
-                 self.pending_mod_selection = None;
+            AppMsg::DownloadJava(version) => {
+                 let Some(launcher) = &self.launcher else { return };
+                 if matches!(self.state, AppState::Launching { .. } | AppState::Downloading { .. }) {
+                     sender.input(AppMsg::ShowToast("Wait for the current download to finish".to_string()));
+                     return;
+                 }
+                 let java_manager = launcher.java_manager.clone();
+                 let vendor = self.settings.java_vendor;
+                 self.state = AppState::Downloading { title: "Downloading...".to_string(), version: format!("Java {}", version), progress: 0.0, status: "Downloading Java...".to_string() };
+                 self.begin_operation(&sender);
+
+                 let cancel = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+                 self.java_download_cancel = Some(cancel.clone());
+
+                 let sender_clone = sender.clone();
+                 self.rt.spawn(async move {
+                     let sender_progress = sender_clone.clone();
+                     let result = java_manager.download_and_install_java(version, vendor, cancel.clone(), move |pct, msg| {
+                         sender_progress.input(AppMsg::DownloadProgress(pct, msg));
+                     }).await;
+                     if !cancel.load(std::sync::atomic::Ordering::Relaxed) {
+                         sender_clone.input(AppMsg::JavaDownloaded(version, vendor, result.map(|_| ()).map_err(|e| e.to_string())));
+                     }
+                 });
             }
-            AppMsg::ModDropdownUpdated => {
-                 self.mod_profile_list_updated = false;
+            AppMsg::JavaDownloaded(version, vendor, result) => {
+                 self.state = AppState::Ready { current_section: Section::Settings };
+                 match result {
+                     Ok(()) => {
+                         if let Some(launcher) = &self.launcher {
+                             self.java_runtimes = launcher.java_manager.managed_runtimes();
+                         }
+                         self.refresh_java_runtimes_list();
+                         sender.input(AppMsg::ShowToast(format!("{} Java {} installed", vendor, version)));
+                     }
+                     Err(e) => sender.input(AppMsg::ShowToast(format!("Failed to install Java {}: {}", version, e))),
+                 }
             }
-            AppMsg::ToggleFabric(install) => {
-                self.input_install_fabric = install;
+            AppMsg::JavaRuntimeUpdated(version, vendor, result) => {
+                 self.state = AppState::Ready { current_section: Section::Settings };
+                 match result {
+                     Ok(()) => {
+                         if let Some(runtime) = self.java_runtimes.iter_mut().find(|r| r.version == version && r.vendor == vendor) {
+                             runtime.installed_build = runtime.latest_build.clone();
+                         }
+                         self.refresh_java_runtimes_list();
+                         sender.input(AppMsg::ShowToast(format!("{} Java {} updated", vendor, version)));
+                     }
+                     Err(e) => sender.input(AppMsg::ShowToast(format!("Failed to update Java {}: {}", version, e))),
+                 }
+            }
+            AppMsg::OperationTick => {
+                 let busy = matches!(self.state, AppState::Launching { .. } | AppState::Downloading { .. });
+                 if !busy {
+                     self.operation_started = None;
+                     self.last_progress_at = None;
+                     self.current_operation = None;
+                     self.java_download_cancel = None;
+                     if let Some(timer) = self.operation_timer.take() {
+                         timer.remove();
+                     }
+                 }
+            }
+            AppMsg::CancelOperation => {
+                 if let Some(task) = self.current_operation.take() {
+                     task.abort();
+                 }
+                 if let Some(cancel) = self.java_download_cancel.take() {
+                     cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+                 }
+                 self.pending_launch_profile = None;
+                 self.operation_started = None;
+                 self.state = AppState::Ready { current_section: Section::Home };
+                 sender.input(AppMsg::ShowToast("Operation cancelled".to_string()));
+            }
+            AppMsg::LaunchCompleted => {
+                self.state = AppState::Ready { current_section: Section::Home };
+            }
+            AppMsg::UsernameChanged(username) => {
+                self.input_username = username;
+            }
+            AppMsg::JvmArgsChanged(args) => {
+                self.input_jvm_args = args;
+            }
+            AppMsg::NotesChanged(notes) => {
+                self.input_notes = notes;
+            }
+            AppMsg::RamChanged(ram) => {
+                self.input_ram = ram;
+            }
+            AppMsg::ToggleInstalledVersionsFilter(active) => {
+                self.version_filter_installed = active;
+                self.refresh_version_list();
+            }
+            AppMsg::ToggleFavoriteVersionsFilter(active) => {
+                self.version_filter_favorites = active;
+                self.refresh_version_list();
+            }
+            AppMsg::SetVersionFavorite(favorite) => {
+                let Some(version) = self.input_version.clone() else { return };
+                let favorites = &mut self.settings.favorite_versions;
+                favorites.retain(|v| *v != version);
+                if favorite {
+                    favorites.push(version);
+                }
+                self.save_settings();
+                if self.version_filter_favorites {
+                    self.refresh_version_list();
+                }
+            }
+            AppMsg::VersionSelected(version) => {
+                self.input_version = Some(version);
+                self.check_loader_availability(&sender);
+            }
+            AppMsg::LoaderSelected(loader) => {
+                self.input_loader = loader;
+                self.check_loader_availability(&sender);
+            }
+            AppMsg::LoaderAvailabilityChecked(version, loader, result) => {
+                let available = match result {
+                    Ok(available) => {
+                        self.loader_support.insert((version.clone(), loader), available);
+                        available
+                    }
+                    // Offline, fall back to the range Fabric and Quilt have always covered
+                    Err(_) => crate::utils::is_at_least_1_14(&version),
+                };
+                // Ignore answers for a version or loader that is no longer selected
+                if self.input_version.as_deref() == Some(version.as_str()) && self.input_loader == loader {
+                    self.loader_checking = false;
+                    self.loader_available = available;
+                }
+            }
+            AppMsg::ChooseGameDir => {
+                 let sender_clone = sender.clone();
+                 self.pick_folder("Choose Game Directory", move |path| {
+                     sender_clone.input(AppMsg::GameDirSelected(Some(path.display().to_string())));
+                 });
+            }
+            AppMsg::ChooseDataDir => {
+                 let sender_clone = sender.clone();
+                 self.pick_folder("Choose Minecraft Folder", move |path| {
+                     sender_clone.input(AppMsg::DataDirSelected(Some(path.display().to_string())));
+                 });
+            }
+            AppMsg::DataDirSelected(dir) => {
+                 // A running install would keep writing into the old dir
+                 if matches!(self.state, AppState::Launching { .. } | AppState::Downloading { .. }) {
+                     sender.input(AppMsg::ShowToast("Wait for the current download to finish".to_string()));
+                     return;
+                 }
+                 let check = dir.as_deref().map(|d| crate::utils::check_dir_writable(std::path::Path::new(d)));
+                 if let Some(Err(e)) = check {
+                     sender.input(AppMsg::ShowToast(format!("{} is not writable: {}", dir.unwrap_or_default(), e)));
+                     return;
+                 }
+                 self.settings.custom_data_dir = dir;
+                 self.apply_data_dir();
+                 self.apply_instances_dir();
+                 self.save_settings();
+                 if let Some(launcher) = &self.launcher {
+                     self.java_runtimes = launcher.java_manager.managed_runtimes();
+                 }
+                 self.refresh_java_runtimes_list();
+                 self.remeasure_profile_sizes();
+                 sender.input(AppMsg::RefreshInstalledVersions);
+                 sender.input(AppMsg::RefreshInstalledMods);
+            }
+            AppMsg::ChooseInstancesDir => {
+                 let sender_clone = sender.clone();
+                 self.pick_folder("Choose Instances Folder", move |path| {
+                     sender_clone.input(AppMsg::InstancesDirSelected(Some(path.display().to_string())));
+                 });
+            }
+            AppMsg::InstancesDirSelected(dir) => {
+                 let check = dir.as_deref().map(|d| crate::utils::check_dir_writable(std::path::Path::new(d)));
+                 if let Some(Err(e)) = check {
+                     sender.input(AppMsg::ShowToast(format!("{} is not writable: {}", dir.unwrap_or_default(), e)));
+                     return;
+                 }
+                 self.settings.instances_dir = dir;
+                 self.apply_instances_dir();
+                 self.save_settings();
+                 self.remeasure_profile_sizes();
+                 sender.input(AppMsg::RefreshInstalledMods);
+            }
+            AppMsg::ChooseOptionsTemplate => {
+                 let sender_clone = sender.clone();
+                 self.pick_file("Choose options.txt Template", move |path| {
+                     sender_clone.input(AppMsg::OptionsTemplateSelected(Some(path.display().to_string())));
+                 });
+            }
+            AppMsg::OptionsTemplateSelected(path) => {
+                 self.settings.options_template = path;
+                 self.apply_options_template();
+                 self.save_settings();
+            }
+            AppMsg::GameDirSelected(dir) => {
+                 let check = dir.as_deref().map(|d| crate::utils::check_dir_writable(std::path::Path::new(d)));
+                 if let Some(Err(e)) = check {
+                     sender.input(AppMsg::ShowToast(format!("{} is not writable: {}", dir.unwrap_or_default(), e)));
+                     return;
+                 }
+                 self.input_game_dir = dir;
+            }
+            AppMsg::ChooseSharedModDir => {
+                 let sender_clone = sender.clone();
+                 self.pick_folder("Choose Shared Mod Folder", move |path| {
+                     sender_clone.input(AppMsg::SharedModDirAdded(path.display().to_string()));
+                 });
+            }
+            AppMsg::SharedModDirAdded(dir) => {
+                 if !self.input_shared_mod_dirs.contains(&dir) {
+                     self.input_shared_mod_dirs.push(dir);
+                 }
+            }
+            AppMsg::ClearSharedModDirs => {
+                 self.input_shared_mod_dirs.clear();
+            }
+            AppMsg::ClearPendingSelection => {
+                 self.pending_mod_selection = None;
+            }
+            AppMsg::ModDropdownUpdated => {
+                 self.mod_profile_list_updated = false;
+            }
+            AppMsg::EditProfile(profile_name) => {
+                let Some(profile) = self.profiles.get(&profile_name) else { return };
+                self.input_username = profile.username.clone();
+                self.input_ram = profile.ram_mb;
+                self.input_loader = profile.loader;
+                self.input_game_dir = profile.game_dir.clone();
+                self.input_shared_mod_dirs = profile.shared_mod_dirs.clone();
+                self.input_notes = profile.notes.clone().unwrap_or_default();
+                self.input_account = profile.account_id.clone();
+                self.input_jvm_args = profile.jvm_args.join(" ");
+                self.input_version = Some(profile.version.clone());
+                self.editing_profile = Some(profile_name);
+                // Re-run the loader availability check for the loaded version
+                sender.input(AppMsg::VersionSelected(profile.version.clone()));
+                sender.input(AppMsg::NavigateToSection(Section::CreateInstance));
+            }
+            AppMsg::CancelEditProfile => {
+                self.reset_profile_form();
+                sender.input(AppMsg::NavigateToSection(Section::Home));
             }
             AppMsg::SaveProfile => {
                 if self.input_username.trim().is_empty() { return; }
                 if self.input_version.is_none() { return; }
 
                 let selected_version = self.input_version.clone().unwrap();
-                let is_fabric = self.input_install_fabric && self.fabric_switch_enabled;
+                // A loader without builds for the version (or not confirmed yet) isn't saved
+                let loader = if self.loader_available { self.input_loader } else { Loader::Vanilla };
 
+                let edited = self.editing_profile.as_ref().and_then(|key| self.profiles.get(key));
+                // Editing keeps the id, so username/version changes never move or orphan the instance
+                let profile_name = self.editing_profile.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
                 let profile = Profile {
+                    id: profile_name.clone(),
                     username: self.input_username.clone(),
                     version: selected_version.clone(),
                     ram_mb: self.input_ram,
-                    playtime_seconds: 0,
-                    last_launch: None,
-                    is_fabric,
-                    game_dir: None,
-                };
-
-                let profile_name = if is_fabric {
-                    format!("{}_{}_fabric", profile.username, profile.version)
-                } else {
-                    format!("{}_{}", profile.username, profile.version)
+                    // Editing keeps the history
+                    playtime_seconds: edited.map_or(0, |p| p.playtime_seconds),
+                    last_launch: edited.and_then(|p| p.last_launch),
+                    loader,
+                    game_dir: self.input_game_dir.clone(),
+                    notes: Some(self.input_notes.trim().to_string()).filter(|n| !n.is_empty()),
+                    last_crashed: edited.is_some_and(|p| p.last_crashed),
+                    last_exit_code: edited.and_then(|p| p.last_exit_code),
+                    shared_mod_dirs: self.input_shared_mod_dirs.clone(),
+                    last_java_path: edited.and_then(|p| p.last_java_path.clone()),
+                    last_java_version: edited.and_then(|p| p.last_java_version),
+                    account_id: self.input_account.clone(),
+                    jvm_args: self.input_jvm_args.split_whitespace().map(str::to_string).collect(),
+                    created_at: edited.map_or(Some(crate::utils::unix_now()), |p| p.created_at),
+                    modpack: edited.and_then(|p| p.modpack.clone()),
                 };
 
                 self.profiles.insert(profile_name.clone(), profile);
+                self.forget_profile_size(&profile_name);
                 self.refresh_mod_profile_dropdown(sender.clone());
                 
                 // If this is the new profile we want to select
                 // (Empty loop originally meant for selection logic removed as it was unused)
                 
                 self.save_profiles(sender.clone());
-
-                self.input_username.clear();
-                self.input_version = None;
-                self.input_ram = 4096;
-                self.input_install_fabric = false;
-                self.fabric_switch_enabled = false;
+                self.reset_profile_form();
 
                 sender.input(AppMsg::NavigateToSection(Section::Home));
             }
+            AppMsg::ChooseModpack => {
+                 let dialog = gtk::FileChooserNative::new(
+                     Some("Install Modpack"),
+                     self.window.as_ref(),
+                     gtk::FileChooserAction::Open,
+                     Some("Install"),
+                     Some("Cancel"),
+                 );
+                 let filter = gtk::FileFilter::new();
+                 filter.set_name(Some("Modrinth Modpacks"));
+                 filter.add_pattern("*.mrpack");
+                 dialog.add_filter(&filter);
+
+                 let sender_clone = sender.clone();
+                 let dialog_clone = dialog.clone();
+                 dialog.connect_response(move |d, response| {
+                     let picked = d.file().and_then(|f| f.path()).filter(|_| response == gtk::ResponseType::Accept);
+                     if let Some(path) = picked {
+                         sender_clone.input(AppMsg::InstallModpack(path));
+                     }
+                     dialog_clone.destroy();
+                 });
+                 dialog.show();
+            }
+            AppMsg::FileDropped(path) => {
+                 let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase()).unwrap_or_default();
+                 let is_modpack = extension == "mrpack" || (extension == "zip" && crate::modpack::read_index(&path).is_ok());
+                 let is_profiles = extension == "json" || (extension == "zip" && crate::profile_bundle::is_bundle(&path));
+                 if is_modpack {
+                     sender.input(AppMsg::InstallModpack(path));
+                 } else if is_profiles {
+                     sender.input(AppMsg::ImportProfiles(path));
+                 } else if extension == "jar" {
+                     sender.input(AppMsg::AddModFile(path));
+                 } else {
+                     let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                     sender.input(AppMsg::ShowToast(format!("{} is not a modpack, profile bundle or mod", name)));
+                 }
+            }
+            AppMsg::AddModFile(path) => {
+                 let Some(mods_dir) = self.get_install_dir(ProjectType::Mod) else {
+                     sender.input(AppMsg::ShowToast("Pick a profile on the Mods page to add mods to".to_string()));
+                     return;
+                 };
+                 let filename = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                 let sender_clone = sender.clone();
+                 self.rt.spawn_blocking(move || {
+                     // Never over a jar of the same name, enabled or not
+                     let result = if crate::mod_index::installed_path(&mods_dir, &filename).is_some() {
+                         Err(format!("{} is already installed", filename))
+                     } else {
+                         std::fs::create_dir_all(&mods_dir)
+                             .and_then(|()| std::fs::copy(&path, mods_dir.join(&filename)))
+                             .map(|_| ())
+                             .map_err(|e| format!("Could not add {}: {}", filename, e))
+                     };
+                     sender_clone.input(AppMsg::ModFileAdded(mods_dir, filename, result));
+                 });
+            }
+            AppMsg::ModFileAdded(mods_dir, filename, result) => {
+                 match result {
+                     Ok(()) => sender.input(AppMsg::ShowToast(format!("Added {}", filename))),
+                     Err(e) => sender.input(AppMsg::ShowToast(e)),
+                 }
+                 // The Mods page may have moved on to another profile meanwhile
+                 if self.get_install_dir(ProjectType::Mod).as_ref() == Some(&mods_dir) {
+                     sender.input(AppMsg::RefreshInstalledMods);
+                 }
+            }
+            AppMsg::ImportProfiles(path) => {
+                 let profiles = match crate::profile_bundle::read(&path) {
+                     Ok(profiles) if !profiles.is_empty() => profiles,
+                     Ok(_) => {
+                         sender.input(AppMsg::ShowToast("No profiles to import".to_string()));
+                         return;
+                     }
+                     Err(e) => {
+                         sender.input(AppMsg::ShowToast(format!("Could not import profiles: {}", e)));
+                         return;
+                     }
+                 };
+                 let Some(window) = &self.window else { return };
+
+                 let shown: Vec<String> = profiles.iter().take(8)
+                     .map(|p| match p.loader_name() {
+                         Some(loader) => format!("{} - {} ({})", p.username, p.version, loader),
+                         None => format!("{} - {}", p.username, p.version),
+                     })
+                     .collect();
+                 let more = profiles.len().saturating_sub(shown.len());
+                 let mut body = format!("These profiles will be created:\n\n{}", shown.join("\n"));
+                 if more > 0 {
+                     body.push_str(&format!("\n...and {} more", more));
+                 }
+                 let dialog = adw::MessageDialog::builder()
+                     .heading("Import Profiles?")
+                     .body(body)
+                     .transient_for(window)
+                     .modal(true)
+                     .build();
+                 dialog.add_response("cancel", "Cancel");
+                 dialog.add_response("import", "Import");
+                 dialog.set_response_appearance("import", adw::ResponseAppearance::Suggested);
+                 dialog.set_default_response(Some("import"));
+                 let sender_clone = sender.clone();
+                 let profiles = std::cell::RefCell::new(Some(profiles));
+                 dialog.connect_response(None, move |d, response| {
+                     if let Some(profiles) = profiles.take().filter(|_| response == "import") {
+                         sender_clone.input(AppMsg::ProfilesImported(profiles));
+                     }
+                     d.close();
+                 });
+                 dialog.present();
+            }
+            AppMsg::ProfilesImported(profiles) => {
+                 let count = profiles.len();
+                 for mut profile in profiles {
+                     // Fresh ids: the bundle may come from this very install
+                     profile.id = uuid::Uuid::new_v4().to_string();
+                     profile.playtime_seconds = 0;
+                     profile.last_launch = None;
+                     profile.last_crashed = false;
+                     profile.last_exit_code = None;
+                     profile.last_java_path = None;
+                     profile.last_java_version = None;
+                     // The instance didn't come along, a folder from the other install would be
+                     // missing here or, worse, another profile's
+                     profile.game_dir = None;
+                     self.profiles.insert(profile.id.clone(), profile);
+                 }
+                 self.save_profiles(sender.clone());
+                 self.refresh_mod_profile_dropdown(sender.clone());
+                 self.request_profile_sizes();
+                 sender.input(AppMsg::ShowToast(format!("Imported {} profile{}", count, if count == 1 { "" } else { "s" })));
+            }
+            AppMsg::DownloadModpack(slug) => {
+                 let modrinth = self.modrinth.clone();
+                 let prefer_stable = self.settings.prefer_stable_mods;
+                 let sender_clone = sender.clone();
+                 sender.input(AppMsg::ShowToast(format!("Downloading modpack {}...", slug)));
+                 self.rt.spawn(async move {
+                     let result = async {
+                         let versions = modrinth.get_versions(&slug, None, None).await?;
+                         let version = crate::models::pick_mod_version(&versions, prefer_stable)
+                             .ok_or_else(|| format!("{} has no versions", slug))?;
+                         let file = version.files.iter().find(|f| f.primary).or(version.files.first())
+                             .ok_or_else(|| format!("{} has no file to download", version.name))?;
+                         let path = crate::config::cache_dir().join("modpacks").join(&file.filename);
+                         modrinth.download_mod(&file.url, &path, |_| {}).await?;
+                         Ok::<_, Box<dyn std::error::Error + Send + Sync>>(path)
+                     }.await;
+                     match result {
+                         Ok(path) => sender_clone.input(AppMsg::InstallModpack(path)),
+                         Err(e) => sender_clone.input(AppMsg::ShowToast(format!("Could not download modpack {}: {}", slug, e))),
+                     }
+                 });
+            }
+            AppMsg::InstallModpack(pack_path) => {
+                 let Some(launcher) = &self.launcher else { return };
+                 if self.pending_modpack.is_some() {
+                     sender.input(AppMsg::ShowToast("Answer the open modpack install first".to_string()));
+                     return;
+                 }
+                 let instances_dir = launcher.config.instances_dir.clone();
+                 let profile_dirs: Vec<(String, std::path::PathBuf)> = self.profiles.keys()
+                     .filter_map(|name| self.get_game_dir(name).map(|dir| (name.clone(), dir)))
+                     .collect();
+                 let sender_clone = sender.clone();
+                 self.rt.spawn_blocking(move || {
+                     let result = (|| -> anyhow::Result<ModpackJob> {
+                         let index = crate::modpack::read_index(&pack_path)?;
+                         if let Some(loader) = index.unsupported_loader() {
+                             anyhow::bail!("{} needs {}, which RCraft can't install yet", index.name, loader);
+                         }
+                         // Same pack installed before: update that profile in place
+                         let existing = profile_dirs.into_iter().find(|(_, dir)| {
+                             crate::modpack::InstalledPack::load(dir).is_some_and(|p| p.index.name == index.name)
+                         });
+                         let (profile_name, game_dir) = match existing {
+                             Some((name, dir)) => (Some(name), dir),
+                             None => (None, instances_dir.join(crate::utils::dir_name_for(&index.name))),
+                         };
+                         let plan = crate::modpack::plan(&pack_path, &index, &game_dir)?;
+                         Ok(ModpackJob { pack_path, index, plan, game_dir, profile_name })
+                     })();
+                     sender_clone.input(AppMsg::ModpackPlanned(result.map_err(|e| e.to_string())));
+                 });
+            }
+            AppMsg::ModpackPlanned(result) => {
+                 let job = match result {
+                     Ok(job) => job,
+                     Err(e) => {
+                         sender.input(AppMsg::ShowToast(format!("Could not read modpack: {}", e)));
+                         return;
+                     }
+                 };
+                 // Two packs dropped together, the dialog for the first one is still open
+                 if self.pending_modpack.is_some() {
+                     sender.input(AppMsg::ShowToast(format!("Answer the open modpack install before installing {}", job.index.name)));
+                     return;
+                 }
+                 let Some(window) = &self.window else { return };
+
+                 // What will happen, so nothing is created or overwritten by surprise
+                 let target = match job.profile_name.as_ref().and_then(|name| self.profiles.get(name)) {
+                     Some(profile) => format!("Updates the profile {} - {}.", profile.username, profile.version),
+                     None => format!("Creates a new {}profile for Minecraft {} in {}.",
+                         job.index.loader().id().map(|_| format!("{} ", job.index.loader().name())).unwrap_or_default(),
+                         job.index.minecraft_version().unwrap_or("?"),
+                         job.game_dir.display()),
+                 };
+                 let mut body = format!("{}\n{} files to download, {} to remove.", target, job.plan.download.len(), job.plan.remove.len());
+
+                 let conflicts = &job.plan.conflicts;
+                 if !conflicts.is_empty() {
+                     let shown: Vec<&str> = conflicts.iter().take(8).map(String::as_str).collect();
+                     let more = conflicts.len().saturating_sub(shown.len());
+                     body.push_str(&format!("\n\nThese files were changed since the modpack was installed:\n\n{}", shown.join("\n")));
+                     if more > 0 {
+                         body.push_str(&format!("\n...and {} more", more));
+                     }
+                 }
+
+                 let dialog = adw::MessageDialog::builder()
+                     .heading(format!("Install {} {}?", job.index.name, job.index.version_id))
+                     .body(body)
+                     .transient_for(window)
+                     .modal(true)
+                     .build();
+                 dialog.add_response("cancel", "Cancel");
+                 if conflicts.is_empty() {
+                     dialog.add_response("keep", "Install");
+                     dialog.set_response_appearance("keep", adw::ResponseAppearance::Suggested);
+                 } else {
+                     dialog.add_response("keep", "Keep Mine");
+                     dialog.add_response("overwrite", "Use Modpack's");
+                     dialog.set_response_appearance("overwrite", adw::ResponseAppearance::Destructive);
+                 }
+                 dialog.set_default_response(Some("keep"));
+                 self.pending_modpack = Some(job);
+                 let sender_clone = sender.clone();
+                 dialog.connect_response(None, move |d, response| {
+                     match response {
+                         "cancel" => sender_clone.input(AppMsg::CancelModpack),
+                         _ => sender_clone.input(AppMsg::ApplyModpack(response == "overwrite")),
+                     }
+                     d.close();
+                 });
+                 dialog.present();
+            }
+            AppMsg::CancelModpack => {
+                 self.pending_modpack = None;
+            }
+            AppMsg::ApplyModpack(overwrite_conflicts) => {
+                 let Some(job) = self.pending_modpack.take() else { return };
+                 self.state = AppState::Downloading { title: "Downloading...".to_string(), version: job.index.name.clone(), progress: 0.0, status: "Installing modpack...".to_string() };
+                 self.begin_operation(&sender);
+
+                 let sender_clone = sender.clone();
+                 let task = self.rt.spawn(async move {
+                     let sender_progress = sender_clone.clone();
+                     let result = crate::modpack::apply(&job.pack_path, &job.index, &job.plan, &job.game_dir, overwrite_conflicts, move |pct, msg| {
+                         sender_progress.input(AppMsg::DownloadProgress(pct, msg));
+                     }).await;
+                     sender_clone.input(AppMsg::ModpackInstalled(result.map(|_| job).map_err(|e| e.to_string())));
+                 });
+                 self.current_operation = Some(task.abort_handle());
+            }
+            AppMsg::ModpackInstalled(result) => {
+                 self.state = AppState::Ready { current_section: Section::Home };
+                 let job = match result {
+                     Ok(job) => job,
+                     Err(e) => {
+                         tracing::error!("Modpack install failed: {}", e);
+                         sender.input(AppMsg::Error(format!("Failed to install modpack: {}", e)));
+                         return;
+                     }
+                 };
+
+                 let summary = if job.profile_name.is_some() {
+                     format!("Updated {} to {}: {} files downloaded, {} removed, {} unchanged",
+                         job.index.name, job.index.version_id, job.plan.download.len(), job.plan.remove.len(), job.plan.unchanged)
+                 } else {
+                     format!("Installed {} {}", job.index.name, job.index.version_id)
+                 };
+
+                 let modpack = Some(ModpackSource { name: job.index.name.clone(), version_id: job.index.version_id.clone() });
+                 match &job.profile_name {
+                     Some(name) => {
+                         if let Some(profile) = self.profiles.get_mut(name) {
+                             profile.modpack = modpack;
+                         }
+                     }
+                     None => {
+                         let Some(version) = job.index.minecraft_version() else {
+                             sender.input(AppMsg::Error(format!("{} doesn't say which Minecraft version it needs", job.index.name)));
+                             return;
+                         };
+                         let username = self.profiles.values().next().map(|p| p.username.clone()).unwrap_or_else(|| "Player".to_string());
+                         let id = uuid::Uuid::new_v4().to_string();
+                         self.profiles.insert(id.clone(), Profile {
+                             id,
+                             username,
+                             version: version.to_string(),
+                             ram_mb: self.settings.new_profile_ram_mb(crate::utils::get_total_memory_mb()),
+                             playtime_seconds: 0,
+                             last_launch: None,
+                             loader: job.index.loader(),
+                             game_dir: Some(job.game_dir.display().to_string()),
+                             notes: None,
+                             last_crashed: false,
+                             last_exit_code: None,
+                             shared_mod_dirs: Vec::new(),
+                             last_java_path: None,
+                             last_java_version: None,
+                             account_id: None,
+                             jvm_args: Vec::new(),
+                             created_at: Some(crate::utils::unix_now()),
+                             modpack,
+                         });
+                         self.refresh_mod_profile_dropdown(sender.clone());
+                     }
+                 }
+                 self.save_profiles(sender.clone());
+                 sender.input(AppMsg::ShowToast(summary));
+            }
             AppMsg::DeleteProfile(profile_name) => {
                 self.profiles.remove(&profile_name);
+                self.forget_profile_size(&profile_name);
                 self.refresh_mod_profile_dropdown(sender.clone());
                 self.save_profiles(sender.clone());
                 sender.input(AppMsg::NavigateToSection(Section::Home));
@@ -763,32 +2293,226 @@ impl SimpleComponent for AppModel {
             }
 
             AppMsg::Error(message) => {
-                self.state = AppState::Error { message };
+                self.state = AppState::Error { message, retry: None };
             }
-            AppMsg::ThemeSelected(theme) => {
-                self.settings.theme = theme.clone();
-                if let Some(window) = &self.window {
-                    let style_manager = adw::StyleManager::default();
-                    
-                    // Reset CSS provider if stored? Since we don't store it, we just add.
-                    // A better approach for "Total Black" is just forcing dark and adding a provider.
-                    // For now, let's just try setting the scheme.
-                    
-                    // Reset classes
-                    window.remove_css_class("transparent-window");
-
-                    match theme {
-                        Theme::Dark => style_manager.set_color_scheme(adw::ColorScheme::ForceDark),
-                        Theme::Light => style_manager.set_color_scheme(adw::ColorScheme::ForceLight),
-                        Theme::System => style_manager.set_color_scheme(adw::ColorScheme::Default),
-                        Theme::Transparent => {
-                            style_manager.set_color_scheme(adw::ColorScheme::ForceDark);
-                            window.add_css_class("transparent-window");
-                        }
+            AppMsg::ErrorWithRetry(message, retry) => {
+                self.state = AppState::Error { message, retry: Some(retry) };
+            }
+            AppMsg::SignInFailed(profile_name, username, error) => {
+                self.state = AppState::Error {
+                    message: format!("Could not sign in: {}", error),
+                    retry: Some(RetryAction::LaunchProfile(profile_name.clone())),
+                };
+                // A broken sign-in (no network, expired login) shouldn't keep the user from playing
+                let Some(window) = &self.window else { return };
+                let dialog = adw::MessageDialog::builder()
+                    .heading(format!("Could not sign in as {}", username))
+                    .body(format!("{}\n\nPlay offline as {} instead? Servers that check accounts won't let you join.", error, username))
+                    .transient_for(window)
+                    .modal(true)
+                    .build();
+                dialog.add_response("cancel", "Cancel");
+                dialog.add_response("offline", "Play Offline");
+                dialog.set_response_appearance("offline", adw::ResponseAppearance::Suggested);
+                dialog.set_default_response(Some("offline"));
+                let sender_clone = sender.clone();
+                dialog.connect_response(None, move |d, response| {
+                    if response == "offline" {
+                        sender_clone.input(AppMsg::LaunchProfileOffline(profile_name.clone()));
+                    }
+                    d.close();
+                });
+                dialog.present();
+            }
+            AppMsg::LaunchProfileOffline(profile_name) => {
+                self.offline_launch = Some(profile_name.clone());
+                sender.input(AppMsg::LaunchProfile(profile_name));
+            }
+            AppMsg::RetryFailedAction => {
+                let AppState::Error { retry: Some(retry), .. } = &self.state else { return };
+                match retry.clone() {
+                    RetryAction::LaunchProfile(profile_name) => {
+                        sender.input(AppMsg::LaunchProfile(profile_name));
+                    }
+                    RetryAction::InstallJavaAndLaunch(profile_name) => {
+                        self.pending_launch_profile = Some(profile_name);
+                        sender.input(AppMsg::InstallJavaAndLaunch);
                     }
                 }
+            }
+            AppMsg::ClearCaches => {
+                 let sender_clone = sender.clone();
+                 self.rt.spawn_blocking(move || {
+                     let dir = crate::config::cache_dir();
+                     let size = crate::utils::dir_size(&dir);
+                     let result = match std::fs::remove_dir_all(&dir) {
+                         Ok(()) => Ok(size),
+                         Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+                         Err(e) => Err(e.to_string()),
+                     };
+                     sender_clone.input(AppMsg::CachesCleared(result));
+                 });
+            }
+            AppMsg::CachesCleared(result) => {
+                 match result {
+                     Ok(freed) => {
+                         tracing::info!("Cleared caches, freed {}", crate::utils::format_size(freed));
+                         sender.input(AppMsg::ShowToast(format!("Caches cleared, freed {}", crate::utils::format_size(freed))));
+                     }
+                     Err(e) => sender.input(AppMsg::ShowToast(format!("Could not clear caches: {}", e))),
+                 }
+            }
+            AppMsg::FindUnusedLibraries => {
+                 // An install in progress may be writing libraries its json doesn't list yet
+                 if matches!(self.state, AppState::Launching { .. } | AppState::Downloading { .. }) {
+                     sender.input(AppMsg::ShowToast("Wait for the current download to finish".to_string()));
+                     return;
+                 }
+                 let Some(launcher) = &self.launcher else { return };
+                 let library_manager = launcher.library_manager.clone();
+                 let sender_clone = sender.clone();
+                 self.rt.spawn(async move {
+                     let result = library_manager.find_orphaned_libraries().await.map_err(|e| e.to_string());
+                     sender_clone.input(AppMsg::UnusedLibrariesFound(result));
+                 });
+            }
+            AppMsg::UnusedLibrariesFound(result) => {
+                 let jars = match result {
+                     Ok(jars) => jars,
+                     Err(e) => {
+                         sender.input(AppMsg::ShowToast(format!("Could not check libraries: {}", e)));
+                         return;
+                     }
+                 };
+                 if jars.is_empty() {
+                     sender.input(AppMsg::ShowToast("No unused libraries found".to_string()));
+                     return;
+                 }
+                 let Some(window) = &self.window else { return };
+                 let total: u64 = jars.iter().map(|(_, size)| size).sum();
+                 let dialog = adw::MessageDialog::builder()
+                     .heading("Remove Unused Libraries?")
+                     .body(format!("{} libraries ({}) aren't used by any installed version. They are downloaded again if a version needs them later.", jars.len(), crate::utils::format_size(total)))
+                     .transient_for(window)
+                     .modal(true)
+                     .build();
+                 dialog.add_response("cancel", "Cancel");
+                 dialog.add_response("remove", "Remove");
+                 dialog.set_response_appearance("remove", adw::ResponseAppearance::Destructive);
+                 let sender_clone = sender.clone();
+                 dialog.connect_response(None, move |d, response| {
+                     if response == "remove" {
+                         sender_clone.input(AppMsg::RemoveUnusedLibraries(jars.clone()));
+                     }
+                     d.close();
+                 });
+                 dialog.present();
+            }
+            AppMsg::RemoveUnusedLibraries(jars) => {
+                 // A download may have started while the dialog was open
+                 if matches!(self.state, AppState::Launching { .. } | AppState::Downloading { .. }) {
+                     sender.input(AppMsg::ShowToast("Wait for the current download to finish".to_string()));
+                     return;
+                 }
+                 let Some(launcher) = &self.launcher else { return };
+                 let library_manager = launcher.library_manager.clone();
+                 let sender_clone = sender.clone();
+                 self.rt.spawn(async move {
+                     let freed = library_manager.remove_libraries(jars).await;
+                     sender_clone.input(AppMsg::UnusedLibrariesRemoved(freed));
+                 });
+            }
+            AppMsg::UnusedLibrariesRemoved(freed) => {
+                 tracing::info!("Removed unused libraries, freed {}", crate::utils::format_size(freed));
+                 sender.input(AppMsg::ShowToast(format!("Unused libraries removed, freed {}", crate::utils::format_size(freed))));
+            }
+            AppMsg::ExportDiagnostics => {
+                let error = match &self.state {
+                    AppState::Error { message, .. } => message.clone(),
+                    _ => String::new(),
+                };
+
+                let skip = self.raw_logs.len().saturating_sub(crate::diagnostics::DIAGNOSTIC_LOG_LINES);
+                let log_lines: Vec<String> = self.raw_logs.iter().skip(skip).cloned().collect();
+
+                let profile = self.pending_launch_profile.as_ref().and_then(|name| self.profiles.get(name)).cloned();
+                let usernames: Vec<String> = self.profiles.values().map(|p| p.username.clone())
+                    .chain(self.accounts.iter().map(|a| a.username.clone()))
+                    .collect();
+
+                if let Some(launcher) = &self.launcher {
+                    let launcher_clone = launcher.clone();
+                    let out_dir = launcher.config.minecraft_dir.join("diagnostics");
+                    let sender_clone = sender.clone();
+                    self.rt.spawn(async move {
+                        let result = tokio::task::spawn_blocking(move || {
+                            let report = crate::diagnostics::DiagnosticReport {
+                                error,
+                                log_lines,
+                                java_installations: launcher_clone.java_manager.get_installed_java_versions(),
+                                profile,
+                                usernames,
+                            };
+                            report.write_to(&out_dir)
+                        }).await;
+
+                        match result {
+                            Ok(Ok(path)) => {
+                                sender_clone.input(AppMsg::ShowToast(format!("Diagnostics saved to {}", path.display())));
+                                if let Some(parent) = path.parent() {
+                                    let _ = open::that(parent);
+                                }
+                            }
+                            Ok(Err(e)) => sender_clone.input(AppMsg::ShowToast(format!("Failed to export diagnostics: {}", e))),
+                            Err(e) => sender_clone.input(AppMsg::ShowToast(format!("Failed to export diagnostics: {}", e))),
+                        }
+                    });
+                }
+            }
+            AppMsg::ThemeSelected(theme) => {
+                self.settings.theme = theme;
+                self.apply_appearance();
+                self.save_settings();
+            }
+            AppMsg::ToggleTransparent(transparent) => {
+                self.settings.transparent = transparent;
+                self.apply_appearance();
                 self.save_settings();
             }
+            AppMsg::LogLevelSelected(level) => {
+                crate::logging::set_level(level);
+                if self.settings.log_level != level {
+                    self.settings.log_level = level;
+                    self.save_settings();
+                }
+            }
+            AppMsg::JavaVendorSelected(vendor) => {
+                self.settings.java_vendor = vendor;
+                self.save_settings();
+            }
+            AppMsg::OpenSessionLog(profile_name) => {
+                // The game keeps the log of its last run as logs/latest.log in the instance
+                let Some(log) = self.get_game_dir(&profile_name).map(|dir| dir.join("logs").join("latest.log")) else { return };
+                if log.exists() {
+                    self.rt.spawn(async move { let _ = open::that(log); });
+                } else {
+                    sender.input(AppMsg::ShowToast("No log was saved for the last session".to_string()));
+                }
+            }
+            AppMsg::OpenLogsFolder => {
+                // The game writes its logs into the instance of the profile played most recently
+                let last_played = self.last_played_profile().map(|p| p.id.clone());
+                let Some(profile_id) = last_played else {
+                    sender.input(AppMsg::ShowToast("No profile has been launched yet".to_string()));
+                    return;
+                };
+                let Some(logs_dir) = self.get_game_dir(&profile_id).map(|dir| dir.join("logs")) else { return };
+                if logs_dir.is_dir() {
+                    self.rt.spawn(async move { let _ = open::that(logs_dir); });
+                } else {
+                    sender.input(AppMsg::ShowToast("The last launched profile has no logs yet".to_string()));
+                }
+            }
             AppMsg::OpenMinecraftFolder => {
                 if let Some(launcher) = &self.launcher {
                      let dir = launcher.config.minecraft_dir.clone();
@@ -816,29 +2540,250 @@ impl SimpleComponent for AppModel {
                     dialog.present();
                  }
             }
-            AppMsg::SessionEnded(profile_name, duration) => {
+            AppMsg::RequestMigrateProfile(profile_name) => {
+                 let Some(window) = &self.window else { return };
+                 let Some(profile) = self.profiles.get(&profile_name) else { return };
+                 if self.sorted_versions.is_empty() {
+                     sender.input(AppMsg::ShowToast("The version list hasn't loaded yet".to_string()));
+                     return;
+                 }
+                 let versions: Vec<&str> = self.sorted_versions.iter().map(String::as_str).collect();
+                 let version_dropdown = gtk::DropDown::from_strings(&versions);
+                 if let Some(pos) = versions.iter().position(|v| *v == profile.version) {
+                     version_dropdown.set_selected(pos as u32);
+                 }
+                 let body = if profile.loader != Loader::Vanilla {
+                     "Copies the profile's files into a new profile and downloads a build of each mod for the chosen version. Mods without one are left out."
+                 } else {
+                     "Copies the profile's files into a new profile on the chosen version."
+                 };
+                 let dialog = adw::MessageDialog::builder()
+                     .heading(format!("Duplicate {}", profile.username))
+                     .body(body)
+                     .extra_child(&version_dropdown)
+                     .transient_for(window)
+                     .modal(true)
+                     .build();
+                 dialog.add_response("cancel", "Cancel");
+                 dialog.add_response("duplicate", "Duplicate");
+                 dialog.set_response_appearance("duplicate", adw::ResponseAppearance::Suggested);
+                 let sender_clone = sender.clone();
+                 let sorted_versions = self.sorted_versions.clone();
+                 dialog.connect_response(None, move |d, response| {
+                     let picked = sorted_versions.get(version_dropdown.selected() as usize).filter(|_| response == "duplicate");
+                     if let Some(version) = picked {
+                         sender_clone.input(AppMsg::MigrateProfile(profile_name.clone(), version.clone()));
+                     }
+                     d.close();
+                 });
+                 dialog.present();
+            }
+            AppMsg::MigrateProfile(profile_name, version) => {
+                 let Some(source) = self.profiles.get(&profile_name) else { return };
+                 let Some(source_dir) = self.get_game_dir(&profile_name) else { return };
+                 let Some(launcher) = &self.launcher else { return };
+                 let prefer_stable = self.settings.prefer_stable_mods;
+
+                 let mut profile = source.clone();
+                 profile.id = uuid::Uuid::new_v4().to_string();
+                 profile.version = version.clone();
+                 profile.game_dir = None;
+                 profile.playtime_seconds = 0;
+                 profile.last_launch = None;
+                 profile.last_crashed = false;
+                 profile.last_exit_code = None;
+                 profile.last_java_path = None;
+                 profile.last_java_version = None;
+                 let target_dir = launcher.config.instances_dir.join(&profile.id);
+
+                 self.state = AppState::Downloading {
+                     title: "Duplicating Profile".to_string(),
+                     version: version.clone(),
+                     progress: 0.0,
+                     status: "Copying files...".to_string(),
+                 };
+                 self.begin_operation(&sender);
+
+                 let modrinth = self.modrinth.clone();
+                 let sender_clone = sender.clone();
+                 let task = self.rt.spawn(async move {
+                     // Dropped with the task when the user cancels, too
+                     let mut cleanup = RemoveUnfinishedDir { dir: target_dir.clone(), finished: false };
+                     let result = async {
+                         let (from, to) = (source_dir.clone(), target_dir.clone());
+                         // Mods are fetched again below, the old jars only run on the old version.
+                         // A profile that was never launched has no folder yet, it starts out empty
+                         tokio::task::spawn_blocking(move || if from.exists() {
+                             crate::utils::copy_dir(&from, &to, &["mods", "logs", "crash-reports"])
+                         } else {
+                             std::fs::create_dir_all(&to)
+                         })
+                             .await.map_err(|e| e.to_string())?
+                             .map_err(|e| format!("Could not copy {}: {}", source_dir.display(), e))?;
+
+                         if profile.loader == Loader::Vanilla {
+                             return Ok((profile, Vec::new()));
+                         }
+
+                         let source_mods = source_dir.join("mods");
+                         let old_index = ModIndex::load(&source_mods);
+                         // Dependencies are resolved again for the new version
+                         let picked: Vec<String> = old_index.mods.iter()
+                             .filter(|(_, m)| !m.auto_installed)
+                             .map(|(pid, _)| pid.clone())
+                             .collect();
+                         let names: HashMap<String, String> = old_index.mods.iter()
+                             .map(|(pid, m)| (pid.clone(), m.filename.clone()))
+                             .collect();
+
+                         let target_mods = target_dir.join("mods");
+                         let sender_progress = sender_clone.clone();
+                         let outcome = modrinth.install_with_dependencies(
+                             &picked,
+                             &target_mods,
+                             profile.loader_id(),
+                             Some(&version),
+                             &HashSet::new(),
+                             prefer_stable,
+                             true,
+                             |project_id, fraction| {
+                                 let name = names.get(project_id).map(String::as_str).unwrap_or(project_id);
+                                 sender_progress.input(AppMsg::DownloadProgress(fraction, format!("Downloading {}", name)));
+                             },
+                         ).await;
+
+                         let mut new_index = ModIndex::default();
+                         new_index.mods.extend(outcome.installed);
+                         new_index.save(&target_mods).map_err(|e| e.to_string())?;
+
+                         let mut left_behind: Vec<String> = outcome.unavailable.iter()
+                             .chain(outcome.failed.iter().map(|(pid, _)| pid))
+                             .map(|pid| names.get(pid).cloned().unwrap_or_else(|| pid.clone()))
+                             .collect();
+                         // Jars RCraft didn't install can't be looked up on Modrinth
+                         if let Ok(entries) = std::fs::read_dir(&source_mods) {
+                             left_behind.extend(entries.flatten()
+                                 .map(|e| e.file_name().to_string_lossy().to_string())
+                                 .filter(|f| f.ends_with(".jar") && old_index.project_for_file(f).is_none()));
+                         }
+                         left_behind.sort();
+                         Ok((profile, left_behind))
+                     }.await;
+
+                     cleanup.finished = result.is_ok();
+                     drop(cleanup);
+                     sender_clone.input(AppMsg::ProfileMigrated(result));
+                 });
+                 self.current_operation = Some(task.abort_handle());
+            }
+            AppMsg::ProfileMigrated(result) => {
+                 self.state = AppState::Ready { current_section: Section::Home };
+                 match result {
+                     Ok((profile, left_behind)) => {
+                         let name = format!("{} ({})", profile.username, profile.version);
+                         self.profiles.insert(profile.id.clone(), profile);
+                         self.save_profiles(sender.clone());
+                         self.refresh_mod_profile_dropdown(sender.clone());
+                         self.request_profile_sizes();
+                         if left_behind.is_empty() {
+                             sender.input(AppMsg::ShowToast(format!("Created {}", name)));
+                         } else if let Some(window) = &self.window {
+                             let dialog = adw::MessageDialog::builder()
+                                 .heading(format!("Created {}", name))
+                                 .body(format!("These mods have no build for the new version and were left out:\n\n{}", left_behind.join("\n")))
+                                 .transient_for(window)
+                                 .modal(true)
+                                 .build();
+                             dialog.add_response("ok", "OK");
+                             dialog.present();
+                         }
+                     }
+                     Err(e) => {
+                         tracing::error!("Duplicating profile failed: {}", e);
+                         sender.input(AppMsg::ShowToast(format!("Could not duplicate profile: {}", e)));
+                     }
+                 }
+            }
+            AppMsg::SessionEnded(profile_name, duration, status) => {
+                self.running_java = None;
                 if let Some(profile) = self.profiles.get_mut(&profile_name) {
                     profile.playtime_seconds += duration;
                     profile.last_launch = Some(std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs());
+                    // Couldn't wait on the game at all, nothing known about how it ended
+                    if let Some(status) = status {
+                        profile.last_crashed = !status.success();
+                        profile.last_exit_code = status.code();
+                        if profile.last_crashed {
+                            tracing::warn!("{} ({}) exited with {}", profile.username, profile.version, status);
+                        }
+                    }
                     self.save_profiles(sender.clone());
                 }
+                // Worlds and logs grew while playing
+                self.forget_profile_size(&profile_name);
+                self.request_profile_sizes();
+            }
+            AppMsg::ProfileSizeComputed(profile_name, bytes) => {
+                // Dropped if the profile was deleted or changed while measuring
+                let still_wanted = self.profile_sizes_requested.contains(&profile_name);
+                if still_wanted {
+                    self.profile_sizes.insert(profile_name, bytes);
+                }
             }
              AppMsg::RefreshInstalledMods => {
                  self.refresh_installed_mods(sender.clone());
              }
+             AppMsg::SelectModProfileAt(index) => {
+                 if let Some(id) = self.mod_profile_ids.get(index as usize) {
+                     sender.input(AppMsg::SelectModProfile(id.clone()));
+                 }
+             }
              AppMsg::SelectModProfile(profile_name) => {
+                 // Fill the empty browse list once per profile, never over the user's own search
+                 if self.mod_search_query.is_empty() && self.popular_mods_profile.as_ref() != Some(&profile_name) {
+                     self.popular_mods_profile = Some(profile_name.clone());
+                     sender.input(AppMsg::LoadPopularMods);
+                 }
+                 // Updates found for another profile's jars don't apply here
+                 if self.selected_mod_profile.as_ref() != Some(&profile_name) {
+                     self.mod_updates.clear();
+                 }
                  self.selected_mod_profile = Some(profile_name);
+                 // Never-launched profiles have no instance yet, create it so the list and installs have a home
+                 if let Some(mods_dir) = self.get_install_dir(self.mod_project_type) {
+                     if let Err(e) = std::fs::create_dir_all(&mods_dir) {
+                         sender.input(AppMsg::ShowToast(format!("Failed to create mods folder: {}", e)));
+                     }
+                 }
+                 sender.input(AppMsg::RefreshInstalledMods);
+             }
+             AppMsg::SelectProjectType(project_type) => {
+                 if self.mod_project_type == project_type { return; }
+                 self.mod_project_type = project_type;
+                 self.mod_updates.clear();
+                 // Results of the previous type are of no use anymore
+                 if self.mod_search_query.is_empty() {
+                     self.popular_mods_profile = self.selected_mod_profile.clone();
+                     sender.input(AppMsg::LoadPopularMods);
+                 } else {
+                     sender.input(AppMsg::SearchMods(self.mod_search_query.clone()));
+                 }
+                 if let Some(Err(e)) = self.get_install_dir(project_type).map(std::fs::create_dir_all) {
+                     sender.input(AppMsg::ShowToast(format!("Failed to create {} folder: {}", project_type.folder(), e)));
+                 }
                  sender.input(AppMsg::RefreshInstalledMods);
              }
              AppMsg::SearchMods(query) => {
                  self.is_searching = true;
+                 self.mod_search_query = query.clone();
+                 let project_type = self.mod_project_type;
                  let modrinth = self.modrinth.clone();
                  let sender_clone = sender.clone();
                  
                   // Get profile version for filtering
                 let (version_filter, loader_filter) = if let Some(profile_name) = &self.selected_mod_profile {
                     if let Some(profile) = self.profiles.get(profile_name) {
-                        (Some(profile.version.clone()), Some("fabric".to_string()))
+                        (Some(profile.version.clone()), profile.loader_id().map(str::to_string))
                     } else { (None, None) }
                 } else { (None, None) };
                  
@@ -847,87 +2792,239 @@ impl SimpleComponent for AppModel {
                      rt.block_on(async {
                          let v_ref = version_filter.as_deref();
                          let l_ref = loader_filter.as_deref();
-                         match modrinth.search_mods(&query, 20, v_ref, l_ref).await {
+                         match modrinth.search_mods(&query, 20, project_type, v_ref, l_ref).await {
+                             Ok(results) if results.data.is_empty() && v_ref.is_some() => {
+                                 // Tell "nothing matches" apart from "nothing matches this version"
+                                 let other_versions = modrinth.search_mods(&query, 1, project_type, None, l_ref).await
+                                     .map(|r| !r.data.is_empty())
+                                     .unwrap_or(false);
+                                 if other_versions {
+                                     sender_clone.input(AppMsg::ModsSearchIncompatible(v_ref.unwrap_or_default().to_string()));
+                                 } else {
+                                     sender_clone.input(AppMsg::ModsSearched(Ok(results)));
+                                 }
+                             }
                              Ok(results) => sender_clone.input(AppMsg::ModsSearched(Ok(results))),
                              Err(e) => sender_clone.input(AppMsg::ModsSearched(Err(e.to_string()))),
                          }
                      });
                  });
              }
+             AppMsg::LoadPopularMods => {
+                 self.is_searching = true;
+                 let modrinth = self.modrinth.clone();
+                 let sender_clone = sender.clone();
+                 let (version_filter, loader_filter) = self.get_profile_filters();
+                 let project_type = self.mod_project_type;
+                 self.rt.spawn(async move {
+                     let result = modrinth.popular_mods(20, project_type, version_filter.as_deref(), loader_filter.as_deref()).await;
+                     sender_clone.input(AppMsg::ModsSearched(result.map_err(|e| e.to_string())));
+                 });
+             }
              AppMsg::ModsSearched(result) => {
                  self.is_searching = false;
-                 match result {
+                 self.mod_results_stale = result.as_ref().is_ok_and(|fetched| fetched.stale);
+                 match result.map(|fetched| fetched.data) {
                      Ok(results) => {
                          self.mod_search_results = results.clone();
-                         if let Some(list) = &self.mod_browse_list {
-                             while let Some(child) = list.first_child() { list.remove(&child); }
-                             for mod_data in results {
-                                 let row = create_mod_search_result_row(&mod_data, &sender);
-                                 list.append(&row);
-                                 if let Some(url) = &mod_data.icon_url {
-                                      sender.input(AppMsg::DownloadModIcon(mod_data.project_id.clone(), url.clone()));
-                                 }
-                             }
+                         if results.is_empty() && self.mod_search_query.is_empty() {
+                             self.show_mod_search_placeholder("No Mods Found", "Nothing is available for this profile's version yet");
+                         } else if results.is_empty() {
+                             self.show_mod_search_placeholder(
+                                 &format!("No results for '{}'", self.mod_search_query),
+                                 "Try a different search term",
+                             );
                          }
+                         self.update_mod_browse_list(&results, &sender);
+                     }
+                     Err(e) => sender.input(AppMsg::ShowToast(format!("Mod search failed: {}", e))),
+                 }
+             }
+             AppMsg::ModsSearchIncompatible(version) => {
+                 self.is_searching = false;
+                 self.mod_results_stale = false;
+                 self.mod_search_results.clear();
+                 self.show_mod_search_placeholder(
+                     &format!("No results for '{}' on {}", self.mod_search_query, version),
+                     "Matching mods exist, but none of them support this profile's version",
+                 );
+             }
+             AppMsg::InstallModBySlug(input) => {
+                 let Some((slug, kind)) = crate::modrinth_client::parse_project_ref(&input) else {
+                     sender.input(AppMsg::ShowToast(format!("'{}' is not a Modrinth project URL or slug", input.trim())));
+                     return;
+                 };
+                 // The link says where it belongs, it mustn't land in the folder of whatever tab is open
+                 match kind {
+                     Some(LinkKind::Modpack) => {
+                         sender.input(AppMsg::DownloadModpack(slug));
+                         return;
                      }
-                     Err(_) => {}
+                     Some(LinkKind::Plugin) => {
+                         sender.input(AppMsg::ShowToast("Plugins run on servers, they can't be installed in a profile".to_string()));
+                         return;
+                     }
+                     Some(LinkKind::Project(project_type)) if project_type != self.mod_project_type => {
+                         sender.input(AppMsg::ShowToast(format!("That link is in {}, switch to it to install", project_type.name())));
+                         return;
+                     }
+                     _ => {}
+                 }
+                 if self.selected_mod_profile.is_none() {
+                     sender.input(AppMsg::ShowToast("Select a profile before installing mods".to_string()));
+                     return;
                  }
+                 let modrinth = self.modrinth.clone();
+                 let sender_clone = sender.clone();
+                 self.rt.spawn(async move {
+                     // Slugs resolve to the project id the rest of the install path keys on
+                     match modrinth.get_project(&slug).await {
+                         Ok(project) if is_server_only(project.client_side.as_deref()) => {
+                             sender_clone.input(AppMsg::ConfirmServerOnlyInstall(project.id, project.title));
+                         }
+                         Ok(project) => sender_clone.input(AppMsg::InstallMod(project.id)),
+                         Err(e) => sender_clone.input(AppMsg::ShowToast(format!("Could not install {}: {}", slug, e))),
+                     }
+                 });
              }
              AppMsg::InstallMod(project_id) => {
+                 if self.installing_mods.contains(&project_id) { return; }
                  let modrinth = self.modrinth.clone();
                  let sender_clone = sender.clone();
                  
-                 let mods_dir = self.get_mods_dir();
+                 let mods_dir = self.get_install_dir(self.mod_project_type);
                  if mods_dir.is_none() { 
-                      sender.input(AppMsg::Error("No profile selected".to_string()));
+                      sender.input(AppMsg::ShowToast(self.no_install_dir_reason()));
                       return; 
                  }
                  let mods_dir = mods_dir.unwrap();
                  if !mods_dir.exists() { let _ = std::fs::create_dir_all(&mods_dir); }
 
-                 // Set button loading state (simplified)
-                 
+                 self.installing_mods.insert(project_id.clone());
+                 self.update_mod_button_state(&project_id);
+
+                 let already_installed: HashSet<String> = self.installed_mods.keys().cloned().collect();
                  let (version_filter, loader_filter) = self.get_profile_filters();
+                 let prefer_stable = self.settings.prefer_stable_mods;
+                 // Shaders and resource packs list their loader mod (Iris...) as required, that
+                 // jar mustn't end up in shaderpacks/
+                 let resolve_dependencies = self.mod_project_type == ProjectType::Mod;
 
-                 std::thread::spawn(move || {
-                     let rt = tokio::runtime::Runtime::new().unwrap();
-                     rt.block_on(async {
-                          let v_ref = version_filter.as_deref();
-                          let l_ref = loader_filter.as_deref();
-                          match modrinth.get_versions(&project_id, l_ref, v_ref).await {
-                              Ok(versions) => {
-                                  if let Some(version) = versions.first() {
-                                      if let Some(file) = version.files.iter().find(|f| f.primary).or(version.files.first()) {
-                                           let path = mods_dir.join(&file.filename);
-                                           match modrinth.download_mod(&file.url, &path).await {
-                                               Ok(_) => {
-                                                   sender_clone.input(AppMsg::ShowToast("Mod installed!".to_string()));
-                                                   sender_clone.input(AppMsg::RefreshInstalledMods);
-                                                   sender_clone.input(AppMsg::RegisterInstalledMod(project_id.clone(), file.filename.clone()));
-                                                   sender_clone.input(AppMsg::ModInstallFinished(project_id.clone(), ()));
-                                               },
-                                               Err(e) => {
-                                                   sender_clone.input(AppMsg::Error(format!("Download failed: {}", e)));
-                                                   sender_clone.input(AppMsg::ModInstallFinished(project_id.clone(), ()));
-                                               }
-                                           }
-                                      } else {
-                                           sender_clone.input(AppMsg::Error("No files found".to_string()));
-                                           sender_clone.input(AppMsg::ModInstallFinished(project_id.clone(), ()));
-                                      }
-                                  } else {
-                                       sender_clone.input(AppMsg::Error("No versions found".to_string()));
-                                       sender_clone.input(AppMsg::ModInstallFinished(project_id.clone(), ()));
-                                  }
-                              }
-                              Err(e) => {
-                                  sender_clone.input(AppMsg::Error(format!("Failed to get mod versions: {}", e)));
-                                  sender_clone.input(AppMsg::ModInstallFinished(project_id.clone(), ()));
-                              }
-                          }
-                     });
+                 self.rt.spawn(async move {
+                     // Required dependencies come along, the game crashes on start without them
+                     let sender_progress = sender_clone.clone();
+                     let outcome = modrinth.install_with_dependencies(
+                         std::slice::from_ref(&project_id),
+                         &mods_dir,
+                         loader_filter.as_deref(),
+                         version_filter.as_deref(),
+                         &already_installed,
+                         prefer_stable,
+                         resolve_dependencies,
+                         |id, fraction| sender_progress.input(AppMsg::ModDownloadProgress(id.to_string(), fraction)),
+                     ).await;
+                     sender_clone.input(AppMsg::ModInstalled(project_id, mods_dir, outcome));
                  });
              }
+             AppMsg::ModInstalled(project_id, mods_dir, outcome) => {
+                 let own_failure = outcome.failed.iter().find(|(id, _)| *id == project_id).map(|(_, e)| e.clone());
+                 if outcome.installed.iter().any(|(id, _)| *id == project_id) {
+                     let extras: Vec<&str> = outcome.installed.iter()
+                         .filter(|(_, m)| m.auto_installed)
+                         .map(|(_, m)| m.filename.trim_end_matches(".jar"))
+                         .collect();
+                     let mut message = if extras.is_empty() {
+                         "Mod installed!".to_string()
+                     } else {
+                         format!("Mod installed, along with {}", extras.join(", "))
+                     };
+                     if !outcome.unavailable.is_empty() {
+                         message.push_str(&format!(". {} required dependencies have no version for this profile", outcome.unavailable.len()));
+                     }
+                     if let Some((_, e)) = outcome.failed.first() {
+                         message.push_str(&format!(". {} required dependencies failed to download: {}", outcome.failed.len(), e));
+                     }
+                     sender.input(AppMsg::ShowToast(message));
+                 } else if let Some(e) = own_failure {
+                     sender.input(AppMsg::ShowToast(format!("Mod download failed: {}", e)));
+                 } else {
+                     sender.input(AppMsg::ShowToast("No compatible version found for this profile".to_string()));
+                 }
+                 // Whatever made it to disk is indexed, so uninstalling and orphan cleanup see it
+                 for (id, installed) in outcome.installed {
+                     sender.input(AppMsg::RegisterInstalledMod(mods_dir.clone(), id, installed));
+                 }
+                 sender.input(AppMsg::ModInstallFinished(project_id, ()));
+                 sender.input(AppMsg::RefreshInstalledMods);
+             }
+             AppMsg::InstallModPreset(preset_id) => {
+                 let Some(preset) = crate::mod_presets::find_preset(&preset_id) else { return };
+                 let Some(mods_dir) = self.get_install_dir(ProjectType::Mod) else {
+                      sender.input(AppMsg::ShowToast("Select a profile before installing mods".to_string()));
+                      return;
+                 };
+
+                 let project_ids: Vec<String> = preset.project_ids.iter()
+                     .map(|id| id.to_string())
+                     .filter(|id| !self.installed_mods.contains_key(id) && !self.installing_mods.contains(id))
+                     .collect();
+                 if project_ids.is_empty() {
+                     sender.input(AppMsg::ShowToast(format!("{} mods are already installed", preset.name)));
+                     return;
+                 }
+
+                 for id in &project_ids {
+                     self.installing_mods.insert(id.clone());
+                     self.update_mod_button_state(id);
+                 }
+
+                 let already_installed: HashSet<String> = self.installed_mods.keys().cloned().collect();
+                 let (version_filter, loader_filter) = self.get_profile_filters();
+                 let prefer_stable = self.settings.prefer_stable_mods;
+                 let modrinth = self.modrinth.clone();
+                 let sender_clone = sender.clone();
+
+                 self.rt.spawn(async move {
+                     let sender_progress = sender_clone.clone();
+                     let outcome = modrinth.install_with_dependencies(
+                         &project_ids,
+                         &mods_dir,
+                         loader_filter.as_deref(),
+                         version_filter.as_deref(),
+                         &already_installed,
+                         prefer_stable,
+                         true,
+                         |project_id, fraction| sender_progress.input(AppMsg::ModDownloadProgress(project_id.to_string(), fraction)),
+                     ).await;
+                     sender_clone.input(AppMsg::ModPresetInstalled(preset_id, mods_dir, outcome));
+                 });
+             }
+             AppMsg::ModPresetInstalled(preset_id, mods_dir, outcome) => {
+                 let Some(preset) = crate::mod_presets::find_preset(&preset_id) else { return };
+                 for id in preset.project_ids {
+                     self.mod_download_progress.remove(*id);
+                     if self.installing_mods.remove(*id) {
+                         self.update_mod_button_state(id);
+                     }
+                 }
+
+                 let extra = outcome.installed.iter().filter(|(_, m)| m.auto_installed).count();
+                 let mut message = format!("{} pack: installed {} mods", preset.name, outcome.installed.len() - extra);
+                 if extra > 0 {
+                     message.push_str(&format!(" and {} dependencies", extra));
+                 }
+                 if !outcome.unavailable.is_empty() {
+                     message.push_str(&format!(", {} not available for this version", outcome.unavailable.len()));
+                 }
+                 if let Some((_, e)) = outcome.failed.first() {
+                     message.push_str(&format!(", {} failed: {}", outcome.failed.len(), e));
+                 }
+                 for (project_id, installed) in outcome.installed {
+                     sender.input(AppMsg::RegisterInstalledMod(mods_dir.clone(), project_id, installed));
+                 }
+                 sender.input(AppMsg::ShowToast(message));
+                 sender.input(AppMsg::RefreshInstalledMods);
+             }
              AppMsg::DownloadModIcon(project_id, url) => {
                  self.icon_download_queue.push_back((project_id, url));
                  if !self.is_downloading_icon { sender.input(AppMsg::ProcessIconQueue); }
@@ -942,7 +3039,7 @@ impl SimpleComponent for AppModel {
                      std::thread::spawn(move || {
                         let rt = tokio::runtime::Runtime::new().unwrap();
                         rt.block_on(async {
-                            let cache_dir = std::env::temp_dir().join("rcraft").join("cache").join("icons");
+                            let cache_dir = crate::config::cache_dir().join("icons");
                             let _ = std::fs::create_dir_all(&cache_dir);
                             let png_path = cache_dir.join(format!("{}.png", project_id));
                             
@@ -951,7 +3048,7 @@ impl SimpleComponent for AppModel {
                             } else {
                                 if let Ok(bytes) = modrinth.download_icon_bytes(&url).await {
                                     if let Ok(img) = image::load_from_memory(&bytes) {
-                                        if img.save_with_format(&png_path, image::ImageFormat::Png).is_ok() {
+                                        if crate::utils::save_png_atomic(&img, &png_path).is_ok() {
                                             sender_clone.input(AppMsg::ModIconDownloaded(project_id, png_path.to_string_lossy().to_string()));
                                         } else {
                                             sender_clone.input(AppMsg::ModIconDownloaded(project_id, "".to_string()));
@@ -961,7 +3058,7 @@ impl SimpleComponent for AppModel {
                                          let s = String::from_utf8_lossy(&bytes);
                                          if s.contains("<svg") {
                                              let svg_path = cache_dir.join(format!("{}.svg", project_id));
-                                             if std::fs::write(&svg_path, &bytes).is_ok() {
+                                             if crate::utils::write_atomic(&svg_path, &bytes).is_ok() {
                                                  sender_clone.input(AppMsg::ModIconDownloaded(project_id, svg_path.to_string_lossy().to_string()));
                                              } else {
                                                   sender_clone.input(AppMsg::ModIconDownloaded(project_id, "".to_string()));
@@ -1009,7 +3106,17 @@ impl SimpleComponent for AppModel {
                       }
                  }
              }
+             AppMsg::ModDownloadProgress(project_id, fraction) => {
+                 // Dependencies pulled in by a preset have no row of their own to show it on
+                 let has_row = self.installing_mods.contains(&project_id);
+                 if has_row {
+                     self.mod_download_progress.insert(project_id.clone(), fraction);
+                     self.update_mod_button_state(&project_id);
+                 }
+             }
              AppMsg::ModInstallFinished(project_id, _) => {
+                 self.mod_download_progress.remove(&project_id);
+                 self.installing_mods.remove(&project_id);
                  self.update_mod_button_state(&project_id);
              }
              AppMsg::ModUninstallFinished(project_id) => {
@@ -1020,30 +3127,319 @@ impl SimpleComponent for AppModel {
                   if let Some(filename) = self.installed_mods.get(&project_id) {
                       sender.input(AppMsg::UninstallMod(filename.clone()));
                   } else {
-                      sender.input(AppMsg::InstallMod(project_id));
+                      let server_only = self.mod_search_results.iter()
+                          .find(|m| m.project_id == project_id)
+                          .filter(|m| is_server_only(m.client_side.as_deref()));
+                      match server_only {
+                          Some(m) => sender.input(AppMsg::ConfirmServerOnlyInstall(project_id, m.title.clone())),
+                          None => sender.input(AppMsg::InstallMod(project_id)),
+                      }
                   }
              }
+             AppMsg::ConfirmServerOnlyInstall(project_id, title) => {
+                 let Some(window) = &self.window else { return };
+                 let dialog = adw::MessageDialog::builder()
+                     .heading(format!("Install {}?", title))
+                     .body(format!("{} only runs on servers. Installed in a client profile it won't do anything.", title))
+                     .transient_for(window)
+                     .modal(true)
+                     .build();
+                 dialog.add_response("cancel", "Cancel");
+                 dialog.add_response("install", "Install Anyway");
+                 dialog.set_default_response(Some("cancel"));
+                 let sender_clone = sender.clone();
+                 dialog.connect_response(None, move |d, response| {
+                     if response == "install" {
+                         sender_clone.input(AppMsg::InstallMod(project_id.clone()));
+                     }
+                     d.close();
+                 });
+                 dialog.present();
+             }
              AppMsg::ShowToast(msg) => {
                  if let Some(o) = &self.toast_overlay { o.add_toast(adw::Toast::new(&msg)); }
              }
-             AppMsg::RegisterInstalledMod(pid, file) => {
-                 self.installed_mods.insert(pid, file);
+             AppMsg::RegisterInstalledMod(dir, pid, entry) => {
+                 // The user may have switched profile or type since the install started
+                 if self.get_install_dir(self.mod_project_type).as_ref() == Some(&dir) {
+                     self.installed_mods.insert(pid.clone(), entry.filename.clone());
+                 }
+                 let mut index = ModIndex::load(&dir);
+                 index.mods.insert(pid, entry);
+                 if let Err(e) = index.save(&dir) {
+                     sender.input(AppMsg::ShowToast(format!("Failed to save mod index: {}", e)));
+                 }
+             }
+             AppMsg::ConfirmRemoveOrphanedMods(dir, pids) => {
+                 if let Some(window) = &self.window {
+                     let index = ModIndex::load(&dir);
+                     let names: Vec<String> = pids.iter()
+                         .map(|pid| index.mods.get(pid).map(|m| m.filename.clone()).unwrap_or_else(|| pid.clone()))
+                         .collect();
+
+                     let dialog = adw::MessageDialog::builder()
+                         .heading("Remove Unused Dependencies?")
+                         .body(format!("These mods were installed automatically and are no longer needed:\n\n{}", names.join("\n")))
+                         .transient_for(window)
+                         .modal(true)
+                         .build();
+                     dialog.add_response("keep", "Keep");
+                     dialog.add_response("remove", "Remove");
+                     dialog.set_response_appearance("remove", adw::ResponseAppearance::Destructive);
+                     let sender_clone = sender.clone();
+                     dialog.connect_response(None, move |d, response| {
+                         if response == "remove" { sender_clone.input(AppMsg::RemoveOrphanedMods(dir.clone(), pids.clone())); }
+                         d.close();
+                     });
+                     dialog.present();
+                 }
+             }
+             AppMsg::RemoveOrphanedMods(dir, pids) => {
+                 let mut index = ModIndex::load(&dir);
+                 for pid in pids {
+                     let Some(entry) = index.mods.remove(&pid) else { continue };
+                     let path = installed_path(&dir, &entry.filename).unwrap_or_else(|| dir.join(&entry.filename));
+                     if let Err(e) = std::fs::remove_file(path) {
+                         sender.input(AppMsg::ShowToast(format!("Failed to uninstall {}: {}", entry.filename, e)));
+                     }
+                     sender.input(AppMsg::ModUninstallFinished(pid));
+                 }
+                 let _ = index.save(&dir);
+                 sender.input(AppMsg::RefreshInstalledMods);
+             }
+             AppMsg::UninstallMod(filename) => {
+                 if let Some(dir) = self.get_install_dir(self.mod_project_type) {
+                     // Disabled mods are known by their enabled name
+                     let filename = filename.strip_suffix(DISABLED_SUFFIX).map(str::to_string).unwrap_or(filename);
+                     if let Some(path) = installed_path(&dir, &filename) {
+                         match std::fs::remove_file(&path) {
+                             Ok(_) => {
+                                 sender.input(AppMsg::RefreshInstalledMods);
+                                 let mut pid_to_remove = None;
+                                 for (pid, fname) in &self.installed_mods {
+                                     if fname == &filename { pid_to_remove = Some(pid.clone()); break; }
+                                 }
+
+                                 let mut index = ModIndex::load(&dir);
+                                 if pid_to_remove.is_none() {
+                                     pid_to_remove = index.project_for_file(&filename);
+                                 }
+                                 if let Some(pid) = &pid_to_remove {
+                                     let orphans = index.orphaned_dependencies(pid);
+                                     index.mods.remove(pid);
+                                     let _ = index.save(&dir);
+                                     if !orphans.is_empty() {
+                                         sender.input(AppMsg::ConfirmRemoveOrphanedMods(dir.clone(), orphans));
+                                     }
+                                 }
+
+                                 if let Some(pid) = pid_to_remove {
+                                     sender.input(AppMsg::ModUninstallFinished(pid));
+                                 }
+                             }
+                             Err(e) => sender.input(AppMsg::ShowToast(format!("Failed to uninstall {}: {}", filename, e))),
+                         }
+                     } else {
+                         sender.input(AppMsg::ShowToast(format!("{} is no longer installed", filename)));
+                         sender.input(AppMsg::RefreshInstalledMods);
+                     }
+                 } else {
+                     sender.input(AppMsg::ShowToast("Select a profile before uninstalling mods".to_string()));
+                 }
+             }
+             AppMsg::ToggleModEnabled(dir, filename) => {
+                 let target = match filename.strip_suffix(DISABLED_SUFFIX) {
+                     Some(enabled) => enabled.to_string(),
+                     None => format!("{}{}", filename, DISABLED_SUFFIX),
+                 };
+                 if dir.join(&target).exists() {
+                     sender.input(AppMsg::ShowToast(format!("Both {} and {} exist, remove one of them first", filename, target)));
+                 } else if let Err(e) = std::fs::rename(dir.join(&filename), dir.join(&target)) {
+                     sender.input(AppMsg::ShowToast(format!("Failed to rename {}: {}", filename, e)));
+                 }
+                 sender.input(AppMsg::RefreshInstalledMods);
+             }
+             AppMsg::CheckModUpdates => {
+                 let (Some(profile_name), Some(mods_dir)) = (self.selected_mod_profile.clone(), self.get_install_dir(self.mod_project_type)) else {
+                     sender.input(AppMsg::ShowToast("Select a profile before checking for updates".to_string()));
+                     return;
+                 };
+                 // Jars linked in from shared folders belong to those folders, not this profile
+                 let linked = crate::shared_mods::linked_names(&mods_dir);
+                 let jars: Vec<String> = std::fs::read_dir(&mods_dir).into_iter().flatten().flatten()
+                     .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+                     .filter(|name| name.ends_with(self.mod_project_type.file_extension()) && !linked.contains(name))
+                     .collect();
+                 if jars.is_empty() {
+                     sender.input(AppMsg::ShowToast(format!("No {} installed in this profile", self.mod_project_type.name().to_lowercase())));
+                     return;
+                 }
+
+                 if let Some(button) = &self.mod_check_updates_button {
+                     button.set_sensitive(false);
+                     button.set_label("Checking...");
+                 }
+                 let index = ModIndex::load(&mods_dir);
+                 let (version_filter, loader_filter) = self.get_profile_filters();
+                 let prefer_stable = self.settings.prefer_stable_mods;
+                 let modrinth = self.modrinth.clone();
+                 let sender_clone = sender.clone();
+                 self.rt.spawn(async move {
+                     let result = modrinth.check_updates(
+                         &mods_dir,
+                         &jars,
+                         &index,
+                         loader_filter.as_deref(),
+                         version_filter.as_deref(),
+                         prefer_stable,
+                     ).await.map_err(|e| e.to_string());
+                     sender_clone.input(AppMsg::ModUpdatesChecked(profile_name, mods_dir, result));
+                 });
+             }
+             AppMsg::ModUpdatesChecked(profile_name, mods_dir, result) => {
+                 if let Some(button) = &self.mod_check_updates_button {
+                     button.set_sensitive(true);
+                     button.set_label("Check Updates");
+                 }
+                 // The user moved on to another profile while this ran
+                 if self.selected_mod_profile.as_ref() != Some(&profile_name) { return; }
+                 match result {
+                     Ok(check) => {
+                         // Remember what the hashes told us, so uninstalling knows these jars too
+                         for (pid, entry) in check.identified {
+                             sender.input(AppMsg::RegisterInstalledMod(mods_dir.clone(), pid, entry));
+                         }
+                         self.mod_updates = check.updates.into_iter().collect();
+                         for (filename, e) in &check.failed {
+                             tracing::warn!("Could not check {} for updates: {}", filename, e);
+                         }
+                         let mut message = match (self.mod_updates.len(), check.failed.is_empty()) {
+                             (0, true) => "All mods are up to date".to_string(),
+                             (0, false) => "No mod updates found".to_string(),
+                             (1, _) => "1 mod update available".to_string(),
+                             (n, _) => format!("{} mod updates available", n),
+                         };
+                         if !check.failed.is_empty() {
+                             message.push_str(&format!(", {} couldn't be checked, see the launcher log", check.failed.len()));
+                         }
+                         sender.input(AppMsg::ShowToast(message));
+                     }
+                     Err(e) => sender.input(AppMsg::ShowToast(format!("Update check failed: {}", e))),
+                 }
+                 sender.input(AppMsg::RefreshInstalledMods);
+             }
+             AppMsg::UpdateMod(filename) => {
+                 if self.updating_mods.contains(&filename) { return; }
+                 let (Some(version), Some(mods_dir)) = (self.mod_updates.get(&filename).cloned(), self.get_install_dir(self.mod_project_type)) else { return };
+                 let Some(file) = version.files.iter().find(|f| f.primary).or(version.files.first()).cloned() else {
+                     sender.input(AppMsg::ShowToast(format!("{} has no file to download", version.name)));
+                     return;
+                 };
+
+                 let index = ModIndex::load(&mods_dir);
+                 let auto_installed = index.mods.get(&version.project_id).is_some_and(|m| m.auto_installed);
+                 self.updating_mods.insert(filename.clone());
+                 self.refresh_installed_mods(sender.clone());
+
+                 let modrinth = self.modrinth.clone();
+                 let sender_clone = sender.clone();
+                 self.rt.spawn(async move {
+                     // Fetched under a temporary name, a failed download leaves the old jar alone
+                     let partial = mods_dir.join(format!("{}.part", file.filename));
+                     let result = async {
+                         modrinth.download_mod(&file.url, &partial, |_| {}).await?;
+                         tokio::fs::rename(&partial, mods_dir.join(&file.filename)).await?;
+                         if file.filename != filename {
+                             tokio::fs::remove_file(mods_dir.join(&filename)).await?;
+                         }
+                         Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+                     }.await;
+                     let result = result.map(|_| (version.project_id.clone(), InstalledMod {
+                         filename: file.filename.clone(),
+                         auto_installed,
+                         dependencies: version.dependencies.iter()
+                             .filter(|d| d.dependency_type == "required")
+                             .filter_map(|d| d.project_id.clone())
+                             .collect(),
+                     })).map_err(|e| e.to_string());
+                     sender_clone.input(AppMsg::ModUpdated(filename, mods_dir, result));
+                 });
+             }
+             AppMsg::ModUpdated(filename, mods_dir, result) => {
+                 self.updating_mods.remove(&filename);
+                 match result {
+                     Ok((pid, entry)) => {
+                         self.mod_updates.remove(&filename);
+                         sender.input(AppMsg::ShowToast(format!("Updated {}", entry.filename.trim_end_matches(".jar"))));
+                         sender.input(AppMsg::RegisterInstalledMod(mods_dir, pid, entry));
+                     }
+                     Err(e) => sender.input(AppMsg::ShowToast(format!("Failed to update {}: {}", filename, e))),
+                 }
+                 sender.input(AppMsg::RefreshInstalledMods);
+             }
+             AppMsg::ShowModDetails(project_id) => {
+                 if let (Some(stack), Some(detail)) = (&self.mod_page_stack, &self.mod_detail) {
+                     detail.show_loading(&project_id);
+                     stack.set_visible_child_name("detail");
+                 }
+
+                 let modrinth = self.modrinth.clone();
+                 let sender_clone = sender.clone();
+                 let (version_filter, loader_filter) = self.get_profile_filters();
+
+                 self.rt.spawn(async move {
+                     let project = match modrinth.get_project(&project_id).await {
+                         Ok(p) => p,
+                         Err(e) => {
+                             sender_clone.input(AppMsg::ModDetailsLoaded(project_id, Err(e.to_string())));
+                             return;
+                         }
+                     };
+                     let versions = modrinth.get_versions(&project.id, loader_filter.as_deref(), version_filter.as_deref()).await.unwrap_or_default();
+                     let gallery: Vec<String> = project.gallery.iter().map(|g| g.url.clone()).collect();
+                     let pid = project.id.clone();
+                     sender_clone.input(AppMsg::ModDetailsLoaded(project_id, Ok((project, versions))));
+
+                     let cache_dir = crate::config::cache_dir().join("gallery");
+                     let _ = std::fs::create_dir_all(&cache_dir);
+                     for (i, url) in gallery.into_iter().enumerate() {
+                         let path = cache_dir.join(format!("{}_{}.png", pid, i));
+                         if !path.exists() {
+                             let Ok(bytes) = modrinth.download_icon_bytes(&url).await else { continue };
+                             let Ok(img) = image::load_from_memory(&bytes) else { continue };
+                             if crate::utils::save_png_atomic(&img, &path).is_err() { continue; }
+                         }
+                         sender_clone.input(AppMsg::ModGalleryImageLoaded(pid.clone(), i, path.to_string_lossy().to_string()));
+                     }
+                 });
              }
-             AppMsg::UninstallMod(filename) => {
-                 if let Some(dir) = self.get_mods_dir() {
-                     let path = dir.join(&filename);
-                     if path.exists() {
-                         if std::fs::remove_file(&path).is_ok() {
-                             sender.input(AppMsg::RefreshInstalledMods);
-                              let mut pid_to_remove = None;
-                              for (pid, fname) in &self.installed_mods {
-                                  if fname == &filename { pid_to_remove = Some(pid.clone()); break; }
-                              }
-                              if let Some(pid) = pid_to_remove {
-                                  sender.input(AppMsg::ModUninstallFinished(pid));
-                              }
+             AppMsg::ModDetailsLoaded(requested, result) => {
+                 // A slow response for a project the user already left must not replace the page
+                 if self.mod_detail.as_ref().is_none_or(|d| d.current_project() != requested) {
+                     return;
+                 }
+                 match result {
+                     Ok((project, versions)) => {
+                         if let Some(detail) = &self.mod_detail {
+                             detail.populate(&project, &versions, &sender);
                          }
+                         self.update_mod_button_state(&project.id);
                      }
+                     Err(e) => {
+                         sender.input(AppMsg::ShowToast(format!("Failed to load mod details: {}", e)));
+                         sender.input(AppMsg::CloseModDetails);
+                     }
+                 }
+             }
+             AppMsg::ModGalleryImageLoaded(project_id, index, path) => {
+                 // Ignore images for a project the user already navigated away from
+                 if let Some(detail) = self.mod_detail.as_ref().filter(|d| d.current_project() == project_id) {
+                     detail.set_gallery_image(index, &path);
+                 }
+             }
+             AppMsg::CloseModDetails => {
+                 if let Some(stack) = &self.mod_page_stack {
+                     stack.set_visible_child_name("browse");
                  }
              }
              AppMsg::OpenModrinthPage(project_id) => {
@@ -1069,18 +3465,91 @@ impl SimpleComponent for AppModel {
                 widgets.loading_spinner.stop();
                 widgets.set_sidebar_buttons_sensitive(true);
                 widgets.clear_sidebar_selection();
+                widgets.highlight_bottom_nav(*current_section);
 
                 match current_section {
                     Section::Home => {
                         widgets.home_button.add_css_class("suggested-action");
                         widgets.content_stack.set_visible_child_name("home");
-                        update_profile_list(&widgets.profile_list, &self.profiles, &self.sender);
+                        update_profile_list(&widgets.profile_list, &self.profiles, self.settings.profile_sort, &self.profile_sizes, &self.profiles_status, &self.sender);
                     }
                     Section::CreateInstance => {
                          widgets.create_sidebar_button.add_css_class("suggested-action");
                          widgets.content_stack.set_visible_child_name("create");
-                         widgets.fabric_switch.set_active(self.input_install_fabric);
-                         widgets.fabric_switch.set_sensitive(self.fabric_switch_enabled);
+                         // Offline first, then the signed in accounts
+                         let account_names: Vec<&str> = std::iter::once("Offline")
+                             .chain(self.accounts.iter().map(|a| a.username.as_str()))
+                             .collect();
+                         let shown_names: Vec<String> = widgets.account_combo.model()
+                             .and_downcast::<gtk::StringList>()
+                             .map(|list| (0..list.n_items()).filter_map(|i| list.string(i)).map(|s| s.to_string()).collect())
+                             .unwrap_or_default();
+                         if shown_names != account_names {
+                             widgets.account_combo.set_model(Some(&gtk::StringList::new(&account_names)));
+                         }
+                         let account_position = self.input_account.as_ref()
+                             .and_then(|id| self.accounts.iter().position(|a| &a.uuid == id))
+                             .map_or(0, |i| i as u32 + 1);
+                         if widgets.account_combo.selected() != account_position {
+                             widgets.account_combo.set_selected(account_position);
+                         }
+                         let loader_position = Loader::ALL.iter().position(|l| *l == self.input_loader).unwrap_or(0) as u32;
+                         if widgets.loader_combo.selected() != loader_position {
+                             widgets.loader_combo.set_selected(loader_position);
+                         }
+                         match &self.versions_status {
+                             LoadStatus::Pending => widgets.version_combo.set_subtitle("Loading versions..."),
+                             LoadStatus::Loaded => widgets.version_combo.set_subtitle(""),
+                             LoadStatus::Failed(e) => widgets.version_combo.set_subtitle(&format!("Could not load versions: {}", e)),
+                         }
+                         let loader_hint = if self.loader_checking {
+                             format!("Checking {} availability...", self.input_loader.name())
+                         } else if self.input_version.is_some() && !self.loader_available {
+                             format!("{} not available for this version", self.input_loader.name())
+                         } else {
+                             LOADER_HINT.to_string()
+                         };
+                         if widgets.loader_combo.subtitle().as_deref() != Some(loader_hint.as_str()) {
+                             widgets.loader_combo.set_subtitle(&loader_hint);
+                         }
+                         widgets.game_dir_row.set_subtitle(self.input_game_dir.as_deref().unwrap_or(GAME_DIR_HINT));
+                         let shared_mods = if self.input_shared_mod_dirs.is_empty() {
+                             SHARED_MODS_HINT.to_string()
+                         } else {
+                             self.input_shared_mod_dirs.join("\n")
+                         };
+                         if widgets.shared_mods_row.subtitle().as_deref() != Some(shared_mods.as_str()) {
+                             widgets.shared_mods_row.set_subtitle(&shared_mods);
+                         }
+                         let editing = self.editing_profile.is_some();
+                         widgets.create_title_label.set_label(if editing { "Edit Profile" } else { "New Profile" });
+                         widgets.save_button.set_label(if editing { "Update" } else { "Save Profile" });
+                         widgets.cancel_button.set_visible(editing);
+
+                         // Form fields follow the model so loading a profile to edit fills them in
+                         if widgets.username_entry.text() != self.input_username.as_str() {
+                             widgets.username_entry.set_text(&self.input_username);
+                         }
+                         if widgets.jvm_args_entry.text() != self.input_jvm_args.as_str() {
+                             widgets.jvm_args_entry.set_text(&self.input_jvm_args);
+                         }
+                         if widgets.ram_scale.value() as u32 != self.input_ram {
+                             widgets.ram_scale.set_value(self.input_ram as f64);
+                         }
+                         let version_index = self.input_version.as_ref()
+                             .and_then(|v| self.shown_versions.iter().position(|s| s == v));
+                         if let Some(index) = version_index.map(|i| i as u32).filter(|i| *i != widgets.version_combo.selected()) {
+                             widgets.version_combo.set_selected(index);
+                         }
+                         let favorite = self.input_version.as_ref().is_some_and(|v| self.settings.favorite_versions.contains(v));
+                         if widgets.favorite_version_button.is_active() != favorite {
+                             widgets.favorite_version_button.set_active(favorite);
+                         }
+                         widgets.favorite_version_button.set_sensitive(self.input_version.is_some());
+                         let buffer = widgets.notes_view.buffer();
+                         if buffer.text(&buffer.start_iter(), &buffer.end_iter(), false) != self.input_notes.as_str() {
+                             buffer.set_text(&self.input_notes);
+                         }
                     }
                     Section::Mods => {
                          widgets.mods_button.add_css_class("suggested-action");
@@ -1096,12 +3565,22 @@ impl SimpleComponent for AppModel {
                     }
                 }
             }
-            AppState::Downloading { progress, status, .. } => {
+            AppState::Downloading { title, progress, status, .. } => {
                 widgets.content_stack.set_visible_child_name("loading");
-                widgets.loading_page.set_title("Downloading...");
-                widgets.loading_page.set_description(Some(status));
-                widgets.loading_page.set_child(Some(&widgets.loading_progress));
-                widgets.loading_progress.set_fraction(*progress);
+                widgets.loading_page.set_title(title);
+                // The status rides on the bar next to the percentage
+                widgets.loading_page.set_description(None);
+                widgets.loading_indicator_stack.set_visible_child_name("progress");
+                let fraction = progress.clamp(0.0, 1.0);
+                widgets.loading_progress.set_fraction(fraction);
+                let text = if status.is_empty() {
+                    format!("{:.0}%", fraction * 100.0)
+                } else {
+                    format!("{:.0}% · {}", fraction * 100.0, status)
+                };
+                if widgets.loading_progress.text().as_deref() != Some(text.as_str()) {
+                    widgets.loading_progress.set_text(Some(&text));
+                }
                 widgets.loading_spinner.stop();
                 widgets.set_sidebar_buttons_sensitive(false);
             }
@@ -1109,7 +3588,7 @@ impl SimpleComponent for AppModel {
                 widgets.content_stack.set_visible_child_name("loading");
                 widgets.loading_page.set_title("Launching...");
                 widgets.loading_page.set_description(Some("If this is your first time launching, it may take longer as files are downloaded."));
-                widgets.loading_page.set_child(Some(&widgets.loading_spinner));
+                widgets.loading_indicator_stack.set_visible_child_name("spinner");
                 widgets.loading_spinner.start();
                 widgets.set_sidebar_buttons_sensitive(false);
             }
@@ -1117,48 +3596,131 @@ impl SimpleComponent for AppModel {
                 widgets.content_stack.set_visible_child_name("loading");
                 widgets.loading_page.set_title("Game Running");
                 widgets.loading_page.set_description(Some("Minecraft is running."));
-                widgets.loading_page.set_child(Some(&widgets.loading_spinner));
+                widgets.loading_indicator_stack.set_visible_child_name("spinner");
                 widgets.loading_spinner.start();
                 widgets.set_sidebar_buttons_sensitive(false);
             }
-            AppState::Error { message } => {
+            AppState::Error { message, retry } => {
                 widgets.error_label.set_text(message);
+                widgets.retry_button.set_visible(retry.is_some());
                 widgets.content_stack.set_visible_child_name("error");
             }
         }
 
+        // Elapsed time, stall hint and cancel only make sense while downloading/launching
+        let busy = matches!(self.state, AppState::Launching { .. } | AppState::Downloading { .. });
+        match self.operation_started.filter(|_| busy) {
+            Some(started) => {
+                let elapsed = started.elapsed().as_secs();
+                widgets.loading_elapsed_label.set_text(&format!("Elapsed: {}:{:02}", elapsed / 60, elapsed % 60));
+                widgets.loading_elapsed_label.set_visible(true);
+            }
+            None => widgets.loading_elapsed_label.set_visible(false),
+        }
+        let stalled = self.last_progress_at.is_some_and(|t| t.elapsed() >= STALL_HINT_AFTER);
+        widgets.loading_stall_label.set_visible(busy && stalled);
+        widgets.loading_cancel_button.set_visible(busy);
+
+        // Nothing to copy or save before the game printed anything
+        let has_logs = !self.raw_logs.is_empty();
+        if widgets.copy_logs_button.is_sensitive() != has_logs {
+            widgets.copy_logs_button.set_sensitive(has_logs);
+            widgets.save_logs_button.set_sensitive(has_logs);
+        }
+
         // Common updates
         widgets.logs_button.set_visible(!self.settings.hide_logs);
         widgets.hide_logs_switch.set_active(self.settings.hide_logs);
+        widgets.colorize_logs_switch.set_active(self.settings.colorize_logs);
+        widgets.stable_mods_switch.set_active(self.settings.prefer_stable_mods);
+        widgets.log_timestamps_switch.set_active(self.settings.log_timestamps);
+        let default_username = self.settings.default_username.as_deref().unwrap_or_default();
+        if widgets.default_username_row.text().trim() != default_username {
+            widgets.default_username_row.set_text(default_username);
+        }
+        if widgets.max_retries_row.value() as u32 != self.settings.max_retries {
+            widgets.max_retries_row.set_value(self.settings.max_retries as f64);
+        }
+        if widgets.max_backoff_row.value() as u64 != self.settings.max_backoff_secs {
+            widgets.max_backoff_row.set_value(self.settings.max_backoff_secs as f64);
+        }
+        if widgets.download_concurrency_row.value() as usize != self.settings.download_concurrency {
+            widgets.download_concurrency_row.set_value(self.settings.download_concurrency as f64);
+        }
+        if widgets.default_ram_row.value() as u32 != self.settings.default_ram_mb {
+            widgets.default_ram_row.set_value(self.settings.default_ram_mb as f64);
+        }
+        let ram_percent = self.settings.ram_percent.unwrap_or(0);
+        if widgets.ram_percent_row.value() as u32 != ram_percent {
+            widgets.ram_percent_row.set_value(ram_percent as f64);
+        }
+        // The default only matters while no share is set
+        widgets.default_ram_row.set_sensitive(self.settings.ram_percent.is_none());
+        if widgets.max_log_lines_row.value() as usize != self.settings.max_log_lines {
+            widgets.max_log_lines_row.set_value(self.settings.max_log_lines as f64);
+        }
+        widgets.verify_files_switch.set_active(self.settings.verify_files);
+        widgets.check_libraries_switch.set_active(self.settings.check_system_libraries);
+        if let Some(launcher) = &self.launcher {
+            let subtitle = format!("Existing files are not moved. Currently: {}", launcher.config.minecraft_dir.display());
+            if widgets.data_dir_row.subtitle().as_deref() != Some(subtitle.as_str()) {
+                widgets.data_dir_row.set_subtitle(&subtitle);
+            }
+            let subtitle = format!("Existing instances are not moved. Currently: {}", launcher.config.instances_dir.display());
+            if widgets.instances_dir_row.subtitle().as_deref() != Some(subtitle.as_str()) {
+                widgets.instances_dir_row.set_subtitle(&subtitle);
+            }
+        }
+        let template_subtitle = self.settings.options_template.as_deref()
+            .unwrap_or("Copied into a new instance's options.txt on its first launch. None set");
+        if widgets.options_template_row.subtitle().as_deref() != Some(template_subtitle) {
+            widgets.options_template_row.set_subtitle(template_subtitle);
+        }
         widgets.mods_button.set_visible(!self.settings.hide_mods_button);
         widgets.hide_mods_switch.set_active(self.settings.hide_mods_button);
+        widgets.minimize_on_launch_switch.set_active(self.settings.minimize_on_launch);
+        widgets.quit_on_launch_switch.set_active(self.settings.quit_on_launch);
 
          let theme_index = match self.settings.theme {
             Theme::System => 0,
             Theme::Light => 1,
             Theme::Dark => 2,
-            Theme::Transparent => 3,
         };
         if widgets.theme_combo.selected() != theme_index {
             widgets.theme_combo.set_selected(theme_index);
         }
+        widgets.transparent_switch.set_active(self.settings.transparent);
 
-        if self.sidebar_collapsed {
-             widgets.navigation_split_view.set_min_sidebar_width(60.0);
-             widgets.navigation_split_view.set_max_sidebar_width(60.0);
-             widgets.home_box.set_halign(gtk::Align::Center);
-             widgets.create_box.set_halign(gtk::Align::Center);
-             widgets.settings_box.set_halign(gtk::Align::Center);
-             widgets.mods_box.set_halign(gtk::Align::Center);
-             widgets.logs_box.set_halign(gtk::Align::Center);
-        } else {
-             widgets.navigation_split_view.set_min_sidebar_width(180.0);
-             widgets.navigation_split_view.set_max_sidebar_width(250.0);
-             widgets.home_box.set_halign(gtk::Align::Start);
-             widgets.create_box.set_halign(gtk::Align::Start);
-             widgets.settings_box.set_halign(gtk::Align::Start);
-             widgets.mods_box.set_halign(gtk::Align::Start);
-             widgets.logs_box.set_halign(gtk::Align::Start);
+        let vendor_index = match self.settings.java_vendor {
+            JavaVendor::Temurin => 0,
+            JavaVendor::GraalVM => 1,
+            JavaVendor::Zulu => 2,
+        };
+        if widgets.java_vendor_combo.selected() != vendor_index {
+            widgets.java_vendor_combo.set_selected(vendor_index);
+        }
+
+        let log_level_index = match self.settings.log_level {
+            LogLevel::Error => 0,
+            LogLevel::Warn => 1,
+            LogLevel::Info => 2,
+            LogLevel::Debug => 3,
+            LogLevel::Trace => 4,
+        };
+        if widgets.log_level_combo.selected() != log_level_index {
+            widgets.log_level_combo.set_selected(log_level_index);
+        }
+
+        let sort_index = ProfileSort::ALL.iter().position(|s| *s == self.settings.profile_sort).unwrap_or(0) as u32;
+        if widgets.profile_sort_dropdown.selected() != sort_index {
+            widgets.profile_sort_dropdown.set_selected(sort_index);
+        }
+
+        // Only touch the sidebar when the collapsed state actually changes. Nothing is applied
+        // before the settings arrive, so a saved collapsed sidebar doesn't expand and shrink on startup.
+        if self.settings_loaded && widgets.sidebar_applied != Some(self.sidebar_collapsed) {
+             let animate = widgets.sidebar_applied.is_some();
+             widgets.apply_sidebar_collapsed(self.sidebar_collapsed, animate);
         }
 
         if self.mod_profile_list_updated {
@@ -1173,41 +3735,214 @@ impl SimpleComponent for AppModel {
             self.sender.input(AppMsg::ClearPendingSelection);
         }
 
-        widgets.home_label.set_visible(!self.sidebar_collapsed);
-        widgets.create_label.set_visible(!self.sidebar_collapsed);
-        widgets.settings_label.set_visible(!self.sidebar_collapsed);
-        
+        widgets.mod_stale_banner.set_revealed(self.mod_results_stale);
         if self.is_searching { widgets.mod_search_stack.set_visible_child_name("spinner"); } 
         else { widgets.mod_search_stack.set_visible_child_name("button"); }
-        
-        widgets.mods_label.set_visible(!self.sidebar_collapsed);
-        widgets.logs_label.set_visible(!self.sidebar_collapsed);
+    }
+}
+
+const ACCOUNT_HINT: &str = "Offline plays as the username above, single player and offline servers only";
+const LOADER_HINT: &str = "Installed on top of this version on the first launch";
+const GAME_DIR_HINT: &str = "Leave empty for an isolated per-profile instance";
+const SHARED_MODS_HINT: &str = "Mods from these folders are added to the profile's own at launch";
+
+const SIDEBAR_COLLAPSED_WIDTH: f64 = 60.0;
+const SIDEBAR_EXPANDED_MIN_WIDTH: f64 = 180.0;
+const SIDEBAR_EXPANDED_MAX_WIDTH: f64 = 250.0;
+const SIDEBAR_ANIMATION_MS: u32 = 200;
+// Window width (sp) below which mini mode swaps the sidebar for a bottom bar
+const MINI_MODE_WIDTH: f64 = 600.0;
+
+// No progress for this long on the loading page and we hint at a dropped connection
+const STALL_HINT_AFTER: std::time::Duration = std::time::Duration::from_secs(60);
+
+const SETTINGS_SAVE_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(400);
+
+// Removes a half-built instance folder if duplicating fails or is cancelled mid-copy
+struct RemoveUnfinishedDir {
+    dir: std::path::PathBuf,
+    finished: bool,
+}
+
+impl Drop for RemoveUnfinishedDir {
+    fn drop(&mut self) {
+        if self.finished {
+            return;
+        }
+        match std::fs::remove_dir_all(&self.dir) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                tracing::error!("Could not remove {}: {}", self.dir.display(), e);
+            }
+            _ => {}
+        }
     }
 }
 
 // Helpers for model to keep update() cleaner
 impl AppModel {
+     // Profiles used to be keyed by "username_version[_fabric]", which was also their instance folder.
+     // Give each one an id and move its folder over. Needs the instances root from settings, so it
+     // runs once both have loaded, whichever arrives last.
+     fn migrate_legacy_profiles(&mut self, sender: &ComponentSender<Self>) {
+         if !self.settings_loaded || self.profiles_status != LoadStatus::Loaded {
+             return;
+         }
+         let legacy: Vec<String> = self.profiles.iter()
+             .filter(|(_, p)| p.id.is_empty())
+             .map(|(key, _)| key.clone())
+             .collect();
+         if legacy.is_empty() {
+             return;
+         }
+         let Some(instances_dir) = self.launcher.as_ref().map(|l| l.config.instances_dir.clone()) else { return };
+
+         for old_key in legacy {
+             let Some(mut profile) = self.profiles.remove(&old_key) else { continue };
+             let id = uuid::Uuid::new_v4().to_string();
+             let old_dir = instances_dir.join(&old_key);
+             if profile.game_dir.is_none() && old_dir.exists() {
+                 if let Err(e) = std::fs::rename(&old_dir, instances_dir.join(&id)) {
+                     // Couldn't move it, point at it instead so nothing is lost
+                     tracing::warn!("Could not move instance {} to {}: {}", old_key, id, e);
+                     profile.game_dir = Some(old_dir.display().to_string());
+                 }
+             }
+             tracing::info!("Assigned id {} to profile {}", id, old_key);
+             for key in [&mut self.selected_mod_profile, &mut self.pending_launch_profile].into_iter().flatten() {
+                 if *key == old_key {
+                     *key = id.clone();
+                 }
+             }
+             profile.id = id.clone();
+             self.profiles.insert(id, profile);
+         }
+         self.save_profiles(sender.clone());
+         self.refresh_mod_profile_dropdown(sender.clone());
+     }
+
+     // Applies the Installed/Favorites filters to the version selector. The selected version is
+     // always kept so filtering never changes what an edited profile runs, and rows are only
+     // added or removed around it so the selection doesn't jump.
+     fn refresh_version_list(&mut self) {
+         if self.version_filter_installed {
+             self.installed_versions = self.launcher.as_ref().map(|l| l.installed_versions()).unwrap_or_default();
+         }
+         let shown: Vec<String> = self.sorted_versions.iter()
+             .filter(|v| {
+                 let selected = self.input_version.as_ref() == Some(*v);
+                 let installed = !self.version_filter_installed || self.installed_versions.contains(*v);
+                 let favorite = !self.version_filter_favorites || self.settings.favorite_versions.contains(*v);
+                 selected || (installed && favorite)
+             })
+             .cloned()
+             .collect();
+
+         if let Some(string_list) = &self.version_list_model {
+             let wanted: HashSet<&str> = shown.iter().map(String::as_str).collect();
+             let mut position = 0;
+             for version in &shown {
+                 while position < string_list.n_items() {
+                     let current = string_list.string(position).map(|s| s.to_string()).unwrap_or_default();
+                     if wanted.contains(current.as_str()) {
+                         break;
+                     }
+                     string_list.remove(position);
+                 }
+                 if string_list.string(position).as_deref() != Some(version.as_str()) {
+                     string_list.splice(position, 0, &[version.as_str()]);
+                 }
+                 position += 1;
+             }
+             while string_list.n_items() > position {
+                 string_list.remove(position);
+             }
+         }
+         self.shown_versions = shown;
+     }
+
+     // Whether the chosen loader has builds for the chosen version. Answers are cached per
+     // version and loader; until one arrives the profile would be saved as vanilla.
+     fn check_loader_availability(&mut self, sender: &ComponentSender<Self>) {
+         let Some(version) = self.input_version.clone() else { return };
+         let loader = self.input_loader;
+         if loader == Loader::Vanilla {
+             self.loader_checking = false;
+             self.loader_available = true;
+             return;
+         }
+         match self.loader_support.get(&(version.clone(), loader)) {
+             Some(available) => {
+                 self.loader_checking = false;
+                 self.loader_available = *available;
+             }
+             None => {
+                 self.loader_checking = true;
+                 self.loader_available = false;
+                 let sender_clone = sender.clone();
+                 self.rt.spawn(async move {
+                     let result = MinecraftLauncher::loader_available(loader, &version).await.map_err(|e| e.to_string());
+                     sender_clone.input(AppMsg::LoaderAvailabilityChecked(version, loader, result));
+                 });
+             }
+         }
+     }
+
+     fn reset_profile_form(&mut self) {
+         self.editing_profile = None;
+         self.input_username.clear();
+         self.input_version = None;
+         self.input_ram = self.settings.new_profile_ram_mb(crate::utils::get_total_memory_mb());
+         self.input_loader = Loader::Vanilla;
+         self.loader_available = true;
+         self.loader_checking = false;
+         self.input_game_dir = None;
+         self.input_shared_mod_dirs.clear();
+         self.input_notes.clear();
+         self.input_account = None;
+         self.input_jvm_args.clear();
+     }
+
+     // Settings toggles can fire in quick bursts (e.g. flipping the sidebar), so coalesce them
+     // into a single write once things go quiet instead of racing one write per change.
      fn save_settings(&self) {
+         use std::sync::atomic::Ordering;
+
          if let Some(launcher) = &self.launcher {
-             let config_dir = launcher.config.minecraft_dir.clone();
+             let config_dir = launcher.config.config_dir.clone();
              let settings_clone = self.settings.clone();
-             std::thread::spawn(move || {
-                 let rt = Runtime::new().unwrap();
-                 rt.block_on(async { let _ = settings_clone.save(&config_dir).await; });
+             let generation = self.settings_save_generation.fetch_add(1, Ordering::SeqCst) + 1;
+             let latest = self.settings_save_generation.clone();
+             if let Ok(mut pending) = self.pending_settings_save.lock() {
+                 *pending = Some((config_dir, settings_clone));
+             }
+             let pending = self.pending_settings_save.clone();
+             self.rt.spawn(async move {
+                 tokio::time::sleep(SETTINGS_SAVE_DEBOUNCE).await;
+                 if latest.load(Ordering::SeqCst) != generation {
+                     return; // Superseded by a newer change
+                 }
+                 let Some((config_dir, settings)) = pending.lock().ok().and_then(|mut p| p.take()) else {
+                     return; // Already written on close
+                 };
+                 let _ = settings.save(&config_dir).await;
              });
          }
      }
 
      fn save_profiles(&self, sender: ComponentSender<Self>) {
          if let Some(launcher) = &self.launcher {
-             let config_dir = launcher.config.minecraft_dir.clone();
+             let config_dir = launcher.config.config_dir.clone();
              let profiles_clone = self.profiles.clone();
              std::thread::spawn(move || {
                  let rt = Runtime::new().unwrap();
                  rt.block_on(async {
                      let path = config_dir.join("profiles.json");
                      let json = serde_json::to_string_pretty(&profiles_clone).unwrap_or_default();
-                     if let Err(e) = tokio::fs::write(&path, json).await {
+                     let written = match tokio::fs::create_dir_all(&config_dir).await {
+                         Ok(()) => tokio::fs::write(&path, json).await,
+                         Err(e) => Err(e),
+                     };
+                     if let Err(e) = written {
                          sender.input(AppMsg::Error(format!("Failed to save profiles: {}", e)));
                      }
                  });
@@ -1216,17 +3951,17 @@ impl AppModel {
      }
 
      fn refresh_mod_profile_dropdown(&mut self, sender: ComponentSender<Self>) {
-         let mut display_strings = Vec::new();
-         let mut sorted_keys: Vec<&String> = self.profiles.keys().collect();
-         sorted_keys.sort();
-
-         for key in sorted_keys {
-              if let Some(profile) = self.profiles.get(key) {
-                  if profile.is_fabric {
-                      display_strings.push(format!("{} - {}", profile.username, profile.version));
-                  }
-              }
-         }
+         let mut modded_profiles: Vec<(&String, &Profile)> = self.profiles.iter().filter(|(_, p)| p.loader != Loader::Vanilla).collect();
+         modded_profiles.sort_by(|a, b| (&a.1.username, &a.1.version).cmp(&(&b.1.username, &b.1.version)));
+
+         let display_strings: Vec<String> = modded_profiles.iter()
+             .map(|(_, profile)| match profile.loader_name() {
+                 Some(loader) => format!("{} - {} ({})", profile.username, profile.version, loader),
+                 None => format!("{} - {}", profile.username, profile.version),
+             })
+             .collect();
+         // Display strings aren't unique, the dropdown position maps back to the profile id
+         self.mod_profile_ids = modded_profiles.iter().map(|(id, _)| (*id).clone()).collect();
 
          let display_strs: Vec<&str> = display_strings.iter().map(|s| s.as_str()).collect();
          let model = gtk::StringList::new(&display_strs);
@@ -1234,49 +3969,419 @@ impl AppModel {
          self.mod_profile_list_updated = true;
          
          // Auto-select first if we have no selection
-         if self.selected_mod_profile.is_none() && !display_strings.is_empty() {
-             if let Some(first) = display_strings.first() {
-                 if let Some((name, version)) = first.rsplit_once(" - ") {
-                     let key = format!("{}_{}_fabric", name, version);
-                     if self.profiles.contains_key(&key) {
-                         self.selected_mod_profile = Some(key);
-                         sender.input(AppMsg::RefreshInstalledMods);
-                     }
+         let selection_gone = self.selected_mod_profile.as_ref().is_none_or(|id| !self.mod_profile_ids.contains(id));
+         if let Some(first) = self.mod_profile_ids.first().filter(|_| selection_gone) {
+             self.selected_mod_profile = Some(first.clone());
+             sender.input(AppMsg::RefreshInstalledMods);
+         }
+     }
+     
+     // The directory the game is launched in: the profile's override, or its isolated instance.
+     // Launching and the Mods page must agree on this, otherwise mods land where the game never looks.
+     fn get_game_dir(&self, profile_name: &str) -> Option<std::path::PathBuf> {
+         let profile = self.profiles.get(profile_name)?;
+         if let Some(dir) = &profile.game_dir {
+             Some(std::path::PathBuf::from(dir))
+         } else {
+             self.launcher.as_ref().map(|l| l.config.instances_dir.join(profile_name))
+         }
+     }
+
+     // Moves the launcher to the configured game data dir, or the default one. A saved dir that
+     // can't be written to any more (unmounted drive, changed permissions) falls back to the
+     // default for this session without forgetting the setting.
+     fn apply_data_dir(&mut self) {
+         let custom = self.settings.custom_data_dir.clone().map(std::path::PathBuf::from);
+         let Some(launcher) = self.launcher.as_mut() else { return };
+         let usable = match custom.map(|dir| crate::utils::check_dir_writable(&dir).map(|_| dir)) {
+             Some(Ok(dir)) => Some(dir),
+             Some(Err(e)) => {
+                 self.sender.input(AppMsg::ShowToast(format!("Minecraft folder is not writable, using the default: {}", e)));
+                 None
+             }
+             None => None,
+         };
+         let Some(dir) = usable.or_else(|| crate::config::LauncherConfig::default_minecraft_dir().ok()) else { return };
+         if launcher.config.minecraft_dir != dir {
+             launcher.set_minecraft_dir(dir);
+         }
+     }
+
+     fn remove_leftover_natives_zips(&self) {
+         let Some(launcher) = &self.launcher else { return };
+         let library_manager = launcher.library_manager.clone();
+         self.rt.spawn(async move {
+             let freed = library_manager.remove_leftover_natives_zips().await;
+             if freed > 0 {
+                 tracing::info!("Removed leftover natives archives, freed {}", crate::utils::format_size(freed));
+             }
+         });
+     }
+
+     // Pushes the configured instances root (or the default) into the launcher config
+     fn apply_instances_dir(&mut self) {
+         let custom = self.settings.instances_dir.clone();
+         if let Some(launcher) = self.launcher.as_mut() {
+             launcher.config.instances_dir = custom
+                 .map(std::path::PathBuf::from)
+                 .unwrap_or_else(|| launcher.config.default_instances_dir());
+         }
+     }
+
+     // Sizes the instance of every profile not measured yet, in the background. Needs the
+     // instances root from settings, so nothing happens before both have loaded.
+     fn request_profile_sizes(&mut self) {
+         if !self.settings_loaded || self.profiles_status != LoadStatus::Loaded {
+             return;
+         }
+         let pending: Vec<(String, std::path::PathBuf)> = self.profiles.keys()
+             .filter(|name| !self.profile_sizes_requested.contains(*name))
+             .filter_map(|name| self.get_game_dir(name).map(|dir| (name.clone(), dir)))
+             .collect();
+         for (name, dir) in pending {
+             self.profile_sizes_requested.insert(name.clone());
+             let sender = self.sender.clone();
+             self.rt.spawn(async move {
+                 if let Ok(bytes) = tokio::task::spawn_blocking(move || crate::utils::dir_size(&dir)).await {
+                     sender.input(AppMsg::ProfileSizeComputed(name, bytes));
                  }
+             });
+         }
+     }
+
+     fn forget_profile_size(&mut self, profile_name: &str) {
+         self.profile_sizes.remove(profile_name);
+         self.profile_sizes_requested.remove(profile_name);
+     }
+
+     // Instance folders moved to another root, every size was measured in the old one
+     fn remeasure_profile_sizes(&mut self) {
+         self.profile_sizes.clear();
+         self.profile_sizes_requested.clear();
+         self.request_profile_sizes();
+     }
+
+     fn refresh_installed_versions_list(&self) {
+         if let Some(list) = &self.installed_versions_list {
+             update_installed_versions_list(list, &self.downloaded_versions, &self.profiles, &self.sender);
+         }
+     }
+
+     fn refresh_accounts_list(&self) {
+         if let Some(list) = &self.accounts_list {
+             update_accounts_list(list, &self.accounts, self.sign_in_task.is_some(), &self.sender);
+         }
+     }
+
+     // Adds the account, or replaces the stored one with the same uuid (a new sign in or token)
+     fn store_account(&mut self, account: Account) {
+         match self.accounts.iter_mut().find(|a| a.uuid == account.uuid) {
+             Some(existing) => *existing = account,
+             None => self.accounts.push(account),
+         }
+         self.save_accounts();
+     }
+
+     fn save_accounts(&self) {
+         let Some(launcher) = &self.launcher else { return };
+         let config_dir = launcher.config.config_dir.clone();
+         let accounts = self.accounts.clone();
+         let sender = self.sender.clone();
+         self.rt.spawn(async move {
+             if let Err(e) = crate::auth::save_accounts(&config_dir, &accounts).await {
+                 sender.input(AppMsg::ShowToast(format!("Could not save accounts: {}", e)));
              }
+         });
+     }
+
+     fn refresh_java_runtimes_list(&self) {
+         if let Some(list) = &self.java_runtimes_list {
+             update_java_runtimes_list(list, &self.java_runtimes, self.java_updates_checking, &self.sender);
          }
      }
-     
-     fn get_mods_dir(&self) -> Option<std::path::PathBuf> {
-         if let Some(profile_name) = &self.selected_mod_profile {
-             if let Some(profile) = self.profiles.get(profile_name) {
-                 if let Some(dir) = &profile.game_dir {
-                     Some(std::path::PathBuf::from(dir).join("mods"))
-                 } else if let Some(launcher) = &self.launcher {
-                     Some(launcher.config.minecraft_dir.join("instances").join(profile_name).join("mods"))
-                 } else { None }
-             } else { None }
-         } else { None }
+
+     fn apply_appearance(&self) {
+         let style_manager = adw::StyleManager::default();
+         style_manager.set_color_scheme(match self.settings.theme {
+             Theme::Dark => adw::ColorScheme::ForceDark,
+             Theme::Light => adw::ColorScheme::ForceLight,
+             Theme::System => adw::ColorScheme::Default,
+         });
+         if let Some(window) = &self.window {
+             if self.settings.transparent {
+                 window.add_css_class("transparent-window");
+             } else {
+                 window.remove_css_class("transparent-window");
+             }
+         }
+     }
+
+     fn apply_options_template(&mut self) {
+         let template = self.settings.options_template.clone().map(std::path::PathBuf::from);
+         if let Some(launcher) = self.launcher.as_mut() {
+             launcher.config.options_template = template;
+         }
+     }
+
+     // Drops the oldest game log lines beyond the configured limit, long sessions would
+     // otherwise keep growing the buffer
+     fn trim_logs(&mut self) {
+         let max_lines = self.settings.max_log_lines.max(1);
+         while self.raw_logs.len() > max_lines {
+             self.raw_logs.pop_front();
+         }
+         // The launcher's own log grows just as fast at Debug level
+         for buffer in [&self.logs, &self.launcher_logs] {
+             // The buffer ends in a newline, its last line is always empty
+             let excess = (buffer.line_count() - 1).saturating_sub(max_lines as i32);
+             if excess > 0 {
+                 let mut start = buffer.start_iter();
+                 let mut end = buffer.iter_at_line(excess).unwrap_or_else(|| buffer.end_iter());
+                 buffer.delete(&mut start, &mut end);
+             }
+         }
+     }
+
+     fn last_played_profile(&self) -> Option<&Profile> {
+         self.profiles.values()
+             .filter(|p| p.last_launch.is_some())
+             .max_by_key(|p| p.last_launch)
+     }
+
+     // Heads copied and saved logs, so a bug report says when and what was played
+     fn logs_header(&self) -> String {
+         let saved = gtk::glib::DateTime::now_local().and_then(|now| now.format("%Y-%m-%d %H:%M:%S")).unwrap_or_default();
+         let mut header = format!("RCraft game log, saved {}\n", saved);
+         if let Some(profile) = self.last_played_profile() {
+             header.push_str(&format!("Profile: {} ({} {})\n", profile.username, profile.loader.name(), profile.version));
+         }
+         header.push('\n');
+         header
+     }
+
+     fn pick_folder(&self, title: &str, on_pick: impl Fn(std::path::PathBuf) + 'static) {
+         self.pick_path(title, gtk::FileChooserAction::SelectFolder, "Select", None, on_pick);
+     }
+
+     fn pick_file(&self, title: &str, on_pick: impl Fn(std::path::PathBuf) + 'static) {
+         self.pick_path(title, gtk::FileChooserAction::Open, "Select", None, on_pick);
+     }
+
+     // `current_name` is the file name the dialog suggests
+     fn pick_save_file(&self, title: &str, current_name: &str, on_pick: impl Fn(std::path::PathBuf) + 'static) {
+         self.pick_path(title, gtk::FileChooserAction::Save, "Save", Some(current_name), on_pick);
+     }
+
+     fn pick_path(&self, title: &str, action: gtk::FileChooserAction, accept_label: &str, current_name: Option<&str>, on_pick: impl Fn(std::path::PathBuf) + 'static) {
+         let dialog = gtk::FileChooserNative::new(
+             Some(title),
+             self.window.as_ref(),
+             action,
+             Some(accept_label),
+             Some("Cancel"),
+         );
+         if let Some(name) = current_name {
+             dialog.set_current_name(name);
+         }
+         // The closure holds the only strong reference, keeping the native dialog alive until it answers
+         let dialog_clone = dialog.clone();
+         dialog.connect_response(move |d, response| {
+             let picked = d.file().and_then(|f| f.path()).filter(|_| response == gtk::ResponseType::Accept);
+             if let Some(path) = picked {
+                 on_pick(path);
+             }
+             dialog_clone.destroy();
+         });
+         dialog.show();
+     }
+
+     // Where projects of `project_type` go for the selected profile. Datapacks belong to a world,
+     // the one played last, so there's nowhere for them until the profile has one.
+     fn get_install_dir(&self, project_type: ProjectType) -> Option<std::path::PathBuf> {
+         let profile_name = self.selected_mod_profile.as_ref()?;
+         let game_dir = self.get_game_dir(profile_name)?;
+         match project_type {
+             ProjectType::Datapack => latest_world(&game_dir.join("saves")).map(|world| world.join(project_type.folder())),
+             _ => Some(game_dir.join(project_type.folder())),
+         }
+     }
+
+     // Why get_install_dir came back empty, for the toast
+     fn no_install_dir_reason(&self) -> String {
+         if self.selected_mod_profile.is_some() && self.mod_project_type == ProjectType::Datapack {
+             "Datapacks go into a world, play this profile once to create one".to_string()
+         } else {
+             format!("Select a profile before installing {}", self.mod_project_type.name().to_lowercase())
+         }
      }
      
+     // Version and loader to filter Modrinth by; the loader only for project types that depend on it
      fn get_profile_filters(&self) -> (Option<String>, Option<String>) {
          if let Some(profile_name) = &self.selected_mod_profile {
              if let Some(profile) = self.profiles.get(profile_name) {
-                 (Some(profile.version.clone()), Some("fabric".to_string()))
+                 let loader = if self.mod_project_type.is_loader_specific() {
+                     profile.loader_id()
+                 } else if self.mod_project_type == ProjectType::Datapack {
+                     // Projects shipping both a datapack and a mod build would hand out the jar
+                     Some("datapack")
+                 } else {
+                     None
+                 };
+                 (Some(profile.version.clone()), loader.map(str::to_string))
              } else { (None, None) }
          } else { (None, None) }
      }
 
+     // Starts the elapsed time clock of the loading page. Ticks stop on their own once
+     // the state leaves Launching/Downloading (see OperationTick).
+     fn begin_operation(&mut self, sender: &ComponentSender<Self>) {
+         let now = std::time::Instant::now();
+         if self.operation_started.is_none() {
+             self.operation_started = Some(now);
+         }
+         self.last_progress_at = Some(now);
+
+         if self.operation_timer.is_none() {
+             let sender_clone = sender.clone();
+             self.operation_timer = Some(gtk::glib::timeout_add_local(std::time::Duration::from_secs(1), move || {
+                 sender_clone.input(AppMsg::OperationTick);
+                 gtk::glib::ControlFlow::Continue
+             }));
+         }
+     }
+
+     // Empty state for the browse list, shown by the ListBox itself while it has no rows
+     fn show_mod_search_placeholder(&mut self, title: &str, description: &str) {
+         let Some(list) = &self.mod_browse_list else { return };
+         while let Some(child) = list.first_child() { list.remove(&child); }
+         self.mod_action_buttons.clear();
+
+         let status = adw::StatusPage::builder()
+             .icon_name("system-search-symbolic")
+             .title(title)
+             .description(description)
+             .build();
+         status.add_css_class("compact");
+         list.set_placeholder(Some(&status));
+     }
+
+     // Brings the browse list in line with `results` by project id: rows still in the results are
+     // kept (icon included) and moved into place, only new ones are built and fetch an icon.
+     fn update_mod_browse_list(&mut self, results: &[ModSearchResult], sender: &ComponentSender<Self>) {
+         let Some(list) = self.mod_browse_list.clone() else { return };
+
+         let wanted: HashSet<&str> = results.iter().map(|m| m.project_id.as_str()).collect();
+         let mut existing: HashMap<String, gtk::ListBoxRow> = HashMap::new();
+         let mut child = list.first_child();
+         while let Some(widget) = child {
+             child = widget.next_sibling();
+             let Ok(row) = widget.downcast::<gtk::ListBoxRow>() else { continue };
+             let project_id = row.widget_name().to_string();
+             if wanted.contains(project_id.as_str()) {
+                 existing.insert(project_id, row);
+             } else {
+                 list.remove(&row);
+                 self.mod_action_buttons.remove(&project_id);
+             }
+         }
+
+         for (position, mod_data) in results.iter().enumerate() {
+             let position = position as i32;
+             match existing.remove(&mod_data.project_id) {
+                 Some(row) => {
+                     if list.row_at_index(position).as_ref() != Some(&row) {
+                         list.remove(&row);
+                         list.insert(&row, position);
+                     }
+                 }
+                 None => {
+                     let (row, button) = create_mod_search_result_row(mod_data, sender);
+                     list.insert(&row, position);
+                     self.mod_action_buttons.insert(mod_data.project_id.clone(), button);
+                     if let Some(url) = &mod_data.icon_url {
+                          sender.input(AppMsg::DownloadModIcon(mod_data.project_id.clone(), url.clone()));
+                     }
+                 }
+             }
+             self.update_mod_button_state(&mod_data.project_id);
+         }
+     }
+
+     // Cached PNG of the icon a jar declares in fabric.mod.json. Keyed by a hash of the icon bytes,
+     // so jars that share an internal icon path never collide and identical icons share one file.
+     fn installed_mod_icon(jar_path: &std::path::Path) -> Option<std::path::PathBuf> {
+          let mut archive = ZipArchive::new(File::open(jar_path).ok()?).ok()?;
+
+          let mut manifest = String::new();
+          archive.by_name("fabric.mod.json").ok()?.read_to_string(&mut manifest).ok()?;
+          let json = serde_json::from_str::<serde_json::Value>(&manifest).ok()?;
+          // Either a single path or a map of sizes ("16", "128"...) to paths, take the largest
+          let icon = match json.get("icon")? {
+              serde_json::Value::String(path) => path.clone(),
+              serde_json::Value::Object(sizes) => sizes.iter()
+                  .filter_map(|(size, path)| Some((size.parse::<u32>().ok()?, path.as_str()?)))
+                  .max_by_key(|(size, _)| *size)?
+                  .1.to_string(),
+              _ => return None,
+          };
+          let icon = icon.trim_start_matches("./");
+
+          let mut buf = Vec::new();
+          archive.by_name(icon).ok()?.read_to_end(&mut buf).ok()?;
+
+          let cache_dir = crate::config::cache_dir().join("installed_icons");
+          let icon_path = cache_dir.join(format!("{}.png", crate::utils::sha1_hex(&buf)));
+          if !icon_path.exists() {
+              let img = image::load_from_memory(&buf).ok()?;
+              std::fs::create_dir_all(&cache_dir).ok()?;
+              crate::utils::save_png_atomic(&img, &icon_path).ok()?;
+          }
+          Some(icon_path)
+     }
+
+     // installed_mod_icon for the Installed list, which is rebuilt on every change. A jar is only
+     // opened again once its mtime changes or the cached PNG was cleared away.
+     fn cached_installed_mod_icon(&mut self, jar_path: &std::path::Path) -> Option<std::path::PathBuf> {
+          let modified = std::fs::metadata(jar_path).and_then(|m| m.modified()).ok();
+          let cached = self.installed_mod_icons.get(jar_path)
+              .filter(|(seen, icon)| *seen == modified && icon.as_ref().is_none_or(|p| p.exists()));
+          if let Some((_, icon)) = cached {
+              return icon.clone();
+          }
+          let icon = Self::installed_mod_icon(jar_path);
+          self.installed_mod_icons.insert(jar_path.to_path_buf(), (modified, icon.clone()));
+          icon
+     }
+
      fn refresh_installed_mods(&mut self, sender: ComponentSender<Self>) {
-          if let Some(list) = &self.mod_installed_list {
+          // Rebuild the ProjectID -> Filename map from the persisted index so it survives restarts
+          if let Some(mods_dir) = self.get_install_dir(self.mod_project_type) {
+              let index = ModIndex::load(&mods_dir);
+              self.installed_mods = index.mods.into_iter()
+                  .filter(|(_, m)| installed_path(&mods_dir, &m.filename).is_some())
+                  .map(|(pid, m)| (pid, m.filename))
+                  .collect();
+          } else {
+              self.installed_mods.clear();
+          }
+          let visible: Vec<String> = self.mod_action_buttons.keys().cloned().collect();
+          for pid in visible {
+              self.update_mod_button_state(&pid);
+          }
+
+          if let Some(list) = self.mod_installed_list.clone() {
               while let Some(child) = list.first_child() { list.remove(&child); }
               
-              if let Some(mods_dir) = self.get_mods_dir() {
+              if let Some(mods_dir) = self.get_install_dir(self.mod_project_type) {
+                  // Links made at the last launch are listed under their shared folder below
+                  let linked = crate::shared_mods::linked_names(&mods_dir);
+                  let extension = self.mod_project_type.file_extension();
                   if mods_dir.exists() {
                        if let Ok(mut entries) = std::fs::read_dir(&mods_dir) {
                             while let Some(Ok(entry)) = entries.next() {
                                 if let Some(name) = entry.file_name().to_str() {
-                                    if name.ends_with(".jar") {
+                                    let disabled = name.ends_with(&format!("{}{}", extension, DISABLED_SUFFIX));
+                                    if (name.ends_with(extension) || disabled) && !linked.contains(name) {
                                         // Helper to create row
                                         let row = gtk::ListBoxRow::new();
                                         let box_container = gtk::Box::new(gtk::Orientation::Horizontal, 12);
@@ -1289,51 +4394,28 @@ impl AppModel {
                                             
                                         // Try to extract icon
                                         let jar_path = mods_dir.join(name);
-                                        let cache_dir = std::env::temp_dir().join("rcraft").join("cache").join("installed_icons");
-                                        let _ = std::fs::create_dir_all(&cache_dir);
-                                        let icon_path = cache_dir.join(format!("{}.png", name));
-
-                                        if icon_path.exists() {
+                                        if let Some(icon_path) = self.cached_installed_mod_icon(&jar_path) {
                                             icon_image.set_from_file(Some(icon_path.to_str().unwrap_or_default()));
-                                        } else {
-                                             // Extraction logic (simplified for brevity, assume similar to before)
-                                              if let Ok(file) = File::open(&jar_path) {
-                                                  if let Ok(mut archive) = ZipArchive::new(file) {
-                                                      // Check fabric.mod.json for icon path -> extract -> save
-                                                      // For this task assume it's working or copy detailed logic if needed.
-                                                      // I'll copy a simplified version for now to save space, but it's important.
-                                                       let mut icon_p: Option<String> = None;
-                                                       if let Ok(mut json_file) = archive.by_name("fabric.mod.json") {
-                                                            let mut s = String::new();
-                                                            if json_file.read_to_string(&mut s).is_ok() {
-                                                                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&s) {
-                                                                    if let Some(v) = json.get("icon") {
-                                                                        if let Some(is) = v.as_str() { icon_p = Some(is.to_string()); }
-                                                                        else if let Some(obj) = v.as_object() {
-                                                                            if let Some(is) = obj.values().last().and_then(|x| x.as_str()) { icon_p = Some(is.to_string()); }
-                                                                        }
-                                                                    }
-                                                                }
-                                                            }
-                                                       }
-                                                       
-                                                       if let Some(mut ip) = icon_p {
-                                                           if ip.starts_with("./") { ip = ip[2..].to_string(); }
-                                                           if let Ok(mut zf) = archive.by_name(&ip) {
-                                                               let mut buf = Vec::new();
-                                                               if zf.read_to_end(&mut buf).is_ok() {
-                                                                    if let Ok(img) = image::load_from_memory(&buf) {
-                                                                        let _ = img.save_with_format(&icon_path, image::ImageFormat::Png);
-                                                                        icon_image.set_from_file(Some(icon_path.to_str().unwrap_or_default()));
-                                                                    }
-                                                               }
-                                                           }
-                                                       }
-                                                  }
-                                              }
                                         }
 
-                                        let label = gtk::Label::builder().label(name).halign(gtk::Align::Start).hexpand(true).build();
+                                        let label = gtk::Label::builder().label(name.trim_end_matches(DISABLED_SUFFIX)).halign(gtk::Align::Start).hexpand(true).build();
+                                        if disabled {
+                                            icon_image.add_css_class("dim-label");
+                                            label.add_css_class("dim-label");
+                                        }
+
+                                        let enabled_switch = gtk::Switch::builder()
+                                            .active(!disabled)
+                                            .valign(gtk::Align::Center)
+                                            .tooltip_text(if disabled { "Disabled, the game won't load it" } else { "Enabled" })
+                                            .build();
+                                        let sender_clone = sender.clone();
+                                        let fname = name.to_string();
+                                        let dir = mods_dir.clone();
+                                        enabled_switch.connect_state_set(move |_, _| {
+                                            sender_clone.input(AppMsg::ToggleModEnabled(dir.clone(), fname.clone()));
+                                            gtk::glib::Propagation::Proceed
+                                        });
                                         let del_btn = gtk::Button::builder().icon_name("user-trash-symbolic").css_classes(vec!["destructive-action"]).tooltip_text("Uninstall").build();
                                         
                                         let sender_clone = sender.clone();
@@ -1342,6 +4424,20 @@ impl AppModel {
 
                                         box_container.append(&icon_image);
                                         box_container.append(&label);
+                                        if let Some(version) = self.mod_updates.get(name) {
+                                            let updating = self.updating_mods.contains(name);
+                                            let update_btn = gtk::Button::builder()
+                                                .label(if updating { "Updating..." } else { "Update" })
+                                                .css_classes(vec!["suggested-action"])
+                                                .tooltip_text(format!("Update to {}", version.version_number))
+                                                .sensitive(!updating)
+                                                .build();
+                                            let sender_clone = sender.clone();
+                                            let fname = name.to_string();
+                                            update_btn.connect_clicked(move |_| { sender_clone.input(AppMsg::UpdateMod(fname.clone())); });
+                                            box_container.append(&update_btn);
+                                        }
+                                        box_container.append(&enabled_switch);
                                         box_container.append(&del_btn);
                                         row.set_child(Some(&box_container));
                                         list.append(&row);
@@ -1350,6 +4446,36 @@ impl AppModel {
                             }
                        }
                   }
+
+                  let shared_dirs = self.selected_mod_profile.as_ref()
+                      .filter(|_| self.mod_project_type == ProjectType::Mod)
+                      .and_then(|id| self.profiles.get(id))
+                      .map(|p| p.shared_mod_dirs.clone())
+                      .unwrap_or_default();
+                  for dir in shared_dirs {
+                      let dir = std::path::PathBuf::from(dir);
+                      let dir_name = dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| dir.display().to_string());
+                      for name in crate::shared_mods::jars_in(&dir) {
+                          // Only clashes with the profile's own jars are known before launch
+                          let shadowed = !linked.contains(&name) && mods_dir.join(&name).exists();
+                          let row = adw::ActionRow::builder()
+                              .title(&name)
+                              .subtitle(if shadowed {
+                                  format!("Shared from {}, not used: the profile has its own copy", dir_name)
+                              } else {
+                                  format!("Shared from {}", dir_name)
+                              })
+                              .use_markup(false)
+                              .tooltip_text(dir.display().to_string())
+                              .build();
+                          let icon_image = gtk::Image::builder()
+                              .icon_name("folder-symbolic")
+                              .pixel_size(32)
+                              .build();
+                          row.add_prefix(&icon_image);
+                          list.append(&row);
+                      }
+                  }
               }
           }
      }
@@ -1365,6 +4491,75 @@ impl AppWidgets {
         self.logs_button.set_sensitive(sensitive);
     }
     
+    fn apply_sidebar_collapsed(&mut self, collapsed: bool, animate: bool) {
+        self.sidebar_applied = Some(collapsed);
+        if let Some(animation) = self.sidebar_animation.take() {
+            animation.skip();
+        }
+
+        let boxes = [&self.home_box, &self.create_box, &self.settings_box, &self.mods_box, &self.logs_box];
+        let labels = [&self.home_label, &self.create_label, &self.settings_label, &self.mods_label, &self.logs_label];
+        let set_labels = move |visible: bool| {
+            let align = if visible { gtk::Align::Start } else { gtk::Align::Center };
+            for b in boxes { b.set_halign(align); }
+            for l in labels { l.set_visible(visible); }
+        };
+
+        let (from, to) = if collapsed {
+            (SIDEBAR_EXPANDED_MAX_WIDTH, SIDEBAR_COLLAPSED_WIDTH)
+        } else {
+            (SIDEBAR_COLLAPSED_WIDTH, SIDEBAR_EXPANDED_MAX_WIDTH)
+        };
+
+        let split_view = self.navigation_split_view.clone();
+        let set_width = move |width: f64| {
+            split_view.set_min_sidebar_width(width.min(SIDEBAR_EXPANDED_MIN_WIDTH));
+            split_view.set_max_sidebar_width(width);
+        };
+
+        if !animate {
+            set_width(to);
+            set_labels(!collapsed);
+            return;
+        }
+
+        // Labels go away before shrinking and only come back once there is room for them
+        if collapsed {
+            set_labels(false);
+        }
+
+        let animation = adw::TimedAnimation::new(
+            &self.navigation_split_view,
+            from,
+            to,
+            SIDEBAR_ANIMATION_MS,
+            adw::CallbackAnimationTarget::new(set_width),
+        );
+        animation.set_easing(adw::Easing::EaseOutCubic);
+
+        if !collapsed {
+            let boxes: Vec<gtk::Box> = boxes.into_iter().cloned().collect();
+            let labels: Vec<gtk::Label> = labels.into_iter().cloned().collect();
+            animation.connect_done(move |_| {
+                for b in &boxes { b.set_halign(gtk::Align::Start); }
+                for l in &labels { l.set_visible(true); }
+            });
+        }
+
+        animation.play();
+        self.sidebar_animation = Some(animation);
+    }
+
+    fn highlight_bottom_nav(&self, current: Section) {
+        for (section, button) in &self.bottom_nav_buttons {
+            if *section == current {
+                button.add_css_class("accent");
+            } else {
+                button.remove_css_class("accent");
+            }
+        }
+    }
+
     fn clear_sidebar_selection(&self) {
         self.home_button.remove_css_class("suggested-action");
         self.create_sidebar_button.remove_css_class("suggested-action");
@@ -1373,3 +4568,25 @@ impl AppWidgets {
         self.logs_button.remove_css_class("suggested-action");
     }
 }
+
+// World played last in a saves dir, going by when its level.dat was written
+fn latest_world(saves_dir: &std::path::Path) -> Option<std::path::PathBuf> {
+    std::fs::read_dir(saves_dir).ok()?.flatten()
+        .filter_map(|entry| {
+            let modified = entry.path().join("level.dat").metadata().and_then(|m| m.modified()).ok()?;
+            Some((modified, entry.path()))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, path)| path)
+}
+
+fn offline_message(version: &str) -> String {
+    format!("Minecraft {} isn't downloaded yet and you're offline. Connect to the internet so it can be downloaded, then try again.", version)
+}
+
+// Couldn't reach the server at all, as opposed to the server answering with an error
+fn is_network_error(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause.downcast_ref::<reqwest::Error>().is_some_and(|e| e.is_connect() || e.is_timeout())
+    })
+}
@@ -0,0 +1,335 @@
+use relm4::gtk;
+use relm4::{ComponentSender, RelmWidgetExt};
+use gtk::prelude::*;
+use adw::prelude::*;
+use crate::ui::model::AppModel;
+use crate::ui::msg::AppMsg;
+use crate::models::{ModProject, ModVersion};
+
+// In-app detail view for a Modrinth project, shown inside the Mods page stack
+pub struct ModDetailView {
+    pub container: gtk::Box,
+    pub content_stack: gtk::Stack,
+    pub title_label: gtk::Label,
+    pub summary_label: gtk::Label,
+    pub body_label: gtk::Label,
+    pub gallery_box: gtk::FlowBox,
+    pub versions_list: gtk::ListBox,
+    pub dependencies_list: gtk::ListBox,
+    pub install_button: gtk::Button,
+}
+
+pub fn create_mod_detail_page(sender: &ComponentSender<AppModel>) -> ModDetailView {
+    let container = gtk::Box::new(gtk::Orientation::Vertical, 12);
+    container.set_vexpand(true);
+    container.set_hexpand(true);
+
+    // Header: back, title, install
+    let header = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+
+    let back_button = gtk::Button::builder()
+        .icon_name("go-previous-symbolic")
+        .tooltip_text("Back to results")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    back_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::CloseModDetails);
+    });
+
+    let title_label = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .ellipsize(gtk::pango::EllipsizeMode::End)
+        .css_classes(vec!["title-2"])
+        .build();
+
+    let install_button = gtk::Button::builder()
+        .label("Install")
+        .css_classes(vec!["suggested-action"])
+        .valign(gtk::Align::Center)
+        .build();
+
+    // The project id of the shown mod lives in the widget name
+    let sender_clone = sender.clone();
+    install_button.connect_clicked(move |btn| {
+        let project_id = btn.widget_name().to_string();
+        if !project_id.is_empty() {
+            sender_clone.input(AppMsg::ModActionButtonClicked(project_id));
+        }
+    });
+
+    header.append(&back_button);
+    header.append(&title_label);
+    header.append(&install_button);
+
+    // Content
+    let content_box = gtk::Box::new(gtk::Orientation::Vertical, 18);
+    content_box.set_margin_all(6);
+
+    let summary_label = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .wrap(true)
+        .css_classes(vec!["dim-label"])
+        .build();
+
+    let gallery_box = gtk::FlowBox::builder()
+        .selection_mode(gtk::SelectionMode::None)
+        .max_children_per_line(3)
+        .row_spacing(6)
+        .column_spacing(6)
+        .build();
+
+    let body_label = gtk::Label::builder()
+        .halign(gtk::Align::Start)
+        .xalign(0.0)
+        .wrap(true)
+        .selectable(true)
+        .use_markup(true)
+        .build();
+
+    let versions_list = gtk::ListBox::new();
+    versions_list.add_css_class("boxed-list");
+    versions_list.set_selection_mode(gtk::SelectionMode::None);
+
+    let dependencies_list = gtk::ListBox::new();
+    dependencies_list.add_css_class("boxed-list");
+    dependencies_list.set_selection_mode(gtk::SelectionMode::None);
+
+    let section_title = |text: &str| {
+        gtk::Label::builder()
+            .label(text)
+            .halign(gtk::Align::Start)
+            .css_classes(vec!["title-4"])
+            .build()
+    };
+
+    content_box.append(&summary_label);
+    content_box.append(&gallery_box);
+    content_box.append(&body_label);
+    content_box.append(&section_title("Dependencies"));
+    content_box.append(&dependencies_list);
+    content_box.append(&section_title("Versions"));
+    content_box.append(&versions_list);
+
+    let scroll = gtk::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    scroll.set_hexpand(true);
+    scroll.set_child(Some(&content_box));
+
+    let spinner = gtk::Spinner::builder()
+        .spinning(true)
+        .width_request(32)
+        .height_request(32)
+        .halign(gtk::Align::Center)
+        .valign(gtk::Align::Center)
+        .build();
+
+    let content_stack = gtk::Stack::new();
+    content_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+    content_stack.add_named(&spinner, Some("loading"));
+    content_stack.add_named(&scroll, Some("content"));
+
+    container.append(&header);
+    container.append(&content_stack);
+
+    ModDetailView {
+        container,
+        content_stack,
+        title_label,
+        summary_label,
+        body_label,
+        gallery_box,
+        versions_list,
+        dependencies_list,
+        install_button,
+    }
+}
+
+impl ModDetailView {
+    pub fn show_loading(&self, project_id: &str) {
+        self.install_button.set_widget_name(project_id);
+        self.install_button.set_sensitive(false);
+        self.title_label.set_text("");
+        self.content_stack.set_visible_child_name("loading");
+    }
+
+    pub fn current_project(&self) -> String {
+        self.install_button.widget_name().to_string()
+    }
+
+    // Same states as the install buttons in the browse list, the click toggles between them
+    pub fn set_install_state(&self, installing: bool, installed: bool) {
+        let label = if installing { "Installing..." } else if installed { "Uninstall" } else { "Install" };
+        self.install_button.set_label(label);
+        self.install_button.set_sensitive(!installing);
+        if installed {
+            self.install_button.remove_css_class("suggested-action");
+            self.install_button.add_css_class("destructive-action");
+        } else {
+            self.install_button.remove_css_class("destructive-action");
+            self.install_button.add_css_class("suggested-action");
+        }
+    }
+
+    pub fn populate(&self, project: &ModProject, versions: &[ModVersion], sender: &ComponentSender<AppModel>) {
+        self.install_button.set_widget_name(&project.id);
+        self.title_label.set_text(&project.title);
+        self.summary_label.set_text(&format!("{} • {} downloads • {} followers", project.description, project.downloads, project.followers));
+        let markup = markdown_to_markup(&project.body);
+        if gtk::pango::parse_markup(&markup, '\0').is_ok() {
+            self.body_label.set_markup(&markup);
+        } else {
+            self.body_label.set_text(&project.body);
+        }
+
+        while let Some(child) = self.gallery_box.first_child() { self.gallery_box.remove(&child); }
+        for image in &project.gallery {
+            let picture = gtk::Picture::builder()
+                .width_request(200)
+                .height_request(120)
+                .can_shrink(true)
+                .build();
+            if let Some(title) = &image.title {
+                picture.set_tooltip_text(Some(title));
+            }
+            self.gallery_box.append(&picture);
+        }
+        self.gallery_box.set_visible(!project.gallery.is_empty());
+
+        while let Some(child) = self.versions_list.first_child() { self.versions_list.remove(&child); }
+        for version in versions.iter().take(15) {
+            let row = adw::ActionRow::builder()
+                .title(&version.version_number)
+                .subtitle(format!("{} • {}", version.loaders.join(", "), version.game_versions.join(", ")))
+                .subtitle_lines(1)
+                .build();
+            self.versions_list.append(&row);
+        }
+        if versions.is_empty() {
+            self.versions_list.append(&adw::ActionRow::builder().title("No versions for this profile").build());
+        }
+
+        while let Some(child) = self.dependencies_list.first_child() { self.dependencies_list.remove(&child); }
+        let dependencies: Vec<_> = versions.first()
+            .map(|v| v.dependencies.iter().filter(|d| d.project_id.is_some()).collect())
+            .unwrap_or_default();
+        for dep in &dependencies {
+            let dep_id = dep.project_id.clone().unwrap_or_default();
+            let row = adw::ActionRow::builder()
+                .title(&dep_id)
+                .subtitle(&dep.dependency_type)
+                .build();
+
+            let open_button = gtk::Button::builder()
+                .icon_name("go-next-symbolic")
+                .tooltip_text("Details")
+                .valign(gtk::Align::Center)
+                .build();
+            let sender_clone = sender.clone();
+            open_button.connect_clicked(move |_| {
+                sender_clone.input(AppMsg::ShowModDetails(dep_id.clone()));
+            });
+            row.add_suffix(&open_button);
+            self.dependencies_list.append(&row);
+        }
+        if dependencies.is_empty() {
+            self.dependencies_list.append(&adw::ActionRow::builder().title("No dependencies").build());
+        }
+
+        self.content_stack.set_visible_child_name("content");
+    }
+
+    pub fn set_gallery_image(&self, index: usize, path: &str) {
+        let mut child = self.gallery_box.first_child();
+        let mut i = 0;
+        while let Some(c) = child {
+            if i == index {
+                if let Some(picture) = c.downcast_ref::<gtk::FlowBoxChild>().and_then(|f| f.child()).and_downcast::<gtk::Picture>() {
+                    picture.set_filename(Some(path));
+                }
+                return;
+            }
+            i += 1;
+            child = c.next_sibling();
+        }
+    }
+}
+
+// Very small markdown subset (headings, lists, bold, links) to Pango markup.
+// Modrinth bodies also contain raw HTML, which is simply stripped.
+fn markdown_to_markup(markdown: &str) -> String {
+    let mut out = String::new();
+
+    for raw_line in strip_html(markdown).lines() {
+        let line = raw_line.trim();
+        if line.starts_with("![") {
+            continue; // Images are shown in the gallery instead
+        }
+
+        if line.starts_with('#') {
+            let text = line.trim_start_matches('#').trim();
+            out.push_str(&format!("<b><big>{}</big></b>\n", inline_markup(text)));
+        } else if let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) {
+            out.push_str(&format!("  • {}\n", inline_markup(item)));
+        } else {
+            out.push_str(&inline_markup(line));
+            out.push('\n');
+        }
+    }
+
+    out.trim().to_string()
+}
+
+fn strip_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn inline_markup(text: &str) -> String {
+    let escaped = gtk::glib::markup_escape_text(text).to_string();
+    let mut out = String::new();
+    let mut rest = escaped.as_str();
+    let mut bold = false;
+
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**") {
+            out.push_str(if bold { "</b>" } else { "<b>" });
+            bold = !bold;
+            rest = after;
+        } else if rest.starts_with('[') {
+            // [text](url)
+            let link = rest.find("](").and_then(|mid| {
+                rest[mid..].find(')').map(|end| (mid, mid + end))
+            });
+            if let Some((mid, end)) = link {
+                let label = &rest[1..mid];
+                let url = &rest[mid + 2..end];
+                out.push_str(&format!("<a href=\"{}\">{}</a>", url, label));
+                rest = &rest[end + 1..];
+            } else {
+                out.push('[');
+                rest = &rest[1..];
+            }
+        } else {
+            let c = rest.chars().next().unwrap_or_default();
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    if bold {
+        out.push_str("</b>");
+    }
+    out
+}
@@ -1,19 +1,46 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use relm4::{ComponentSender, gtk};
 use adw::prelude::*;
-use crate::models::{MinecraftVersion, Profile, Section, ModSearchResult};
+use crate::models::{Account, Loader, MinecraftVersion, Profile, Section, ModSearchResult, ModVersion, ProjectType};
 use crate::settings::Settings;
 use crate::launcher::MinecraftLauncher;
 use crate::modrinth_client::ModrinthClient;
+use crate::ui::mod_detail::ModDetailView;
 
 #[derive(Debug, Clone)]
 pub enum AppState {
     Loading,
     Ready { current_section: Section },
-    Downloading { version: String, progress: f64, status: String },
+    Downloading { title: String, version: String, progress: f64, status: String },
     Launching { version: String },
     GameRunning { #[allow(dead_code)] version: String },
-    Error { message: String },
+    Error { message: String, retry: Option<RetryAction> },
+}
+
+// Failed work the Error screen can re-dispatch with its Retry button
+#[derive(Debug, Clone)]
+pub enum RetryAction {
+    LaunchProfile(String), // profile name
+    InstallJavaAndLaunch(String), // profile name
+}
+
+// A modpack install or update waiting to be applied, possibly on the user's answer about conflicts
+#[derive(Debug, Clone)]
+pub struct ModpackJob {
+    pub pack_path: std::path::PathBuf,
+    pub index: crate::modpack::PackIndex,
+    pub plan: crate::modpack::PackPlan,
+    pub game_dir: std::path::PathBuf,
+    // Profile that already has this pack, None installs a new profile
+    pub profile_name: Option<String>,
+}
+
+// Startup data that arrives independently, so one slow or failed fetch doesn't hold up the rest
+#[derive(Debug, Clone, PartialEq)]
+pub enum LoadStatus {
+    Pending,
+    Loaded,
+    Failed(String),
 }
 
 impl Default for AppState {
@@ -30,39 +57,90 @@ pub struct AppModel {
 
     // Data
     pub profiles: HashMap<String, Profile>,
+    // Signed in Microsoft accounts, from accounts.json
+    pub accounts: Vec<Account>,
     pub available_versions: Vec<MinecraftVersion>,
     pub sorted_versions: Vec<String>,
+    // What the version selector currently lists, sorted_versions narrowed by the filters below
+    pub shown_versions: Vec<String>,
+    pub installed_versions: HashSet<String>,
+    pub version_filter_installed: bool,
+    pub version_filter_favorites: bool,
 
     // Inputs
     pub input_username: String,
     pub input_version: Option<String>,
     pub input_ram: u32,
-    pub input_install_fabric: bool,
-    pub fabric_switch_enabled: bool,
+    pub input_loader: Loader,
+    // Custom game directory, None for the isolated per-profile instance
+    pub input_game_dir: Option<String>,
+    pub input_shared_mod_dirs: Vec<String>,
+    pub input_notes: String,
+    // uuid of the account the profile plays as, None plays offline
+    pub input_account: Option<String>,
+    // Space separated, split into Profile::jvm_args on save
+    pub input_jvm_args: String,
+    // Key of the profile loaded into the form, None when creating
+    pub editing_profile: Option<String>,
+    // Whether the chosen loader has builds for the chosen version, always true for vanilla
+    pub loader_available: bool,
+    // Waiting on the loader's meta for the selected version
+    pub loader_checking: bool,
+    // Loader meta answers per game version, so switching back and forth doesn't re-query
+    pub loader_support: HashMap<(String, Loader), bool>,
 
     // Settings & Logs
     pub settings: Settings,
     pub logs: gtk::TextBuffer,
+    pub launcher_logs: gtk::TextBuffer,
+    // Game output as received (ANSI stripped), without the timestamps shown in `logs`
+    pub raw_logs: VecDeque<String>,
 
     // UI State
     pub error_message: Option<String>,
 
     pub sidebar_collapsed: bool,
+    pub settings_loaded: bool,
 
     pub versions_updated: bool,
+    pub versions_status: LoadStatus,
+    pub profiles_status: LoadStatus,
+    pub pending_modpack: Option<ModpackJob>,
+    // Profile the popular mods were last loaded for
+    pub popular_mods_profile: Option<String>,
     pub version_list_model: Option<gtk::StringList>,
     pub is_searching: bool,
 
     // Mods UI State
     pub mod_search_results: Vec<ModSearchResult>,
+    pub mod_search_query: String,
     pub mod_search_entry: Option<gtk::SearchEntry>,
     pub mod_browse_list: Option<gtk::ListBox>,
+    // Browse results came from the on-disk cache because Modrinth couldn't be reached
+    pub mod_results_stale: bool,
     pub mod_installed_list: Option<gtk::ListBox>,
+    // What the Mods page browses and installs
+    pub mod_project_type: ProjectType,
+    pub mod_check_updates_button: Option<gtk::Button>,
+    // Newer versions found by the last update check: Filename -> version
+    pub mod_updates: HashMap<String, ModVersion>,
+    // Filenames whose update is downloading
+    pub updating_mods: HashSet<String>,
     pub selected_mod_profile: Option<String>,
     pub mod_profile_list_model: Option<gtk::StringList>,
+    // Profile ids in mod_profile_list_model order
+    pub mod_profile_ids: Vec<String>,
+    pub mod_page_stack: Option<gtk::Stack>,
+    pub mod_detail: Option<ModDetailView>,
 
     // Track installed mods: ProjectID -> Filename
     pub installed_mods: HashMap<String, String>,
+    // Downloads in flight, so a second click can't queue a duplicate
+    pub installing_mods: HashSet<String>,
+    // Download fraction of installing mods, once the size is known
+    pub mod_download_progress: HashMap<String, f64>,
+    // Install/uninstall button of each visible search row: ProjectID -> Button
+    pub mod_action_buttons: HashMap<String, gtk::Button>,
 
     pub toast_overlay: Option<adw::ToastOverlay>,
 
@@ -73,55 +151,87 @@ pub struct AppModel {
     // Selection Sync
     pub pending_mod_selection: Option<u32>,
     pub pending_launch_profile: Option<String>,
+    // Profile whose next launch skips signing in, after the user chose to play offline
+    pub offline_launch: Option<String>,
     pub mod_profile_list_updated: bool,
 
     // Component sender for UI updates
     pub sender: ComponentSender<AppModel>,
 
     pub java_dialog_request: Option<u32>,
+    // Managed runtimes shown in Settings, `latest_build` filled by the last update check
+    pub java_runtimes: Vec<crate::java_manager::RuntimeStatus>,
+    // Bytes on disk per profile instance, filled in lazily by ProfileSizeComputed
+    pub profile_sizes: HashMap<String, u64>,
+    pub profile_sizes_requested: HashSet<String>,
+    pub java_updates_checking: bool,
+    pub java_runtimes_list: Option<gtk::ListBox>,
+    // Settings' installed versions manager, reloaded whenever Settings is opened
+    pub downloaded_versions: Vec<crate::launcher::InstalledVersion>,
+    pub installed_versions_list: Option<gtk::ListBox>,
+    pub accounts_list: Option<gtk::ListBox>,
+    // Device code sign in from requesting the code until it's entered, aborted by cancelling its dialog
+    pub sign_in_task: Option<tokio::task::AbortHandle>,
+
+    // Long running download/launch shown on the loading page
+    pub operation_started: Option<std::time::Instant>,
+    pub last_progress_at: Option<std::time::Instant>,
+    pub operation_timer: Option<gtk::glib::SourceId>,
+    pub current_operation: Option<tokio::task::AbortHandle>,
+    // Java downloads stop through this instead of being aborted, so they can clean up after themselves
+    pub java_download_cancel: Option<std::sync::Arc<std::sync::atomic::AtomicBool>>,
+    // java binary of the game that's running, its runtime can't be replaced under it
+    pub running_java: Option<std::path::PathBuf>,
+    // Jar -> (its mtime, the icon extracted from it) for the Installed list
+    pub installed_mod_icons: HashMap<std::path::PathBuf, (Option<std::time::SystemTime>, Option<std::path::PathBuf>)>,
 
     // Shared Tokio Runtime
     pub rt: std::sync::Arc<tokio::runtime::Runtime>,
+
+    // Bumped on every settings change, a pending save only writes if it's still the latest
+    pub settings_save_generation: std::sync::Arc<std::sync::atomic::AtomicU64>,
+    // Settings waiting out the debounce, written straight away if the window closes first
+    pub pending_settings_save: std::sync::Arc<std::sync::Mutex<Option<(std::path::PathBuf, Settings)>>>,
 }
 
 impl AppModel {
      // Helper to update button state based on installation status
     pub fn update_mod_button_state(&self, project_id: &str) {
-         if let Some(list) = &self.mod_browse_list {
-             let is_installed = self.installed_mods.contains_key(project_id);
-             let icon_name = if is_installed { "user-trash-symbolic" } else { "folder-download-symbolic" };
-             let tooltip = if is_installed { "Uninstall" } else { "Install" };
-             let sensitive = true;
-
-             let mut sibling = list.first_child();
-             while let Some(child) = sibling {
-                   if let Some(row) = child.downcast_ref::<gtk::ListBoxRow>() {
-                        if let Some(box_widget) = row.child() {
-                             if let Some(bx) = box_widget.downcast_ref::<gtk::Box>() {
-                                  let mut box_child = bx.first_child();
-                                  while let Some(b_child) = box_child {
-                                       if let Some(button) = b_child.downcast_ref::<gtk::Button>() {
-                                            if button.widget_name() == format!("btn_{}", project_id) {
-                                                 button.set_icon_name(icon_name);
-                                                 button.set_tooltip_text(Some(tooltip));
-                                                 button.set_sensitive(sensitive);
-
-                                                 // Update CSS class?
-                                                 if is_installed {
-                                                     button.add_css_class("destructive-action");
-                                                 } else {
-                                                     button.remove_css_class("destructive-action");
-                                                 }
-                                                 break;
-                                            }
-                                       }
-                                       box_child = b_child.next_sibling();
-                                  }
-                             }
-                        }
-                   }
-                   sibling = child.next_sibling();
+         if let Some(detail) = self.mod_detail.as_ref().filter(|d| d.current_project() == project_id) {
+             detail.set_install_state(self.installing_mods.contains(project_id), self.installed_mods.contains_key(project_id));
+         }
+         let Some(button) = self.mod_action_buttons.get(project_id) else { return };
+
+         if self.installing_mods.contains(project_id) {
+             match self.mod_download_progress.get(project_id) {
+                 Some(fraction) => {
+                     let percent = format!("{}%", (fraction * 100.0).round() as u32);
+                     let label = gtk::Label::builder().label(&percent).css_classes(vec!["numeric", "caption"]).build();
+                     button.set_child(Some(&label));
+                     button.set_tooltip_text(Some(&format!("Downloading... {}", percent)));
+                 }
+                 None => {
+                     let spinner = gtk::Spinner::builder().spinning(true).build();
+                     button.set_child(Some(&spinner));
+                     button.set_tooltip_text(Some("Installing..."));
+                 }
              }
+             button.set_sensitive(false);
+             return;
+         }
+
+         let is_installed = self.installed_mods.contains_key(project_id);
+         let icon_name = if is_installed { "user-trash-symbolic" } else { "folder-download-symbolic" };
+         let tooltip = if is_installed { "Uninstall" } else { "Install" };
+
+         button.set_icon_name(icon_name);
+         button.set_tooltip_text(Some(tooltip));
+         button.set_sensitive(true);
+
+         if is_installed {
+             button.add_css_class("destructive-action");
+         } else {
+             button.remove_css_class("destructive-action");
          }
     }
 }
@@ -1,11 +1,13 @@
 use relm4::gtk;
 use relm4::{ComponentSender, RelmWidgetExt};
 use gtk::prelude::*;
+use adw::prelude::*;
 use crate::ui::model::AppModel;
 use crate::ui::msg::AppMsg;
-use crate::models::ModSearchResult;
+use crate::models::{is_server_only, side_support_label, ModSearchResult, ProjectType};
+use crate::mod_presets::PRESETS;
 
-pub fn create_mods_page(sender: &ComponentSender<AppModel>) -> (gtk::Box, gtk::SearchEntry, gtk::Button, gtk::Stack, gtk::ListBox, gtk::ListBox, gtk::DropDown) {
+pub fn create_mods_page(sender: &ComponentSender<AppModel>, stale_banner: &adw::Banner) -> (gtk::Box, gtk::SearchEntry, gtk::Button, gtk::Stack, gtk::ListBox, gtk::ListBox, gtk::DropDown, gtk::Stack, gtk::Button) {
     let container = gtk::Box::new(gtk::Orientation::Vertical, 24);
     container.set_margin_all(24);
     container.set_vexpand(true);
@@ -39,17 +41,15 @@ pub fn create_mods_page(sender: &ComponentSender<AppModel>) -> (gtk::Box, gtk::S
 
     let sender_clone = sender.clone();
     profile_dropdown.connect_selected_item_notify(move |dropdown| {
-        if let Some(item) = dropdown.selected_item() {
-            if let Some(string_obj) = item.downcast_ref::<gtk::StringObject>() {
-                let full_string = string_obj.string().to_string();
-                if let Some((name, version)) = full_string.rsplit_once(" - ") {
-                     let key = format!("{}_{}_fabric", name, version);
-                     sender_clone.input(AppMsg::SelectModProfile(key));
-                }
-            }
+        if dropdown.selected_item().is_some() {
+            sender_clone.input(AppMsg::SelectModProfileAt(dropdown.selected()));
         }
     });
 
+    let type_names: Vec<&str> = ProjectType::ALL.iter().map(|t| t.name()).collect();
+    let type_dropdown = gtk::DropDown::from_strings(&type_names);
+    type_dropdown.set_tooltip_text(Some("What to browse and install"));
+
     let installed_btn_content = gtk::Box::new(gtk::Orientation::Horizontal, 6);
     installed_btn_content.append(&gtk::Image::from_icon_name("folder-download-symbolic"));
     installed_btn_content.append(&gtk::Label::new(Some("Installed")));
@@ -84,6 +84,7 @@ pub fn create_mods_page(sender: &ComponentSender<AppModel>) -> (gtk::Box, gtk::S
     });
 
     top_bar.append(&profile_dropdown);
+    top_bar.append(&type_dropdown);
     top_bar.append(&installed_button);
     top_bar.append(&browse_button);
 
@@ -102,8 +103,17 @@ pub fn create_mods_page(sender: &ComponentSender<AppModel>) -> (gtk::Box, gtk::S
     installed_scroll.set_halign(gtk::Align::Fill);
     installed_scroll.set_child(Some(&installed_list));
 
-    // Toolbar removed as per request
+    let check_updates_button = gtk::Button::builder()
+        .label("Check Updates")
+        .halign(gtk::Align::End)
+        .tooltip_text("Look for newer versions of the installed mods on Modrinth")
+        .build();
+    let sender_clone = sender.clone();
+    check_updates_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::CheckModUpdates);
+    });
 
+    installed_box.append(&check_updates_button);
     installed_box.append(&installed_scroll);
 
     stack.add_named(&installed_box, Some("installed"));
@@ -142,7 +152,49 @@ pub fn create_mods_page(sender: &ComponentSender<AppModel>) -> (gtk::Box, gtk::S
 
     let browse_list = gtk::ListBox::new();
     browse_list.add_css_class("boxed-list");
-    browse_list.set_selection_mode(gtk::SelectionMode::None);
+    // Selectable so the results can be walked with the arrow keys, Enter installs the selected row.
+    // Single click only selects, otherwise every click on a row would start a download.
+    browse_list.set_selection_mode(gtk::SelectionMode::Single);
+    browse_list.set_activate_on_single_click(false);
+
+    let sender_clone = sender.clone();
+    browse_list.connect_row_activated(move |_, row| {
+        let project_id = row.widget_name().to_string();
+        if !project_id.is_empty() {
+            sender_clone.input(AppMsg::ModActionButtonClicked(project_id));
+        }
+    });
+
+    // Down from the search entry jumps into the results
+    let browse_list_clone = browse_list.clone();
+    let key_controller = gtk::EventControllerKey::new();
+    key_controller.connect_key_pressed(move |_, key, _, _| {
+        match (key, browse_list_clone.row_at_index(0)) {
+            (gtk::gdk::Key::Down, Some(row)) => {
+                browse_list_clone.select_row(Some(&row));
+                row.grab_focus();
+                gtk::glib::Propagation::Stop
+            }
+            _ => gtk::glib::Propagation::Proceed,
+        }
+    });
+    search_bar.add_controller(key_controller);
+
+    // Ctrl+I installs the selected row from anywhere on the page (e.g. while still in the search entry)
+    let shortcuts = gtk::ShortcutController::new();
+    shortcuts.set_scope(gtk::ShortcutScope::Managed);
+    let sender_clone = sender.clone();
+    let browse_list_clone = browse_list.clone();
+    shortcuts.add_shortcut(gtk::Shortcut::new(
+        gtk::ShortcutTrigger::parse_string("<Control>i"),
+        Some(gtk::CallbackAction::new(move |_, _| {
+            if let Some(row) = browse_list_clone.selected_row() {
+                sender_clone.input(AppMsg::ModActionButtonClicked(row.widget_name().to_string()));
+            }
+            gtk::glib::Propagation::Stop
+        })),
+    ));
+    container.add_controller(shortcuts);
 
     let browse_scroll = gtk::ScrolledWindow::new();
     browse_scroll.set_vexpand(true);
@@ -150,7 +202,61 @@ pub fn create_mods_page(sender: &ComponentSender<AppModel>) -> (gtk::Box, gtk::S
     browse_scroll.set_halign(gtk::Align::Fill);
     browse_scroll.set_child(Some(&browse_list));
 
+    // Quick install presets
+    let presets_list = gtk::ListBox::new();
+    presets_list.add_css_class("boxed-list");
+    presets_list.set_selection_mode(gtk::SelectionMode::None);
+
+    // Known exact mod, skip the search
+    let slug_row = adw::EntryRow::builder()
+        .title("Install from Modrinth URL or slug")
+        .show_apply_button(true)
+        .build();
+
+    let sender_clone = sender.clone();
+    slug_row.connect_apply(move |row| {
+        sender_clone.input(AppMsg::InstallModBySlug(row.text().to_string()));
+        row.set_text("");
+    });
+    presets_list.append(&slug_row);
+
+    let mut preset_rows = Vec::new();
+    for preset in PRESETS {
+        let row = adw::ActionRow::builder()
+            .title(format!("Quick install: {}", preset.name))
+            .subtitle(preset.description)
+            .build();
+
+        let install_button = gtk::Button::builder()
+            .label("Install")
+            .valign(gtk::Align::Center)
+            .css_classes(vec!["suggested-action"])
+            .build();
+
+        let sender_clone = sender.clone();
+        let preset_id = preset.id.to_string();
+        install_button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::InstallModPreset(preset_id.clone()));
+        });
+
+        row.add_suffix(&install_button);
+        presets_list.append(&row);
+        preset_rows.push(row);
+    }
+
+    // Presets are all mod packs
+    let sender_clone = sender.clone();
+    type_dropdown.connect_selected_notify(move |dropdown| {
+        let Some(&project_type) = ProjectType::ALL.get(dropdown.selected() as usize) else { return };
+        for row in &preset_rows {
+            row.set_visible(project_type == ProjectType::Mod);
+        }
+        sender_clone.input(AppMsg::SelectProjectType(project_type));
+    });
+
+    browse_box.append(&presets_list);
     browse_box.append(&search_box);
+    browse_box.append(stale_banner);
     browse_box.append(&browse_scroll);
 
     stack.add_named(&browse_box, Some("browse"));
@@ -160,11 +266,12 @@ pub fn create_mods_page(sender: &ComponentSender<AppModel>) -> (gtk::Box, gtk::S
 
     stack.set_visible_child_name("installed");
 
-    (container, search_bar, search_button, search_stack, installed_list, browse_list, profile_dropdown)
+    (container, search_bar, search_button, search_stack, installed_list, browse_list, profile_dropdown, stack, check_updates_button)
 }
 
-pub fn create_mod_search_result_row(mod_data: &ModSearchResult, sender: &ComponentSender<AppModel>) -> gtk::ListBoxRow {
+pub fn create_mod_search_result_row(mod_data: &ModSearchResult, sender: &ComponentSender<AppModel>) -> (gtk::ListBoxRow, gtk::Button) {
     let row = gtk::ListBoxRow::new();
+    row.set_widget_name(&mod_data.project_id);
     let box_container = gtk::Box::new(gtk::Orientation::Horizontal, 12);
     box_container.set_margin_all(12);
 
@@ -189,7 +296,22 @@ pub fn create_mod_search_result_row(mod_data: &ModSearchResult, sender: &Compone
         .lines(2)
         .build();
 
-    info_box.append(&title);
+    let title_box = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+    title_box.append(&title);
+    if let Some(side) = side_support_label(mod_data.client_side.as_deref(), mod_data.server_side.as_deref()) {
+        let badge = gtk::Label::builder()
+            .label(side)
+            .valign(gtk::Align::Center)
+            .css_classes(vec!["caption", "dim-label"])
+            .build();
+        if is_server_only(mod_data.client_side.as_deref()) {
+            badge.add_css_class("warning");
+            badge.set_tooltip_text(Some("Runs on servers only, installing it in a client profile does nothing"));
+        }
+        title_box.append(&badge);
+    }
+
+    info_box.append(&title_box);
     info_box.append(&description);
 
     let download_button = gtk::Button::builder()
@@ -217,15 +339,28 @@ pub fn create_mod_search_result_row(mod_data: &ModSearchResult, sender: &Compone
         sender_clone_2.input(AppMsg::OpenModrinthPage(project_id_clone_2.clone()));
     });
 
+    let details_button = gtk::Button::builder()
+        .icon_name("help-about-symbolic")
+        .tooltip_text("Details")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let project_id_clone_3 = mod_data.project_id.clone();
+    let sender_clone_3 = sender.clone();
+    details_button.connect_clicked(move |_| {
+        sender_clone_3.input(AppMsg::ShowModDetails(project_id_clone_3.clone()));
+    });
+
     box_container.append(&icon);
     box_container.append(&info_box);
 
     let spacer = gtk::Box::new(gtk::Orientation::Horizontal, 0);
     spacer.set_hexpand(true);
     box_container.append(&spacer);
+    box_container.append(&details_button);
     box_container.append(&view_button);
     box_container.append(&download_button);
 
     row.set_child(Some(&box_container));
-    row
+    (row, download_button)
 }
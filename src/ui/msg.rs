@@ -1,61 +1,190 @@
 use std::collections::HashMap;
-use crate::models::{MinecraftVersion, Profile, Section, Theme, ModSearchResult};
+use crate::models::{Account, JavaVendor, Loader, LogLevel, MinecraftVersion, Profile, ProfileSort, Section, Theme, ModProject, ModSearchResult, ModVersion, ProjectType};
 use crate::settings::Settings;
+use crate::mod_index::InstalledMod;
+use crate::ui::model::{ModpackJob, RetryAction};
+use crate::modrinth_client::{Fetched, ModInstallOutcome, ModUpdateCheck};
+use crate::java_manager::RuntimeStatus;
 
 #[derive(Debug)]
 pub enum AppMsg {
     LaunchProfile(String),
+    CopyLaunchCommand(String), // profile name
+    RepairNatives(String), // game version, natives are shared by every profile on it
+    RefreshInstalledVersions,
+    InstalledVersionsLoaded(Vec<crate::launcher::InstalledVersion>),
+    RequestDeleteVersion(String), // version id, asks first
+    DeleteVersion(String),
+    VersionDeleted(String, Result<(), String>),
+    VerifyVersion(String, bool), // version id, whether to delete and download it again first
+    VersionVerified(String, bool, Result<(), String>),
+    NativesRepaired(String, Result<(), String>),
+    AccountsLoaded(Vec<Account>),
+    AddAccount,
+    DeviceCodeReceived(Result<crate::auth::DeviceCode, String>),
+    CancelSignIn,
+    AccountSignedIn(Result<Account, String>),
+    AccountRefreshed(Account), // new token from a launch, persisted
+    RemoveAccount(String), // account uuid
+    AccountSelected(u32), // position in the create form's account row, 0 is offline
+    LaunchCommandResolved(Result<String, String>),
     DeleteProfile(String),
+    EditProfile(String), // profile name
+    CancelEditProfile,
     UsernameChanged(String),
+    JvmArgsChanged(String),
     VersionSelected(String),
+    ToggleInstalledVersionsFilter(bool),
+    ToggleFavoriteVersionsFilter(bool),
+    SetVersionFavorite(bool), // for the selected version
+    LoaderAvailabilityChecked(String, Loader, Result<bool, String>), // game version, loader, whether it has builds for it
     RamChanged(u32),
-    ToggleFabric(bool),
+    NotesChanged(String),
+    LoaderSelected(Loader),
+    ChooseGameDir,
+    GameDirSelected(Option<String>), // None resets to the per-profile instance dir
+    ChooseSharedModDir,
+    SharedModDirAdded(String),
+    ClearSharedModDirs,
+    ChooseDataDir,
+    DataDirSelected(Option<String>), // None resets to ~/.minecraft
+    ChooseInstancesDir,
+    InstancesDirSelected(Option<String>), // None resets to .minecraft/instances
+    ChooseOptionsTemplate,
+    OptionsTemplateSelected(Option<String>), // None stops seeding options.txt
     SaveProfile,
+    ChooseModpack,
+    InstallModpack(std::path::PathBuf),
+    DownloadModpack(String), // Modrinth slug, its newest .mrpack is then installed
+    ModpackPlanned(Result<ModpackJob, String>),
+    ApplyModpack(bool), // overwrite files the user changed
+    CancelModpack,
+    ModpackInstalled(Result<ModpackJob, String>),
+    FileDropped(std::path::PathBuf), // onto the window, a modpack, profile bundle or mod jar
+    AddModFile(std::path::PathBuf), // a jar into the Mods page's profile
+    ModFileAdded(std::path::PathBuf, String, Result<(), String>), // mods dir, file name
+    ImportProfiles(std::path::PathBuf),
+    ProfilesImported(Vec<Profile>),
     // CancelCreate removed
     VersionsLoaded(Result<Vec<MinecraftVersion>, String>),
     ProfilesLoaded(Result<HashMap<String, Profile>, String>),
     // DownloadCompleted removed
     // DownloadStarted(String) removed
     DownloadProgress(f64, String),
+    InstallerOutput(String, Option<String>), // loader ("Fabric"), line printed by its installer or None when done
+    OperationTick,
+    CancelOperation,
+    JavaUsed(String, String, Option<u32>), // profile id, java binary, its major version
     GameStarted,
     LaunchCompleted,
     NavigateToSection(Section),
     BackToMainMenu,
     // UpdateDownloadDots removed
     OpenMinecraftFolder,
-    // ShowAboutWindow removed
+    ShowAboutWindow,
+    CheckForUpdates,
+    UpdateCheckFinished(Result<Option<String>, String>), // Newer version, if any
     ThemeSelected(Theme),
+    ToggleTransparent(bool),
+    JavaVendorSelected(JavaVendor),
+    LogLevelSelected(LogLevel),
     ToggleHideLogs(bool),
+    ToggleColorizeLogs(bool),
+    TogglePreferStableMods(bool),
+    ProfileSortChanged(ProfileSort),
+    DefaultRamChanged(u32), // MB
+    RamPercentChanged(Option<u32>), // None uses the default
+    ToggleLogTimestamps(bool),
+    DefaultUsernameChanged(String),
+    MaxRetriesChanged(u32),
+    MaxBackoffChanged(u64),
+    DownloadConcurrencyChanged(usize),
+    CopyLogs,
+    SaveLogs,
+    ClearLogs,
+    MaxLogLinesChanged(usize),
+    WriteLogs(std::path::PathBuf), // chosen in the save dialog
+    ToggleVerifyFiles(bool),
+    ToggleCheckSystemLibraries(bool),
+    ToggleMinimizeOnLaunch(bool),
+    ToggleQuitOnLaunch(bool),
+    MinimizeWindow,
+    CloseWindow,
     ToggleHideMods(bool),
     ToggleSidebar,
     Log(String),
+    LauncherLog(String), // RCraft's own log, see logging.rs
 
 
     Error(String),
+    ErrorWithRetry(String, RetryAction),
+    SignInFailed(String, String, String), // profile name, account username, error
+    LaunchProfileOffline(String), // profile name, plays as its account's username without signing in
+    RetryFailedAction,
+    ExportDiagnostics,
+    ClearCaches,
+    CachesCleared(Result<u64, String>), // bytes freed
+    FindUnusedLibraries,
+    UnusedLibrariesFound(Result<Vec<(std::path::PathBuf, u64)>, String>), // jars and their sizes
+    RemoveUnusedLibraries(Vec<(std::path::PathBuf, u64)>),
+    UnusedLibrariesRemoved(u64), // bytes freed
     RequestDeleteProfile(String),
+    RequestMigrateProfile(String), // profile id, asks for the version to copy it to
+    MigrateProfile(String, String), // profile id, target game version
+    ProfileMigrated(Result<(Profile, Vec<String>), String>), // new profile, mods left behind
     SettingsLoaded(Settings),
-    SessionEnded(String, u64),
+    SessionEnded(String, u64, Option<std::process::ExitStatus>), // profile id, seconds played, how the game exited
+    OpenSessionLog(String), // profile id
+    OpenLogsFolder, // of the profile launched last
+    ProfileSizeComputed(String, u64), // profile id, bytes
     // ColorsLoaded removed
     RefreshInstalledMods,
-    SelectModProfile(String),
+    SelectModProfile(String), // profile id
+    SelectModProfileAt(u32), // position in the Mods page dropdown
     // Modrinth Messages
+    SelectProjectType(ProjectType),
     SearchMods(String),
-    ModsSearched(Result<Vec<ModSearchResult>, String>),
+    LoadPopularMods,
+    ModsSearched(Result<Fetched<Vec<ModSearchResult>>, String>),
+    ModsSearchIncompatible(String), // Profile version nothing matched, but other versions did
     InstallMod(String), // Project ID
+    InstallModBySlug(String), // Modrinth URL or slug as typed
+    ConfirmServerOnlyInstall(String, String), // project_id, title
+    InstallModPreset(String), // Preset id, see mod_presets
+    ModPresetInstalled(String, std::path::PathBuf, ModInstallOutcome), // Preset id, mods dir, outcome
+    ModInstalled(String, std::path::PathBuf, ModInstallOutcome), // project_id, install dir, outcome including its dependencies
     UninstallMod(String), // Filename
+    ToggleModEnabled(std::path::PathBuf, String), // Install dir, filename as on disk, renamed to or from .jar.disabled
+    CheckModUpdates,
+    ModUpdatesChecked(String, std::path::PathBuf, Result<ModUpdateCheck, String>), // profile id, install dir, outcome
+    UpdateMod(String), // Filename
+    ModUpdated(String, std::path::PathBuf, Result<(String, InstalledMod), String>), // old filename, install dir, project_id and its new index entry
     DownloadModIcon(String, String), // Project ID, URL
     ModIconDownloaded(String, String), // project_id, path
     ProcessIconQueue,
     ModActionButtonClicked(String), // project_id (Toggle Install/Uninstall)
+    ModDownloadProgress(String, f64), // project_id, fraction downloaded
     ModInstallFinished(String, ()), // project_id, success (bool unused)
     ModUninstallFinished(String), // project_id
-    RegisterInstalledMod(String, String), // project_id, filename
+    RegisterInstalledMod(std::path::PathBuf, String, InstalledMod), // Install dir the entry belongs to, project_id, index entry
+    ConfirmRemoveOrphanedMods(std::path::PathBuf, Vec<String>), // Install dir, project_ids of unused auto-installed deps
+    RemoveOrphanedMods(std::path::PathBuf, Vec<String>), // Install dir, project_ids
     ShowToast(String),
     ClearPendingSelection,
     ModDropdownUpdated,
     OpenModrinthPage(String),
+    ShowModDetails(String), // project_id
+    ModDetailsLoaded(String, Result<(ModProject, Vec<ModVersion>), String>), // requested project_id
+    ModGalleryImageLoaded(String, usize, String), // project_id, gallery index, path
+    CloseModDetails,
     ShowJavaDialog(u32),
     JavaDownloadConfirmed,
     JavaDownloadCancelled,
     InstallJavaAndLaunch,
+    CheckJavaUpdates,
+    JavaUpdatesChecked(Vec<RuntimeStatus>),
+    UpdateJavaRuntime(u32, JavaVendor), // feature version
+    DownloadJava(u32), // feature version, installed from settings with no launch after
+    JavaDownloaded(u32, JavaVendor, Result<(), String>),
+    JavaRuntimeUpdated(u32, JavaVendor, Result<(), String>),
 }
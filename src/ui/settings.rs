@@ -4,9 +4,16 @@ use gtk::prelude::*;
 use adw::prelude::*;
 use crate::ui::model::AppModel;
 use crate::ui::msg::AppMsg;
-use crate::models::Theme;
+use std::collections::HashMap;
+use crate::models::{Account, JavaVendor, LogLevel, Profile, Theme};
+use crate::java_manager::RuntimeStatus;
+use crate::launcher::InstalledVersion;
 
-pub fn create_settings_page(sender: &ComponentSender<AppModel>, hide_logs_switch: &adw::SwitchRow, hide_mods_switch: &adw::SwitchRow) -> (gtk::ScrolledWindow, adw::ComboRow) {
+// The Java versions Minecraft releases have required, offered for installing ahead of time
+const INSTALLABLE_JAVA_VERSIONS: [u32; 4] = [8, 17, 21, 25];
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_settings_page(sender: &ComponentSender<AppModel>, transparent_switch: &adw::SwitchRow, hide_logs_switch: &adw::SwitchRow, colorize_logs_switch: &adw::SwitchRow, log_timestamps_switch: &adw::SwitchRow, max_log_lines_row: &adw::SpinRow, hide_mods_switch: &adw::SwitchRow, stable_mods_switch: &adw::SwitchRow, minimize_on_launch_switch: &adw::SwitchRow, quit_on_launch_switch: &adw::SwitchRow, log_level_row: &adw::ComboRow, max_retries_row: &adw::SpinRow, max_backoff_row: &adw::SpinRow, download_concurrency_row: &adw::SpinRow, options_template_row: &adw::ActionRow, default_username_row: &adw::EntryRow, default_ram_row: &adw::SpinRow, ram_percent_row: &adw::SpinRow, installed_versions_list: &gtk::ListBox, java_runtimes_list: &gtk::ListBox, accounts_list: &gtk::ListBox) -> (gtk::ScrolledWindow, adw::ComboRow, adw::ComboRow, adw::SwitchRow, adw::ActionRow, adw::ActionRow, adw::SwitchRow) {
     let scrolled_window = gtk::ScrolledWindow::builder()
         .hexpand(true)
         .vexpand(true)
@@ -50,6 +57,35 @@ pub fn create_settings_page(sender: &ComponentSender<AppModel>, hide_logs_switch
         sender_clone.input(AppMsg::ToggleHideLogs(switch.is_active()));
     });
 
+    colorize_logs_switch.set_title("Colored Logs");
+    colorize_logs_switch.set_subtitle("Show colors from game output. When off, color codes are removed");
+    colorize_logs_switch.set_hexpand(true);
+    colorize_logs_switch.set_halign(gtk::Align::Fill);
+
+    let sender_clone = sender.clone();
+    colorize_logs_switch.connect_active_notify(move |switch| {
+        sender_clone.input(AppMsg::ToggleColorizeLogs(switch.is_active()));
+    });
+
+    log_timestamps_switch.set_title("Log Timestamps");
+    log_timestamps_switch.set_subtitle("Prefix each game log line with the time it was received");
+    log_timestamps_switch.set_hexpand(true);
+    log_timestamps_switch.set_halign(gtk::Align::Fill);
+
+    let sender_clone = sender.clone();
+    log_timestamps_switch.connect_active_notify(move |switch| {
+        sender_clone.input(AppMsg::ToggleLogTimestamps(switch.is_active()));
+    });
+
+    max_log_lines_row.set_title("Log Line Limit");
+    max_log_lines_row.set_subtitle("Lines kept per tab on the Logs page. The oldest go first, log files on disk are not affected");
+    max_log_lines_row.set_adjustment(Some(&gtk::Adjustment::new(crate::settings::DEFAULT_MAX_LOG_LINES as f64, 500.0, 100000.0, 500.0, 5000.0, 0.0)));
+
+    let sender_clone = sender.clone();
+    max_log_lines_row.connect_value_notify(move |row| {
+        sender_clone.input(AppMsg::MaxLogLinesChanged(row.value() as usize));
+    });
+
     // Hide Mods switch configuration
     let sender_clone = sender.clone();
     hide_mods_switch.connect_active_notify(move |switch| {
@@ -60,6 +96,36 @@ pub fn create_settings_page(sender: &ComponentSender<AppModel>, hide_logs_switch
     hide_mods_switch.set_title("Hide Mods");
     hide_mods_switch.set_subtitle("Hide the Mods button in the sidebar");
 
+    stable_mods_switch.set_title("Prefer Stable Mod Versions");
+    stable_mods_switch.set_subtitle("Install the newest release of a mod. Betas and alphas are only used when there's no release");
+    stable_mods_switch.set_hexpand(true);
+    stable_mods_switch.set_halign(gtk::Align::Fill);
+
+    let sender_clone = sender.clone();
+    stable_mods_switch.connect_active_notify(move |switch| {
+        sender_clone.input(AppMsg::TogglePreferStableMods(switch.is_active()));
+    });
+
+    // What happens to the launcher once the game window is up
+    minimize_on_launch_switch.set_title("Minimize on Game Start");
+    minimize_on_launch_switch.set_subtitle("Minimize the launcher once Minecraft is running");
+    minimize_on_launch_switch.set_hexpand(true);
+    minimize_on_launch_switch.set_halign(gtk::Align::Fill);
+
+    let sender_clone = sender.clone();
+    minimize_on_launch_switch.connect_active_notify(move |switch| {
+        sender_clone.input(AppMsg::ToggleMinimizeOnLaunch(switch.is_active()));
+    });
+
+    quit_on_launch_switch.set_title("Quit on Game Start");
+    quit_on_launch_switch.set_subtitle("Close the launcher once Minecraft is running. Game logs and play time are no longer recorded");
+    quit_on_launch_switch.set_hexpand(true);
+    quit_on_launch_switch.set_halign(gtk::Align::Fill);
+
+    let sender_clone = sender.clone();
+    quit_on_launch_switch.connect_active_notify(move |switch| {
+        sender_clone.input(AppMsg::ToggleQuitOnLaunch(switch.is_active()));
+    });
 
     // Theme selection
     let theme_row = adw::ComboRow::builder()
@@ -68,13 +134,12 @@ pub fn create_settings_page(sender: &ComponentSender<AppModel>, hide_logs_switch
         .halign(gtk::Align::Fill)
         .build();
 
-    let theme_model = gtk::StringList::new(&["System", "Light", "Dark", "Transparent"]);
+    let theme_model = gtk::StringList::new(&["System", "Light", "Dark"]);
     theme_row.set_model(Some(&theme_model));
 
     let sender_clone = sender.clone();
     theme_row.connect_notify(Some("selected"), move |combo, _| {
         let theme = match combo.selected() {
-            3 => Theme::Transparent,
             2 => Theme::Dark,
             1 => Theme::Light,
             _ => Theme::System,
@@ -82,6 +147,84 @@ pub fn create_settings_page(sender: &ComponentSender<AppModel>, hide_logs_switch
         sender_clone.input(AppMsg::ThemeSelected(theme));
     });
 
+    transparent_switch.set_title("Transparent Background");
+    transparent_switch.set_subtitle("Make the window translucent, works with any theme");
+    transparent_switch.set_hexpand(true);
+    transparent_switch.set_halign(gtk::Align::Fill);
+
+    let sender_clone = sender.clone();
+    transparent_switch.connect_active_notify(move |switch| {
+        sender_clone.input(AppMsg::ToggleTransparent(switch.is_active()));
+    });
+
+    // Java distribution used for automatic runtime downloads
+    let java_vendor_row = adw::ComboRow::builder()
+        .title("Java Distribution")
+        .subtitle("Vendor used when downloading a missing Java runtime")
+        .hexpand(true)
+        .halign(gtk::Align::Fill)
+        .build();
+
+    let java_vendor_model = gtk::StringList::new(&["Temurin", "GraalVM", "Zulu"]);
+    java_vendor_row.set_model(Some(&java_vendor_model));
+
+    let sender_clone = sender.clone();
+    java_vendor_row.connect_notify(Some("selected"), move |combo, _| {
+        let vendor = match combo.selected() {
+            2 => JavaVendor::Zulu,
+            1 => JavaVendor::GraalVM,
+            _ => JavaVendor::Temurin,
+        };
+        sender_clone.input(AppMsg::JavaVendorSelected(vendor));
+    });
+
+    // Verbosity of the launcher's own log
+    log_level_row.set_title("Launcher Log Level");
+    log_level_row.set_subtitle("How much RCraft itself records, shown in the Launcher tab of the logs");
+    log_level_row.set_hexpand(true);
+    log_level_row.set_halign(gtk::Align::Fill);
+    log_level_row.set_model(Some(&gtk::StringList::new(&["Error", "Warning", "Info", "Debug", "Trace"])));
+    log_level_row.set_selected(2);
+
+    let sender_clone = sender.clone();
+    log_level_row.connect_notify(Some("selected"), move |combo, _| {
+        let level = match combo.selected() {
+            0 => LogLevel::Error,
+            1 => LogLevel::Warn,
+            3 => LogLevel::Debug,
+            4 => LogLevel::Trace,
+            _ => LogLevel::Info,
+        };
+        sender_clone.input(AppMsg::LogLevelSelected(level));
+    });
+
+    // Re-hash game files on every launch
+    let verify_files_row = adw::SwitchRow::builder()
+        .title("Verify File Integrity")
+        .subtitle("Check game files against their checksums before launching. Turning this off launches faster, new downloads are still checked")
+        .active(true)
+        .hexpand(true)
+        .halign(gtk::Align::Fill)
+        .build();
+
+    let sender_clone = sender.clone();
+    verify_files_row.connect_active_notify(move |switch| {
+        sender_clone.input(AppMsg::ToggleVerifyFiles(switch.is_active()));
+    });
+
+    // Pre-launch check for GL/X11 libraries, advisory only
+    let check_libraries_row = adw::SwitchRow::builder()
+        .title("Check System Libraries")
+        .subtitle("Warn before launching when OpenGL or X11 libraries are missing")
+        .hexpand(true)
+        .halign(gtk::Align::Fill)
+        .build();
+
+    let sender_clone = sender.clone();
+    check_libraries_row.connect_active_notify(move |switch| {
+        sender_clone.input(AppMsg::ToggleCheckSystemLibraries(switch.is_active()));
+    });
+
     // Open Minecraft folder button
     let folder_row = adw::ActionRow::builder()
         .title("Open Minecraft Folder")
@@ -104,15 +247,348 @@ pub fn create_settings_page(sender: &ComponentSender<AppModel>, hide_logs_switch
 
     folder_row.set_activatable(false);
 
+    // Where versions, assets, libraries and runtimes are kept instead of ~/.minecraft
+    let data_dir_row = adw::ActionRow::builder()
+        .title("Minecraft Folder Location")
+        .use_markup(false)
+        .hexpand(true)
+        .halign(gtk::Align::Fill)
+        .build();
+
+    let data_dir_reset_button = gtk::Button::builder()
+        .icon_name("edit-undo-symbolic")
+        .tooltip_text("Use Default")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    data_dir_reset_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::DataDirSelected(None));
+    });
+
+    let data_dir_button = gtk::Button::builder()
+        .label("Change")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    data_dir_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::ChooseDataDir);
+    });
+
+    data_dir_row.add_suffix(&data_dir_reset_button);
+    data_dir_row.add_suffix(&data_dir_button);
+    data_dir_row.set_activatable(false);
+
+    // Where per-profile instances live, apart from the shared libraries/assets cache
+    let instances_row = adw::ActionRow::builder()
+        .title("Instances Folder")
+        .use_markup(false)
+        .hexpand(true)
+        .halign(gtk::Align::Fill)
+        .build();
+
+    let instances_reset_button = gtk::Button::builder()
+        .icon_name("edit-undo-symbolic")
+        .tooltip_text("Use Default")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    instances_reset_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::InstancesDirSelected(None));
+    });
+
+    let instances_button = gtk::Button::builder()
+        .label("Change")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    instances_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::ChooseInstancesDir);
+    });
+
+    instances_row.add_suffix(&instances_reset_button);
+    instances_row.add_suffix(&instances_button);
+    instances_row.set_activatable(false);
+
+    default_username_row.set_title("Default Username");
+    default_username_row.set_hexpand(true);
+    let sender_clone = sender.clone();
+    default_username_row.connect_changed(move |entry| {
+        sender_clone.input(AppMsg::DefaultUsernameChanged(entry.text().to_string()));
+    });
+
+    let total_ram = crate::utils::get_total_memory_mb();
+    default_ram_row.set_title("Default Memory (MB)");
+    default_ram_row.set_subtitle("What new profiles start with");
+    default_ram_row.set_adjustment(Some(&gtk::Adjustment::new(crate::settings::DEFAULT_RAM_MB as f64, crate::settings::MIN_RAM_MB as f64, total_ram.max(crate::settings::MIN_RAM_MB as u64) as f64, 256.0, 1024.0, 0.0)));
+
+    let sender_clone = sender.clone();
+    default_ram_row.connect_value_notify(move |row| {
+        sender_clone.input(AppMsg::DefaultRamChanged(row.value() as u32));
+    });
+
+    ram_percent_row.set_title("Share of System Memory (%)");
+    ram_percent_row.set_subtitle(&format!("Give new profiles this much of the {} MB installed instead, 0 uses the default above", total_ram));
+    ram_percent_row.set_adjustment(Some(&gtk::Adjustment::new(0.0, 0.0, 90.0, 5.0, 10.0, 0.0)));
+
+    let sender_clone = sender.clone();
+    ram_percent_row.connect_value_notify(move |row| {
+        let percent = row.value() as u32;
+        sender_clone.input(AppMsg::RamPercentChanged((percent > 0).then_some(percent)));
+    });
+
     // Add rows to list box
+    settings_list.append(default_username_row);
+    settings_list.append(default_ram_row);
+    settings_list.append(ram_percent_row);
     settings_list.append(&theme_row);
+    settings_list.append(transparent_switch);
+    settings_list.append(&java_vendor_row);
+    settings_list.append(&verify_files_row);
+    settings_list.append(log_level_row);
+    settings_list.append(&check_libraries_row);
     settings_list.append(&folder_row);
+    settings_list.append(&data_dir_row);
+    settings_list.append(&instances_row);
     settings_list.append(hide_logs_switch);
+    settings_list.append(colorize_logs_switch);
+    settings_list.append(log_timestamps_switch);
+    settings_list.append(max_log_lines_row);
     settings_list.append(hide_mods_switch);
+    settings_list.append(stable_mods_switch);
+    settings_list.append(minimize_on_launch_switch);
+    settings_list.append(quit_on_launch_switch);
 
     // Add list box to main content
     content_container.append(&settings_list);
 
+    // Microsoft accounts profiles can play as, filled by update_accounts_list
+    let accounts_header = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    let accounts_label = gtk::Label::builder()
+        .label("Accounts")
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .css_classes(vec!["heading".to_string()])
+        .build();
+
+    let add_account_button = gtk::Button::builder()
+        .label("Add Account")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    add_account_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::AddAccount);
+    });
+
+    accounts_header.append(&accounts_label);
+    accounts_header.append(&add_account_button);
+
+    accounts_list.add_css_class("boxed-list");
+    accounts_list.set_selection_mode(gtk::SelectionMode::None);
+    accounts_list.set_hexpand(true);
+    accounts_list.set_halign(gtk::Align::Fill);
+
+    content_container.append(&accounts_header);
+    content_container.append(accounts_list);
+
+    // Advanced: network retry behaviour shared by game and mod downloads
+    let advanced_label = gtk::Label::builder()
+        .label("Advanced")
+        .halign(gtk::Align::Start)
+        .css_classes(vec!["heading".to_string()])
+        .build();
+
+    let advanced_list = gtk::ListBox::new();
+    advanced_list.add_css_class("boxed-list");
+    advanced_list.set_selection_mode(gtk::SelectionMode::None);
+    advanced_list.set_hexpand(true);
+    advanced_list.set_halign(gtk::Align::Fill);
+
+    max_retries_row.set_title("Download Retries");
+    max_retries_row.set_subtitle("Extra attempts for a failed download or Modrinth request. 0 fails right away");
+    max_retries_row.set_adjustment(Some(&gtk::Adjustment::new(crate::http::DEFAULT_MAX_RETRIES as f64, 0.0, 10.0, 1.0, 1.0, 0.0)));
+
+    let sender_clone = sender.clone();
+    max_retries_row.connect_value_notify(move |row| {
+        sender_clone.input(AppMsg::MaxRetriesChanged(row.value() as u32));
+    });
+
+    max_backoff_row.set_title("Maximum Retry Delay");
+    max_backoff_row.set_subtitle("Longest wait between attempts, in seconds. The wait doubles after every failure");
+    max_backoff_row.set_adjustment(Some(&gtk::Adjustment::new(crate::http::DEFAULT_MAX_BACKOFF_SECS as f64, 1.0, 120.0, 1.0, 10.0, 0.0)));
+
+    let sender_clone = sender.clone();
+    max_backoff_row.connect_value_notify(move |row| {
+        sender_clone.input(AppMsg::MaxBackoffChanged(row.value() as u64));
+    });
+
+    download_concurrency_row.set_title("Parallel Downloads");
+    download_concurrency_row.set_subtitle("Libraries and assets fetched at the same time when setting up a version");
+    download_concurrency_row.set_adjustment(Some(&gtk::Adjustment::new(crate::http::DEFAULT_DOWNLOAD_CONCURRENCY as f64, 1.0, 32.0, 1.0, 4.0, 0.0)));
+
+    let sender_clone = sender.clone();
+    download_concurrency_row.connect_value_notify(move |row| {
+        sender_clone.input(AppMsg::DownloadConcurrencyChanged(row.value() as usize));
+    });
+
+    // Seed options.txt of new instances
+    options_template_row.set_title("Options Template");
+    options_template_row.set_use_markup(false);
+    options_template_row.set_hexpand(true);
+    options_template_row.set_halign(gtk::Align::Fill);
+
+    let options_template_reset_button = gtk::Button::builder()
+        .icon_name("edit-clear-symbolic")
+        .tooltip_text("Don't Use a Template")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    options_template_reset_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::OptionsTemplateSelected(None));
+    });
+
+    let options_template_button = gtk::Button::builder()
+        .label("Choose")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    options_template_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::ChooseOptionsTemplate);
+    });
+
+    options_template_row.add_suffix(&options_template_reset_button);
+    options_template_row.add_suffix(&options_template_button);
+    options_template_row.set_activatable(false);
+
+    // Icons and Modrinth responses, see config::cache_dir
+    let clear_caches_row = adw::ActionRow::builder()
+        .title("Cached Files")
+        .subtitle("Mod icons, gallery images and saved Modrinth results, kept for a month. Downloaded again when needed")
+        .build();
+
+    let clear_caches_button = gtk::Button::builder()
+        .label("Clear")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    clear_caches_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::ClearCaches);
+    });
+
+    clear_caches_row.add_suffix(&clear_caches_button);
+    clear_caches_row.set_activatable(false);
+
+    // Jars left in libraries/ by versions that have since been removed or updated
+    let unused_libraries_row = adw::ActionRow::builder()
+        .title("Unused Libraries")
+        .subtitle("Libraries no installed version needs anymore. Asks before removing anything")
+        .build();
+
+    let unused_libraries_button = gtk::Button::builder()
+        .label("Find")
+        .valign(gtk::Align::Center)
+        .build();
+
+    let sender_clone = sender.clone();
+    unused_libraries_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::FindUnusedLibraries);
+    });
+
+    unused_libraries_row.add_suffix(&unused_libraries_button);
+    unused_libraries_row.set_activatable(false);
+
+    advanced_list.append(max_retries_row);
+    advanced_list.append(max_backoff_row);
+    advanced_list.append(download_concurrency_row);
+    advanced_list.append(options_template_row);
+    advanced_list.append(&clear_caches_row);
+    advanced_list.append(&unused_libraries_row);
+
+    content_container.append(&advanced_label);
+    content_container.append(&advanced_list);
+
+    // What's under versions/, with the profiles that need each one
+    let versions_label = gtk::Label::builder()
+        .label("Installed Versions")
+        .halign(gtk::Align::Start)
+        .css_classes(vec!["heading".to_string()])
+        .build();
+
+    installed_versions_list.add_css_class("boxed-list");
+    installed_versions_list.set_selection_mode(gtk::SelectionMode::None);
+    installed_versions_list.set_hexpand(true);
+    installed_versions_list.set_halign(gtk::Align::Fill);
+
+    content_container.append(&versions_label);
+    content_container.append(installed_versions_list);
+
+    // Java runtimes RCraft manages, with an update check against their vendor
+    let java_header = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+    let java_label = gtk::Label::builder()
+        .label("Java Runtimes")
+        .halign(gtk::Align::Start)
+        .hexpand(true)
+        .css_classes(vec!["heading".to_string()])
+        .build();
+
+    let java_check_button = gtk::Button::builder()
+        .label("Check for Updates")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .build();
+
+    let sender_clone = sender.clone();
+    java_check_button.connect_clicked(move |_| {
+        sender_clone.input(AppMsg::CheckJavaUpdates);
+    });
+
+    // Lets an offline machine be prepared before its first launch
+    let java_install_box = gtk::Box::new(gtk::Orientation::Vertical, 0);
+    let java_install_popover = gtk::Popover::builder().child(&java_install_box).build();
+    for version in INSTALLABLE_JAVA_VERSIONS {
+        let button = gtk::Button::builder()
+            .label(format!("Java {}", version))
+            .css_classes(vec!["flat".to_string()])
+            .build();
+        let sender_clone = sender.clone();
+        let popover = java_install_popover.clone();
+        button.connect_clicked(move |_| {
+            popover.popdown();
+            sender_clone.input(AppMsg::DownloadJava(version));
+        });
+        java_install_box.append(&button);
+    }
+
+    let java_install_button = gtk::MenuButton::builder()
+        .label("Install Java")
+        .valign(gtk::Align::Center)
+        .css_classes(vec!["flat".to_string()])
+        .popover(&java_install_popover)
+        .build();
+
+    java_header.append(&java_label);
+    java_header.append(&java_install_button);
+    java_header.append(&java_check_button);
+
+    java_runtimes_list.add_css_class("boxed-list");
+    java_runtimes_list.set_selection_mode(gtk::SelectionMode::None);
+    java_runtimes_list.set_hexpand(true);
+    java_runtimes_list.set_halign(gtk::Align::Fill);
+
+    content_container.append(&java_header);
+    content_container.append(java_runtimes_list);
+
     // About Section
     let about_list = gtk::ListBox::new();
     about_list.add_css_class("boxed-list");
@@ -146,5 +622,198 @@ pub fn create_settings_page(sender: &ComponentSender<AppModel>, hide_logs_switch
     content_container.append(&about_list);
 
     scrolled_window.set_child(Some(&content_container));
-    (scrolled_window, theme_row)
+    (scrolled_window, theme_row, java_vendor_row, verify_files_row, data_dir_row, instances_row, check_libraries_row)
+}
+
+pub fn update_accounts_list(list: &gtk::ListBox, accounts: &[Account], signing_in: bool, sender: &ComponentSender<AppModel>) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    for account in accounts {
+        let row = adw::ActionRow::builder()
+            .title(&account.username)
+            .subtitle("Microsoft account")
+            .use_markup(false)
+            .build();
+
+        let remove_button = gtk::Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text("Remove Account")
+            .valign(gtk::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+
+        let sender_clone = sender.clone();
+        let uuid = account.uuid.clone();
+        remove_button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::RemoveAccount(uuid.clone()));
+        });
+        row.add_suffix(&remove_button);
+
+        list.append(&row);
+    }
+
+    if signing_in {
+        let row = adw::ActionRow::builder()
+            .title("Signing in...")
+            .subtitle("Enter the code from the sign in dialog on the Microsoft page")
+            .build();
+
+        let cancel_button = gtk::Button::builder()
+            .label("Cancel")
+            .valign(gtk::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+
+        let sender_clone = sender.clone();
+        cancel_button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::CancelSignIn);
+        });
+        row.add_suffix(&cancel_button);
+
+        list.append(&row);
+    } else if accounts.is_empty() {
+        let empty_row = adw::ActionRow::builder()
+            .title("No accounts")
+            .subtitle("Profiles play offline. Add a Microsoft account to join online servers")
+            .build();
+        list.append(&empty_row);
+    }
+}
+
+pub fn update_java_runtimes_list(list: &gtk::ListBox, runtimes: &[RuntimeStatus], checking: bool, sender: &ComponentSender<AppModel>) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    if runtimes.is_empty() {
+        let empty_row = adw::ActionRow::builder()
+            .title("No runtimes downloaded yet")
+            .subtitle("RCraft downloads the Java a version needs the first time it's launched, or use Install Java")
+            .build();
+        list.append(&empty_row);
+        return;
+    }
+
+    for runtime in runtimes {
+        let installed = runtime.installed_build.as_deref().unwrap_or("unknown build");
+        let subtitle = match (&runtime.latest_build, checking) {
+            (_, true) => format!("{} \u{2022} Checking for updates...", installed),
+            (Some(latest), false) if runtime.update_available() => format!("{} \u{2022} {} available", installed, latest),
+            (Some(_), false) => format!("{} \u{2022} Up to date", installed),
+            (None, false) => installed.to_string(),
+        };
+
+        let row = adw::ActionRow::builder()
+            .title(format!("{} Java {}", runtime.vendor, runtime.version))
+            .subtitle(subtitle)
+            .use_markup(false)
+            .build();
+
+        if runtime.update_available() && !checking {
+            let update_button = gtk::Button::builder()
+                .label("Update")
+                .valign(gtk::Align::Center)
+                .css_classes(vec!["suggested-action".to_string()])
+                .build();
+
+            let sender_clone = sender.clone();
+            let (version, vendor) = (runtime.version, runtime.vendor);
+            update_button.connect_clicked(move |_| {
+                sender_clone.input(AppMsg::UpdateJavaRuntime(version, vendor));
+            });
+            row.add_suffix(&update_button);
+        }
+
+        list.append(&row);
+    }
+}
+
+// Profiles that launch `version`: every profile on a vanilla version (loader installs run on its
+// jar too), or those with the matching loader for a loader install
+pub fn profiles_using<'a>(version: &InstalledVersion, profiles: &'a HashMap<String, Profile>) -> Vec<&'a Profile> {
+    let mut users: Vec<&Profile> = profiles.values()
+        .filter(|p| p.version == version.game_version())
+        .filter(|p| version.loader_name().is_none() || p.loader_name() == version.loader_name())
+        .collect();
+    users.sort_by(|a, b| a.username.cmp(&b.username));
+    users
+}
+
+pub fn update_installed_versions_list(list: &gtk::ListBox, versions: &[InstalledVersion], profiles: &HashMap<String, Profile>, sender: &ComponentSender<AppModel>) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+
+    if versions.is_empty() {
+        let empty_row = adw::ActionRow::builder()
+            .title("No versions downloaded yet")
+            .subtitle("A version is downloaded the first time a profile using it is launched")
+            .build();
+        list.append(&empty_row);
+        return;
+    }
+
+    for version in versions {
+        let kind = match version.loader_name() {
+            Some(loader) => format!("{} for {}", loader, version.game_version()),
+            None => "Vanilla".to_string(),
+        };
+        let users: Vec<&str> = profiles_using(version, profiles).iter().map(|p| p.username.as_str()).collect();
+        let used_by = if users.is_empty() { "Not used by any profile".to_string() } else { format!("Used by {}", users.join(", ")) };
+
+        let row = adw::ActionRow::builder()
+            .title(&version.id)
+            .subtitle(format!("{} \u{2022} {} \u{2022} {}", kind, crate::utils::format_size(version.size), used_by))
+            .use_markup(false)
+            .build();
+
+        let verify_button = gtk::Button::builder()
+            .icon_name("emblem-ok-symbolic")
+            .tooltip_text("Verify Files")
+            .valign(gtk::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+
+        let sender_clone = sender.clone();
+        let id = version.id.clone();
+        verify_button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::VerifyVersion(id.clone(), false));
+        });
+        row.add_suffix(&verify_button);
+
+        // Loader installs are set up again by the next launch instead
+        if version.loader_name().is_none() {
+            let reinstall_button = gtk::Button::builder()
+                .icon_name("view-refresh-symbolic")
+                .tooltip_text("Reinstall")
+                .valign(gtk::Align::Center)
+                .css_classes(vec!["flat".to_string()])
+                .build();
+
+            let sender_clone = sender.clone();
+            let id = version.id.clone();
+            reinstall_button.connect_clicked(move |_| {
+                sender_clone.input(AppMsg::VerifyVersion(id.clone(), true));
+            });
+            row.add_suffix(&reinstall_button);
+        }
+
+        let delete_button = gtk::Button::builder()
+            .icon_name("user-trash-symbolic")
+            .tooltip_text("Delete")
+            .valign(gtk::Align::Center)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+
+        let sender_clone = sender.clone();
+        let id = version.id.clone();
+        delete_button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::RequestDeleteVersion(id.clone()));
+        });
+        row.add_suffix(&delete_button);
+
+        list.append(&row);
+    }
 }
@@ -122,3 +122,50 @@ pub fn create_sidebar(sender: &ComponentSender<AppModel>) -> (NavigationPage, gt
 
     (sidebar_page, home_button, create_button, mods_button, settings_button, logs_button, home_label, create_label, mods_label, settings_label, logs_label, home_box, create_box, mods_box, settings_box, logs_box)
 }
+
+// Navigation for narrow windows, shown instead of the sidebar below the mini mode breakpoint
+pub fn create_bottom_bar(sender: &ComponentSender<AppModel>) -> (gtk::Box, Vec<(Section, gtk::Button)>) {
+    let bar = gtk::Box::builder()
+        .orientation(gtk::Orientation::Horizontal)
+        .homogeneous(true)
+        .css_classes(vec!["toolbar".to_string()])
+        .visible(false)
+        .build();
+
+    let sections = [
+        (Section::Home, "Home", "user-home-symbolic"),
+        (Section::CreateInstance, "New", "list-add-symbolic"),
+        (Section::Mods, "Mods", "application-x-addon-symbolic"),
+        (Section::Logs, "Logs", "utilities-terminal-symbolic"),
+        (Section::Settings, "Settings", "emblem-system-symbolic"),
+    ];
+
+    let mut buttons = Vec::new();
+    for (section, label_text, icon_name) in sections {
+        let content = gtk::Box::builder()
+            .orientation(gtk::Orientation::Vertical)
+            .spacing(2)
+            .build();
+        content.append(&gtk::Image::from_icon_name(icon_name));
+        content.append(&gtk::Label::builder()
+            .label(label_text)
+            .css_classes(vec!["caption".to_string()])
+            .build());
+
+        let button = gtk::Button::builder()
+            .child(&content)
+            .tooltip_text(label_text)
+            .css_classes(vec!["flat".to_string()])
+            .build();
+
+        let sender_clone = sender.clone();
+        button.connect_clicked(move |_| {
+            sender_clone.input(AppMsg::NavigateToSection(section));
+        });
+
+        bar.append(&button);
+        buttons.push((section, button));
+    }
+
+    (bar, buttons)
+}
@@ -1,6 +1,7 @@
 
 use adw::{self, NavigationSplitView, NavigationPage};
 use relm4::gtk;
+use crate::models::Section;
 
 #[allow(dead_code)]
 pub struct AppWidgets {
@@ -20,17 +21,40 @@ pub struct AppWidgets {
 
     // Home page widgets
     pub profile_list: gtk::ListBox,
+    pub profile_sort_dropdown: gtk::DropDown,
     pub username_entry: adw::EntryRow,
+    pub notes_view: gtk::TextView,
     pub version_combo: adw::ComboRow,
+    pub favorite_version_button: gtk::ToggleButton,
     pub ram_scale: adw::SpinRow,
-    pub fabric_switch: adw::SwitchRow,
+    pub loader_combo: adw::ComboRow,
+    pub jvm_args_entry: adw::EntryRow,
+    pub account_combo: adw::ComboRow,
+    pub game_dir_row: adw::ActionRow,
+    pub shared_mods_row: adw::ActionRow,
+    pub transparent_switch: adw::SwitchRow,
     pub hide_logs_switch: adw::SwitchRow,
+    pub colorize_logs_switch: adw::SwitchRow,
+    pub stable_mods_switch: adw::SwitchRow,
+    pub log_timestamps_switch: adw::SwitchRow,
+    pub max_retries_row: adw::SpinRow,
+    pub max_backoff_row: adw::SpinRow,
+    pub download_concurrency_row: adw::SpinRow,
+    pub max_log_lines_row: adw::SpinRow,
+    pub default_ram_row: adw::SpinRow,
+    pub ram_percent_row: adw::SpinRow,
+    pub options_template_row: adw::ActionRow,
+    pub default_username_row: adw::EntryRow,
     pub hide_mods_switch: adw::SwitchRow,
+    pub minimize_on_launch_switch: adw::SwitchRow,
+    pub quit_on_launch_switch: adw::SwitchRow,
+    pub log_level_combo: adw::ComboRow,
 
     // Buttons
     pub launch_button: gtk::Button,
     pub create_button: gtk::Button,
     pub delete_button: gtk::Button,
+    pub create_title_label: gtk::Label,
     pub save_button: gtk::Button,
     pub cancel_button: gtk::Button,
 
@@ -44,6 +68,7 @@ pub struct AppWidgets {
     // Mods widgets
     pub mod_profile_dropdown: gtk::DropDown,
     pub mod_search_stack: gtk::Stack,
+    pub mod_stale_banner: adw::Banner,
 
     // Sidebar button labels (for visibility)
     pub home_label: gtk::Label,
@@ -61,18 +86,32 @@ pub struct AppWidgets {
 
     // Sidebar Toggle
     pub sidebar_toggle_button: gtk::Button,
+    // Collapsed state last applied to the sidebar, None until settings are loaded
+    pub sidebar_applied: Option<bool>,
+    pub bottom_nav_buttons: Vec<(Section, gtk::Button)>,
+    pub sidebar_animation: Option<adw::TimedAnimation>,
 
     // Settings widgets
     pub theme_combo: adw::ComboRow,
+    pub java_vendor_combo: adw::ComboRow,
+    pub verify_files_switch: adw::SwitchRow,
+    pub data_dir_row: adw::ActionRow,
+    pub instances_dir_row: adw::ActionRow,
+    pub check_libraries_switch: adw::SwitchRow,
 
     // Status/error labels
     pub status_label: gtk::Label,
     pub error_label: gtk::Label,
+    pub retry_button: gtk::Button,
 
     // Loading widgets
     pub loading_spinner: gtk::Spinner,
     pub loading_progress: gtk::ProgressBar,
     pub loading_label: gtk::Label,
+    pub loading_indicator_stack: gtk::Stack,
+    pub loading_elapsed_label: gtk::Label,
+    pub loading_stall_label: gtk::Label,
+    pub loading_cancel_button: gtk::Button,
 
     // Toast Overlay
     pub toast_overlay: adw::ToastOverlay,
@@ -82,4 +121,6 @@ pub struct AppWidgets {
 
     // Logs view
     pub logs_view: gtk::TextView,
+    pub copy_logs_button: gtk::Button,
+    pub save_logs_button: gtk::Button,
 }
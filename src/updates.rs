@@ -0,0 +1,32 @@
+use anyhow::Result;
+
+use crate::utils::compare_versions;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/vdkvdev/rcraft/releases/latest";
+pub const RELEASES_PAGE_URL: &str = "https://github.com/vdkvdev/rcraft/releases/latest";
+
+#[derive(serde::Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+// Returns the newer release tag if there is one, None when this build is up to date
+pub async fn check_for_update() -> Result<Option<String>> {
+    let client = reqwest::Client::builder()
+        .user_agent(concat!("rcraft/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+
+    let release: Release = client.get(LATEST_RELEASE_URL)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let latest = release.tag_name.trim_start_matches('v');
+    if compare_versions(latest, env!("CARGO_PKG_VERSION")).is_gt() {
+        Ok(Some(latest.to_string()))
+    } else {
+        Ok(None)
+    }
+}
@@ -3,22 +3,61 @@ use std::cmp::Ordering;
 
 use crate::models::Library;
 
+// Numeric part of a release id, "1.20.1-rc1" -> (1, 20, 1). Missing or non-numeric parts are 0.
 pub fn parse_version(s: &str) -> (i32, i32, i32) {
-    let parts: Vec<&str> = s.split('.').collect();
-    (
-        parts.get(0).unwrap_or(&"0").parse().unwrap_or(0),
-        parts.get(1).map_or(0, |x| x.parse().unwrap_or(0)),
-        parts.get(2).map_or(0, |x| x.parse().unwrap_or(0)),
-    )
+    let parts: Vec<&str> = split_suffix(s).0.split('.').collect();
+    let part = |i: usize| parts.get(i).map_or(0, |x| x.parse().unwrap_or(0));
+    (part(0), part(1), part(2))
 }
 
-pub fn compare_versions(a: &str, b: &str) -> Ordering {
-    let pa = parse_version(a);
-    let pb = parse_version(b);
-    (pa.0, pa.1, pa.2).cmp(&(pb.0, pb.1, pb.2))
+// "1.20-pre1" -> ("1.20", Some("pre1")). Old pre-releases use a space: "1.14 Pre-Release 1"
+fn split_suffix(s: &str) -> (&str, Option<&str>) {
+    match s.find(['-', ' ']) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    }
 }
 
+// Weekly snapshot ids, "23w31a" -> (23, 31, "a")
+fn parse_snapshot(s: &str) -> Option<(u32, u32, &str)> {
+    let (year, rest) = s.split_once('w')?;
+    let week = rest.get(..2)?;
+    let digits = |x: &str| x.len() == 2 && x.chars().all(|c| c.is_ascii_digit());
+    if !digits(year) || !digits(week) {
+        return None;
+    }
+    Some((year.parse().ok()?, week.parse().ok()?, &rest[2..]))
+}
 
+// Orders what leads up to a release: snapshots, pre-releases, release candidates, then the
+// release itself. Unknown suffixes go first. The number is the suffix's trailing digits.
+fn suffix_rank(suffix: Option<&str>) -> (u8, u32) {
+    let Some(suffix) = suffix else { return (4, 0) };
+    let suffix = suffix.to_ascii_lowercase();
+    let number = suffix.rsplit(|c: char| !c.is_ascii_digit()).next().and_then(|n| n.parse().ok()).unwrap_or(0);
+    let stage = if suffix.starts_with("rc") {
+        3
+    } else if suffix.starts_with("pre") {
+        2
+    } else if suffix.starts_with("snapshot") {
+        1
+    } else {
+        0
+    };
+    (stage, number)
+}
+
+// Release ids by number, then pre-release stage. Weekly snapshots are ordered by their week
+// code; the id doesn't say which release a snapshot leads up to, so they sort above releases.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (parse_snapshot(a), parse_snapshot(b)) {
+        (Some(sa), Some(sb)) => sa.cmp(&sb),
+        (Some(_), None) => Ordering::Greater,
+        (None, Some(_)) => Ordering::Less,
+        (None, None) => parse_version(a).cmp(&parse_version(b))
+            .then_with(|| suffix_rank(split_suffix(a).1).cmp(&suffix_rank(split_suffix(b).1))),
+    }
+}
 
 pub fn is_at_least_1_14(v: &str) -> bool {
     let p = parse_version(v);
@@ -53,6 +92,25 @@ pub fn get_os_name() -> &'static str {
     "linux"
 }
 
+// CPU architecture as the Java download APIs name it
+pub fn get_arch() -> &'static str {
+    match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        other => other, // aarch64, x86, arm...
+    }
+}
+
+// Suffix of the natives classifiers built for this CPU (natives-linux-arm64). None on x86_64,
+// whose natives are the plain natives-linux ones.
+pub fn natives_arch_suffix() -> Option<&'static str> {
+    match std::env::consts::ARCH {
+        "aarch64" => Some("arm64"),
+        "arm" => Some("arm32"),
+        "x86" => Some("x86"),
+        _ => None,
+    }
+}
+
 pub fn get_total_memory_mb() -> u64 {
     if let Ok(meminfo) = std::fs::read_to_string("/proc/meminfo") {
         for line in meminfo.lines() {
@@ -69,3 +127,280 @@ pub fn get_total_memory_mb() -> u64 {
     // Fallback if reading fails
     8192
 }
+
+pub fn sha1_hex(bytes: &[u8]) -> String {
+    use sha1::{Digest, Sha1};
+    Sha1::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// Streams the file through the hasher so big jars don't have to fit in memory
+pub fn sha1_file(path: &std::path::Path) -> std::io::Result<String> {
+    use sha1::{Digest, Sha1};
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha1::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// Folder name for user-supplied text such as a modpack's name. Anything outside letters, digits,
+// '-' and '_' becomes '_', and long names are cut and given a hash of the full name so they stay
+// well under the 255 byte limit filesystems put on a single name, and distinct from each other.
+// An empty name gets "instance", never the parent folder itself.
+pub fn dir_name_for(name: &str) -> String {
+    const MAX_BYTES: usize = 64;
+    if name.is_empty() {
+        return "instance".to_string();
+    }
+    let sanitized: String = name.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    if sanitized.len() <= MAX_BYTES {
+        return sanitized;
+    }
+
+    let hash = &sha1_hex(name.as_bytes())[..8];
+    let mut cut = MAX_BYTES - hash.len() - 1;
+    while !sanitized.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    format!("{}-{}", &sanitized[..cut], hash)
+}
+
+// Creates the dir if needed and proves we can write into it
+pub fn check_dir_writable(dir: &std::path::Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(".rcraft-write-test");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+// Splits a line on ANSI escape sequences into runs of text, each with the foreground color
+// (0-7, bright variants folded in) active at that point. Escapes other than colors are dropped.
+pub fn ansi_segments(line: &str) -> Vec<(String, Option<u8>)> {
+    let mut segments = Vec::new();
+    let mut color = None;
+    let mut text = String::new();
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            text.push(c);
+            continue;
+        }
+        if chars.peek() != Some(&'[') {
+            // Two-character escape, nothing we can show
+            chars.next();
+            continue;
+        }
+        chars.next();
+        let mut params = String::new();
+        let mut command = None;
+        for c in chars.by_ref() {
+            if ('\x40'..='\x7e').contains(&c) {
+                command = Some(c);
+                break;
+            }
+            params.push(c);
+        }
+        if command != Some('m') {
+            continue;
+        }
+        if !text.is_empty() {
+            segments.push((std::mem::take(&mut text), color));
+        }
+        for param in params.split(';') {
+            match param.parse::<u8>().unwrap_or(0) {
+                0 | 39 => color = None,
+                n @ 30..=37 => color = Some(n - 30),
+                n @ 90..=97 => color = Some(n - 90),
+                _ => {}
+            }
+        }
+    }
+    if !text.is_empty() {
+        segments.push((text, color));
+    }
+    segments
+}
+
+pub fn strip_ansi(line: &str) -> String {
+    ansi_segments(line).into_iter().map(|(text, _)| text).collect()
+}
+
+// Total size of the files under `path`. Symlinks are not followed, so links into the shared
+// store (e.g. an old version's resources/) don't count towards the instance.
+pub fn dir_size(path: &std::path::Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else { return 0 };
+    entries.flatten()
+        .filter_map(|entry| Some((entry.path(), entry.path().symlink_metadata().ok()?)))
+        .map(|(path, meta)| if meta.is_dir() { dir_size(&path) } else if meta.is_file() { meta.len() } else { 0 })
+        .sum()
+}
+
+// Copies `from` into `to`, leaving out the top level entries named in `skip`. Symlinks are
+// skipped like in dir_size, the launcher recreates the ones it needs.
+pub fn copy_dir(from: &std::path::Path, to: &std::path::Path, skip: &[&str]) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        if skip.iter().any(|s| entry.file_name() == *s) {
+            continue;
+        }
+        let meta = entry.path().symlink_metadata()?;
+        let target = to.join(entry.file_name());
+        if meta.is_dir() {
+            copy_dir(&entry.path(), &target, &[])?;
+        } else if meta.is_file() {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+pub fn unix_now() -> u64 {
+    std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+// Writes through a uniquely named temp file and renames it into place, so a reader never
+// sees a half-written file and two writers racing on the same path can't interleave
+pub fn write_atomic(path: &std::path::Path, bytes: &[u8]) -> std::io::Result<()> {
+    // What fs::write uses, narrowed by the umask
+    write_atomic_with_mode(path, bytes, 0o666)
+}
+
+// write_atomic for secrets: the file is only readable by the user from the moment it's created,
+// not just after the rename
+pub fn write_atomic_private(path: &std::path::Path, bytes: &[u8]) -> std::io::Result<()> {
+    write_atomic_with_mode(path, bytes, 0o600)
+}
+
+fn write_atomic_with_mode(path: &std::path::Path, bytes: &[u8], mode: u32) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.{}.{}.tmp", file_name, std::process::id(), COUNTER.fetch_add(1, Ordering::Relaxed)));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(target_family = "unix")]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, mode);
+    #[cfg(not(target_family = "unix"))]
+    let _ = mode;
+    let written = options.open(&tmp).and_then(|mut file| file.write_all(bytes));
+    written.and_then(|_| std::fs::rename(&tmp, path)).inspect_err(|_| {
+        let _ = std::fs::remove_file(&tmp);
+    })
+}
+
+pub fn save_png_atomic(img: &image::DynamicImage, path: &std::path::Path) -> std::io::Result<()> {
+    let mut png = std::io::Cursor::new(Vec::new());
+    img.write_to(&mut png, image::ImageOutputFormat::Png)
+        .map_err(std::io::Error::other)?;
+    write_atomic(path, png.get_ref())
+}
+
+// Shared libraries the game can't render without, with a hint on what provides them
+pub const REQUIRED_SYSTEM_LIBRARIES: &[(&str, &str)] = &[
+    ("libGL.so.1", "OpenGL (install Mesa or your GPU driver)"),
+    ("libX11.so.6", "X11 client library (libx11)"),
+    ("libXrandr.so.2", "X11 RandR extension (libxrandr)"),
+];
+
+// Required libraries missing from the dynamic linker cache. Advisory only: if `ldconfig`
+// can't be run we can't tell, so nothing is reported.
+pub fn missing_system_libraries() -> Vec<(&'static str, &'static str)> {
+    let output = ["ldconfig", "/sbin/ldconfig", "/usr/sbin/ldconfig"].iter()
+        .find_map(|bin| std::process::Command::new(bin).arg("-p").output().ok())
+        .filter(|o| o.status.success());
+    let Some(output) = output else { return Vec::new() };
+
+    let cache = String::from_utf8_lossy(&output.stdout);
+    let known: std::collections::HashSet<&str> = cache.lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .collect();
+
+    REQUIRED_SYSTEM_LIBRARIES.iter()
+        .filter(|(lib, _)| !known.contains(lib))
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_short_dir_names_readable() {
+        assert_eq!(dir_name_for("Fabulously Optimized"), "Fabulously_Optimized");
+        assert_eq!(dir_name_for(""), "instance");
+    }
+
+    #[test]
+    fn caps_long_dir_names() {
+        let long = "a".repeat(300);
+        let name = dir_name_for(&long);
+        assert!(name.len() <= 64);
+        assert!(name.starts_with("aaaa"));
+        assert_eq!(name, dir_name_for(&long));
+        // Sharing the cut-off prefix doesn't make two names collide
+        assert_ne!(name, dir_name_for(&format!("{}b", long)));
+
+        let wide = "é".repeat(300);
+        assert!(dir_name_for(&wide).len() <= 64);
+    }
+
+    fn sorted(versions: &[&str]) -> Vec<String> {
+        let mut sorted: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        sorted.sort_by(|a, b| compare_versions(a, b));
+        sorted
+    }
+
+    #[test]
+    fn orders_pre_releases_below_the_release() {
+        let expected = ["1.19.4", "1.20-pre1", "1.20-pre2", "1.20-rc1", "1.20", "1.20.1-rc1", "1.20.1", "23w31a"];
+        assert_eq!(sorted(&["23w31a", "1.20", "1.20.1", "1.20-rc1", "1.20-pre2", "1.19.4", "1.20.1-rc1", "1.20-pre1"]), expected);
+        assert_eq!(sorted(&["1.14", "1.14 Pre-Release 2", "1.14 Pre-Release 1"]), ["1.14 Pre-Release 1", "1.14 Pre-Release 2", "1.14"]);
+        assert_eq!(sorted(&["26.1", "26.1-snapshot-1", "26.1-pre-1"]), ["26.1-snapshot-1", "26.1-pre-1", "26.1"]);
+        assert!(compare_versions("1.20.10", "1.20.9").is_gt());
+    }
+
+    #[test]
+    fn orders_snapshots_by_week() {
+        assert_eq!(sorted(&["24w01a", "23w31b", "23w32a", "23w31a"]), ["23w31a", "23w31b", "23w32a", "24w01a"]);
+    }
+
+    #[test]
+    fn pre_releases_keep_their_version_number() {
+        assert_eq!(parse_version("1.14-pre1"), (1, 14, 0));
+        assert!(is_at_least_1_14("1.14 Pre-Release 3"));
+        assert!(!is_at_least_1_14("1.13.2"));
+    }
+
+    #[cfg(target_family = "unix")]
+    #[test]
+    fn private_files_are_never_readable_by_others() {
+        use std::os::unix::fs::PermissionsExt;
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("accounts.json");
+        std::fs::write(&path, "old").unwrap();
+        write_atomic_private(&path, b"tokens").unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"tokens");
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 1);
+    }
+}
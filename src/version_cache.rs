@@ -0,0 +1,49 @@
+// Parsed version jsons keyed by version id. A launch reads the same json (and its parents) for
+// the Java version, classpath, arguments and natives; this reads and parses each one once.
+// Shared by the launcher and its library manager, cleared at the start of every launch so
+// files changed on disk in between are picked up.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::models::VersionJson;
+
+#[derive(Clone)]
+pub struct VersionJsonCache {
+    versions_dir: PathBuf,
+    entries: Arc<Mutex<HashMap<String, Arc<VersionJson>>>>,
+}
+
+impl VersionJsonCache {
+    pub fn new(versions_dir: PathBuf) -> Self {
+        Self { versions_dir, entries: Arc::default() }
+    }
+
+    pub fn path(&self, version: &str) -> PathBuf {
+        self.versions_dir.join(version).join(format!("{}.json", version))
+    }
+
+    // Errors when the json isn't on disk; failures are not cached
+    pub async fn load(&self, version: &str) -> Result<Arc<VersionJson>> {
+        if let Some(cached) = self.entries.lock().unwrap().get(version) {
+            return Ok(cached.clone());
+        }
+
+        let path = self.path(version);
+        let content = tokio::fs::read_to_string(&path).await
+            .map_err(|e| anyhow!("Could not read {}: {}", path.display(), e))?;
+        let parsed: Arc<VersionJson> = Arc::new(serde_json::from_str(&content)?);
+        self.entries.lock().unwrap().insert(version.to_string(), parsed.clone());
+        Ok(parsed)
+    }
+
+    pub fn invalidate(&self, version: &str) {
+        self.entries.lock().unwrap().remove(version);
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}